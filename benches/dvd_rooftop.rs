@@ -0,0 +1,72 @@
+//! Throughput of the MMS CSV reader on the DVD rooftop PV fixture (30,720 `D` rows).
+//!
+//! Run with `cargo bench --bench dvd_rooftop`. Target: `typed_records` sustains at least one
+//! million rows per second, the rate MMSDM-scale loads need. Rerun this bench after touching
+//! the hot path. Typed rows cost two to three times borrowed ones, mostly in mapping ten
+//! columns by name, converting two datetimes and interning four strings.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use serde::Deserialize;
+use std::fs::File;
+use std::io::Read;
+use zip::ZipArchive;
+
+use au_energy_scraper::common::mms_csv::MmsCsvReader;
+use au_energy_scraper::models::nem_current_rooftop_pv_actual::stream_file_current_rooftop_actual;
+
+const FIXTURE: &str = "src/fixtures/PUBLIC_DVD_ROOFTOP_PV_ACTUAL_201912010000.zip";
+const DATA_ROWS: u64 = 30720;
+
+/// The columns an aggregation needs, borrowed straight from the reader's buffer.
+#[derive(Deserialize)]
+struct RooftopPower<'a> {
+    #[serde(rename = "REGIONID")]
+    region_id: &'a str,
+    #[serde(rename = "POWER")]
+    power: Option<f64>,
+}
+
+fn read_fixture() -> Vec<u8> {
+    let mut archive = ZipArchive::new(File::open(FIXTURE).unwrap()).unwrap();
+    let mut contents = Vec::new();
+    archive
+        .by_index(0)
+        .unwrap()
+        .read_to_end(&mut contents)
+        .unwrap();
+    contents
+}
+
+fn bench_dvd_rooftop(c: &mut Criterion) {
+    let contents = read_fixture();
+    let mut group = c.benchmark_group("dvd_rooftop");
+    group.throughput(Throughput::Elements(DATA_ROWS));
+
+    group.bench_function("typed_records", |b| {
+        b.iter(|| {
+            let mut count = 0;
+            for record in stream_file_current_rooftop_actual(contents.as_slice()) {
+                black_box(record.unwrap());
+                count += 1;
+            }
+            assert_eq!(count, DATA_ROWS);
+        })
+    });
+
+    group.bench_function("borrowed_rows", |b| {
+        b.iter(|| {
+            let mut reader = MmsCsvReader::new(contents.as_slice());
+            let mut total = 0.0;
+            while let Some(row) = reader.next_data_row().unwrap() {
+                let row: RooftopPower = row.deserialize().unwrap();
+                total += black_box(row.region_id).len() as f64 + row.power.unwrap_or_default();
+            }
+            total
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_dvd_rooftop);
+criterion_main!(benches);
@@ -0,0 +1,132 @@
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A row of a forecast report, such as predispatch, that was produced by one run for one
+/// target interval. A report holds many runs per interval, so the run time is needed to ask
+/// what was expected for an interval at a given moment.
+pub trait ForecastRow {
+    /// When the run that produced this row was made.
+    fn run_datetime(&self) -> DateTime<Utc>;
+
+    /// The interval the row is a forecast for.
+    fn interval_datetime(&self) -> DateTime<Utc>;
+
+    /// How far ahead of the target interval the run was made.
+    fn lead_time(&self) -> Duration {
+        self.interval_datetime() - self.run_datetime()
+    }
+}
+
+/// The forecast for `interval` from the latest run made at or before `as_of`, i.e. what the
+/// market expected at `as_of`. Filter `rows` to one region or interconnector first.
+pub fn latest_as_of<'a, F, I>(
+    rows: I,
+    interval: DateTime<Utc>,
+    as_of: DateTime<Utc>,
+) -> Option<&'a F>
+where
+    F: ForecastRow + 'a,
+    I: IntoIterator<Item = &'a F>,
+{
+    rows.into_iter()
+        .filter(|row| row.interval_datetime() == interval && row.run_datetime() <= as_of)
+        .max_by_key(|row| row.run_datetime())
+}
+
+/// Keeps only the latest run's row for each key, e.g. `(region, interval)`, turning a store of
+/// overlapping runs into the current outlook. PASA reports look days to years ahead, so each
+/// interval is covered by many runs. Rows are returned in interval order.
+pub fn latest_by<'a, F, K, I>(rows: I, key: impl Fn(&F) -> K) -> Vec<&'a F>
+where
+    F: ForecastRow + 'a,
+    K: Eq + Hash,
+    I: IntoIterator<Item = &'a F>,
+{
+    let mut latest: HashMap<K, &'a F> = HashMap::new();
+    for row in rows {
+        latest
+            .entry(key(row))
+            .and_modify(|current| {
+                if row.run_datetime() > current.run_datetime() {
+                    *current = row;
+                }
+            })
+            .or_insert(row);
+    }
+    let mut rows: Vec<&'a F> = latest.into_values().collect();
+    rows.sort_by_key(|row| row.interval_datetime());
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    struct Forecast {
+        run: DateTime<Utc>,
+        interval: DateTime<Utc>,
+    }
+
+    impl ForecastRow for Forecast {
+        fn run_datetime(&self) -> DateTime<Utc> {
+            self.run
+        }
+
+        fn interval_datetime(&self) -> DateTime<Utc> {
+            self.interval
+        }
+    }
+
+    #[test]
+    fn test_latest_as_of_ignores_later_runs() {
+        let at = |hour| Utc.with_ymd_and_hms(2024, 3, 3, hour, 0, 0).unwrap();
+        let rows = [
+            Forecast {
+                run: at(1),
+                interval: at(5),
+            },
+            Forecast {
+                run: at(2),
+                interval: at(5),
+            },
+            Forecast {
+                run: at(3),
+                interval: at(5),
+            },
+            Forecast {
+                run: at(2),
+                interval: at(6),
+            },
+        ];
+
+        let expected = latest_as_of(&rows, at(5), at(2)).expect("No forecast");
+        assert_eq!(expected.run, at(2));
+        assert_eq!(expected.lead_time(), Duration::try_hours(3).unwrap());
+        assert!(latest_as_of(&rows, at(5), at(0)).is_none());
+    }
+
+    #[test]
+    fn test_latest_by_keeps_latest_run_per_interval() {
+        let at = |hour| Utc.with_ymd_and_hms(2024, 3, 3, hour, 0, 0).unwrap();
+        let rows = [
+            Forecast {
+                run: at(1),
+                interval: at(6),
+            },
+            Forecast {
+                run: at(2),
+                interval: at(5),
+            },
+            Forecast {
+                run: at(1),
+                interval: at(5),
+            },
+        ];
+
+        let latest = latest_by(&rows, |row| row.interval);
+        let runs: Vec<_> = latest.iter().map(|row| (row.interval, row.run)).collect();
+        assert_eq!(runs, [(at(5), at(2)), (at(6), at(1))]);
+    }
+}
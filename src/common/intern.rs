@@ -0,0 +1,83 @@
+use rustc_hash::FxHashSet;
+use serde::de::{Error, Visitor};
+use serde::Deserializer;
+use std::cell::RefCell;
+use std::fmt;
+use std::sync::Arc;
+
+// Interned values live per thread, so on a long-running runtime the set would otherwise keep
+// every DUID and station ID ever seen on every worker. It is cleared once it grows past what
+// one report needs; values already handed out stay valid, they just stop being shared.
+const INTERN_CACHE_SIZE: usize = 4096;
+
+thread_local! {
+    static INTERNED: RefCell<FxHashSet<Arc<str>>> = RefCell::new(FxHashSet::default());
+}
+
+/// Returns a shared copy of `value`, allocating only the first time a value is seen on this
+/// thread since the cache was last cleared.
+///
+/// Columns such as `ROW_TYPE`, `REGIONID` or `TYPE` repeat a handful of values across every
+/// row of a report, so interning them keeps large files from allocating a `String` per field.
+pub fn intern(value: &str) -> Arc<str> {
+    INTERNED.with(|interned| {
+        let mut interned = interned.borrow_mut();
+        if let Some(existing) = interned.get(value) {
+            return Arc::clone(existing);
+        }
+        if interned.len() >= INTERN_CACHE_SIZE {
+            interned.clear();
+        }
+        let value: Arc<str> = Arc::from(value);
+        interned.insert(Arc::clone(&value));
+        value
+    })
+}
+
+struct InternedVisitor;
+
+impl<'de> Visitor<'de> for InternedVisitor {
+    type Value = Arc<str>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a string")
+    }
+
+    fn visit_str<E: Error>(self, value: &str) -> Result<Self::Value, E> {
+        Ok(intern(value))
+    }
+}
+
+/// Deserializes a low-cardinality text column through [`intern`].
+pub fn deserialize_interned<'de, D>(deserializer: D) -> Result<Arc<str>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_str(InternedVisitor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intern_shares_allocation() {
+        let first = intern("NSW1");
+        let second = intern("NSW1");
+
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(!Arc::ptr_eq(&first, &intern("QLD1")));
+    }
+
+    #[test]
+    fn test_intern_cache_is_bounded() {
+        let first = intern("DUID0");
+        for i in 1..=INTERN_CACHE_SIZE {
+            intern(&format!("DUID{}", i));
+        }
+
+        assert!(INTERNED.with(|interned| interned.borrow().len()) <= INTERN_CACHE_SIZE);
+        assert_eq!(&*first, "DUID0");
+        assert!(!Arc::ptr_eq(&first, &intern("DUID0")));
+    }
+}
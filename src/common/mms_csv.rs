@@ -0,0 +1,836 @@
+use csv::{ByteRecord, DeserializeErrorKind, Reader, ReaderBuilder, StringRecord};
+use serde::de::value::MapAccessDeserializer;
+use serde::de::{DeserializeSeed, Error as _, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::Read;
+use std::marker::PhantomData;
+
+use crate::common::report_header::ReportHeader;
+use crate::error::{Location, ScraperError};
+use crate::time::datetimezone_conversion::INVALID_DATETIME;
+
+// The first four columns of every `I` and `D` row describe the table rather than the data,
+// so they are given fixed names that the models can rename against.
+pub const ROW_TYPE: &str = "ROW_TYPE";
+pub const FILE_TYPE: &str = "FILE_TYPE";
+pub const FILE_SUBTYPE: &str = "FILE_SUBTYPE";
+pub const FILE_DESCRIPTOR: &str = "FILE_DESCRIPTOR";
+
+/// Identifies a table within a report, e.g. `TRADING,PRICE` or `DREGION,` (empty subtype).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TableKey {
+    pub file_type: String,
+    pub file_subtype: String,
+}
+
+impl TableKey {
+    pub fn new(file_type: &str, file_subtype: &str) -> Self {
+        TableKey {
+            file_type: file_type.to_string(),
+            file_subtype: file_subtype.to_string(),
+        }
+    }
+
+    /// Returns true when this key matches the given type and subtype.
+    pub fn is(&self, file_type: &str, file_subtype: &str) -> bool {
+        self.file_type == file_type && self.file_subtype == file_subtype
+    }
+
+    fn from_record(record: &ByteRecord) -> Self {
+        TableKey::new(&field_str(record, 1), &field_str(record, 2))
+    }
+
+    fn matches_record(&self, record: &ByteRecord) -> bool {
+        record.get(1) == Some(self.file_type.as_bytes())
+            && record.get(2) == Some(self.file_subtype.as_bytes())
+    }
+}
+
+impl fmt::Display for TableKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}", self.file_type, self.file_subtype)
+    }
+}
+
+/// Column names taken from the most recent `I` row of a table.
+#[derive(Debug)]
+struct TableHeader {
+    key: TableKey,
+    columns: StringRecord,
+}
+
+/// Field `index` of a raw record as text, replacing invalid UTF-8 rather than failing.
+fn field_str(record: &ByteRecord, index: usize) -> Cow<'_, str> {
+    String::from_utf8_lossy(record.get(index).unwrap_or_default())
+}
+
+/// A single `D` row together with the header it should be read against.
+///
+/// The row borrows the reader's buffer, which is reused for the next line, so fields are only
+/// checked as UTF-8 when they are deserialized and `deserialize` can produce borrowed `&str`
+/// fields that avoid allocating at all.
+#[derive(Debug)]
+pub struct DataRow<'a> {
+    pub table: &'a TableKey,
+    pub headers: &'a StringRecord,
+    pub record: &'a ByteRecord,
+    pub line: u64,
+}
+
+impl<'a> DataRow<'a> {
+    /// Deserializes the row by column name using the preceding `I` row.
+    pub fn deserialize<T: Deserialize<'a>>(&self) -> Result<T, ScraperError> {
+        self.record
+            .deserialize(Some(self.headers.as_byte_record()))
+            .map_err(|err| self.deserialize_error::<T>(err))
+    }
+
+    /// The table version held in the fourth column, e.g. the `3` in `D,TRADING,PRICE,3`.
+    pub fn version(&self) -> Result<u32, ScraperError> {
+        let version = field_str(self.record, 3);
+        version
+            .parse::<u32>()
+            .map_err(|_| ScraperError::SchemaMismatch {
+                location: Box::new(Location {
+                    column: Some(FILE_DESCRIPTOR.to_string()),
+                    ..self.location()
+                }),
+                message: format!("table version {:?} is not a number", version),
+            })
+    }
+
+    /// Deserializes the row using the layout of an older table version `V` and upgrades it
+    /// into the canonical record `T`.
+    pub fn deserialize_upgraded<V, T>(&self) -> Result<T, ScraperError>
+    where
+        V: Deserialize<'a> + Into<T>,
+    {
+        self.deserialize::<V>().map(Into::into)
+    }
+
+    /// Error for a table version that no model layout has been written for.
+    pub fn unsupported_version(&self, version: u32) -> ScraperError {
+        ScraperError::UnsupportedVersion {
+            location: Box::new(self.location()),
+            version,
+        }
+    }
+
+    /// Error for a table the processor has no model for.
+    pub fn unknown_table(&self) -> ScraperError {
+        ScraperError::UnknownTable {
+            location: Box::new(self.location()),
+        }
+    }
+
+    pub fn location(&self) -> Location {
+        Location {
+            line: Some(self.line),
+            table: Some(self.table.clone()),
+            ..Default::default()
+        }
+    }
+
+    fn deserialize_error<T: Deserialize<'a>>(&self, err: csv::Error) -> ScraperError {
+        let csv::ErrorKind::Deserialize { err: de_err, .. } = err.kind() else {
+            return ScraperError::Csv {
+                location: Box::new(self.location()),
+                source: err,
+            };
+        };
+        let mut column = de_err
+            .field()
+            .and_then(|field| self.headers.get(field as usize))
+            .map(str::to_string);
+        // Custom serde errors, e.g. from `deserialize_with` functions, carry no field index,
+        // so the row is read again with its path tracked to find the column
+        if column.is_none() && matches!(de_err.kind(), DeserializeErrorKind::Message(_)) {
+            column = self.failed_column::<T>();
+        }
+        let datetime_value = match de_err.kind() {
+            DeserializeErrorKind::Message(message) => message.strip_prefix(INVALID_DATETIME),
+            _ => None,
+        };
+        let location = Box::new(Location {
+            column,
+            ..self.location()
+        });
+        match datetime_value {
+            Some(value) => ScraperError::DateTime {
+                location,
+                value: value.to_string(),
+            },
+            None => ScraperError::SchemaMismatch {
+                location,
+                message: de_err.kind().to_string(),
+            },
+        }
+    }
+
+    /// The column a row failed on, found by reading the row again with `ColumnTracked`.
+    fn failed_column<T: Deserialize<'a>>(&self) -> Option<String> {
+        let err = self
+            .record
+            .deserialize::<ColumnTracked<T>>(Some(self.headers.as_byte_record()))
+            .err()?;
+        let csv::ErrorKind::Deserialize { err: de_err, .. } = err.kind() else {
+            return None;
+        };
+        let DeserializeErrorKind::Message(message) = de_err.kind() else {
+            return None;
+        };
+        let (index, _) = message.split_once(COLUMN_SEPARATOR)?;
+        let index = index.parse::<usize>().ok()?;
+        self.headers.get(index).map(str::to_string)
+    }
+}
+
+// Separates the column index from the original message in errors raised by `ColumnTracked`.
+const COLUMN_SEPARATOR: char = '\u{1f}';
+
+/// Deserializes `T` while counting the columns it reads, prefixing any error with the index
+/// of the column being read. csv only records the column for errors it raises itself, so
+/// this explains a row that has already failed; rows that parse never go through it.
+struct ColumnTracked<T>(PhantomData<T>);
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for ColumnTracked<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(ColumnTracked(PhantomData))
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for ColumnTracked<T> {
+    type Value = ColumnTracked<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a row with headers")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        let mut columns = CountedColumns { map, read: 0 };
+        T::deserialize(MapAccessDeserializer::new(&mut columns))
+            .map(|_| ColumnTracked(PhantomData))
+            .map_err(|err| {
+                let index = columns.read.saturating_sub(1);
+                A::Error::custom(format!("{}{}{}", index, COLUMN_SEPARATOR, err))
+            })
+    }
+}
+
+struct CountedColumns<A> {
+    map: A,
+    read: usize,
+}
+
+impl<'de, A: MapAccess<'de>> MapAccess<'de> for &mut CountedColumns<A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        self.read += 1;
+        self.map.next_key_seed(seed)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.map.next_value_seed(seed)
+    }
+}
+
+/// Builds a report-level record (usually an enum over the report's tables) from a `D` row.
+pub trait FromDataRow: Sized {
+    fn from_data_row(row: &DataRow) -> Result<Self, ScraperError>;
+
+    /// Whether rows of `table` should be read at all. Reports such as Next_Day_Dispatch carry
+    /// tables that have no model, and their rows are skipped without being parsed. By default
+    /// every table is read, so an unexpected table is reported as [`ScraperError::UnknownTable`].
+    fn reads_table(_table: &TableKey) -> bool {
+        true
+    }
+}
+
+const END_OF_REPORT: &str = "END OF REPORT";
+
+/// Reads an AEMO MMS CSV (`C`, `I`, `D` rows) and yields each `D` row mapped to its header.
+///
+/// A report may hold several tables and may repeat an `I` row, so the header map keeps the
+/// latest `I` row seen for each table. Reaching the end of the file without a matching
+/// `END OF REPORT` trailer, or reading any row after it, is reported as
+/// [`ScraperError::ReportTruncated`].
+pub struct MmsCsvReader<R> {
+    reader: Reader<LineCounter<R>>,
+    headers: Vec<TableHeader>,
+    record: ByteRecord,
+    report_header: Option<ReportHeader>,
+    /// The line count declared by the `END OF REPORT` trailer, once it has been validated.
+    end_of_report: Option<u64>,
+    lines_read: u64,
+    /// Line of the raw input the row just read came from, counting blank lines.
+    raw_line: u64,
+}
+
+impl<R: Read> MmsCsvReader<R> {
+    pub fn new(reader: R) -> Self {
+        MmsCsvReader {
+            reader: ReaderBuilder::new()
+                .has_headers(false)
+                .flexible(true)
+                .from_reader(LineCounter::new(reader)),
+            headers: Vec::new(),
+            record: ByteRecord::new(),
+            report_header: None,
+            end_of_report: None,
+            lines_read: 0,
+            raw_line: 0,
+        }
+    }
+
+    /// Advances to the next `D` row, returning `None` at the end of the file.
+    pub fn next_data_row(&mut self) -> Result<Option<DataRow<'_>>, ScraperError> {
+        let header_index = loop {
+            if !self.reader.read_byte_record(&mut self.record)? {
+                return self.finish();
+            }
+            // AEMO rows never span lines, so each record read is one line of the report
+            self.lines_read += 1;
+            self.raw_line = self.reader.get_mut().next_row_line();
+            if let Some(expected_lines) = self.end_of_report {
+                // A trailer followed by more rows is a corrupt or concatenated file
+                return Err(ScraperError::ReportTruncated {
+                    location: Box::new(self.location()),
+                    expected_lines: Some(expected_lines),
+                    actual_lines: self.lines_read,
+                });
+            }
+            match self.record.get(0) {
+                Some(b"C") if self.lines_read == 1 => {
+                    let record = StringRecord::from_byte_record_lossy(self.record.clone());
+                    self.report_header = Some(ReportHeader::from_record(&record)?)
+                }
+                Some(b"C") => self.check_trailer()?,
+                Some(b"I") => self.update_header(),
+                Some(b"D") => {
+                    match self
+                        .headers
+                        .iter()
+                        .position(|header| header.key.matches_record(&self.record))
+                    {
+                        Some(index) => break index,
+                        None => {
+                            return Err(ScraperError::SchemaMismatch {
+                                location: Box::new(self.location()),
+                                message: "D row has no preceding I row".to_string(),
+                            })
+                        }
+                    }
+                }
+                _ => {
+                    return Err(ScraperError::InvalidLine {
+                        location: Box::new(self.location()),
+                    })
+                }
+            }
+        };
+
+        let line = self.lines_read;
+        let header = &self.headers[header_index];
+        Ok(Some(DataRow {
+            table: &header.key,
+            headers: &header.columns,
+            record: &self.record,
+            line,
+        }))
+    }
+
+    /// The report metadata from the leading `C` row, once it has been read.
+    pub fn report_header(&self) -> Option<&ReportHeader> {
+        self.report_header.as_ref()
+    }
+
+    /// The raw fields of the row most recently read, e.g. to quarantine a rejected row.
+    pub fn current_record(&self) -> &ByteRecord {
+        &self.record
+    }
+
+    /// Turns the reader into an iterator of records, parsing one row at a time so memory use
+    /// does not grow with the size of the file.
+    pub fn records<T: FromDataRow>(self) -> MmsRecords<R, T> {
+        MmsRecords {
+            reader: self,
+            finished: false,
+            record_type: PhantomData,
+        }
+    }
+
+    fn check_trailer(&mut self) -> Result<(), ScraperError> {
+        if self.record.get(1) != Some(END_OF_REPORT.as_bytes()) {
+            return Ok(());
+        }
+        // Compared with the raw line of the trailer so blank lines count here as in `finish`
+        let expected_lines = field_str(&self.record, 2).parse::<u64>().ok();
+        if expected_lines != Some(self.raw_line) {
+            return Err(ScraperError::ReportTruncated {
+                location: Box::new(Location {
+                    line: Some(self.raw_line),
+                    ..Default::default()
+                }),
+                expected_lines,
+                actual_lines: self.raw_line,
+            });
+        }
+        self.end_of_report = expected_lines;
+        Ok(())
+    }
+
+    /// Checks the trailer against the raw line count once the whole file has been read, so
+    /// blank lines after the trailer are caught too.
+    fn finish<'b>(&self) -> Result<Option<DataRow<'b>>, ScraperError> {
+        let actual_lines = self.reader.get_ref().lines();
+        match self.end_of_report {
+            Some(expected_lines) if expected_lines == actual_lines => Ok(None),
+            expected_lines => Err(ScraperError::ReportTruncated {
+                location: Box::new(Location {
+                    line: Some(actual_lines),
+                    ..Default::default()
+                }),
+                expected_lines,
+                actual_lines,
+            }),
+        }
+    }
+
+    /// Location of the row just read, with the table taken from its own columns.
+    fn location(&self) -> Location {
+        Location {
+            line: Some(self.lines_read),
+            table: match self.record.get(0) {
+                Some(b"I") | Some(b"D") => Some(TableKey::from_record(&self.record)),
+                _ => None,
+            },
+            ..Default::default()
+        }
+    }
+
+    fn update_header(&mut self) {
+        let mut columns = StringRecord::new();
+        columns.push_field(ROW_TYPE);
+        columns.push_field(FILE_TYPE);
+        columns.push_field(FILE_SUBTYPE);
+        columns.push_field(FILE_DESCRIPTOR);
+        for column in self.record.iter().skip(4) {
+            columns.push_field(&String::from_utf8_lossy(column));
+        }
+
+        match self
+            .headers
+            .iter_mut()
+            .find(|header| header.key.matches_record(&self.record))
+        {
+            Some(header) => header.columns = columns,
+            None => self.headers.push(TableHeader {
+                key: TableKey::from_record(&self.record),
+                columns,
+            }),
+        }
+    }
+}
+
+/// Counts the lines of the raw input, including blank lines that never reach the csv reader.
+///
+/// The csv reader reads ahead, so the raw line of each non-blank line is queued until the
+/// row parsed from it is taken with [`LineCounter::next_row_line`].
+struct LineCounter<R> {
+    inner: R,
+    newlines: u64,
+    last_byte: Option<u8>,
+    /// Whether the line being scanned has had anything but `\r` in it yet.
+    line_has_content: bool,
+    row_lines: VecDeque<u64>,
+}
+
+impl<R> LineCounter<R> {
+    fn new(inner: R) -> Self {
+        LineCounter {
+            inner,
+            newlines: 0,
+            last_byte: None,
+            line_has_content: false,
+            row_lines: VecDeque::new(),
+        }
+    }
+
+    /// Lines read so far, counting a final line without a terminator.
+    fn lines(&self) -> u64 {
+        match self.last_byte {
+            Some(b'\n') | None => self.newlines,
+            Some(_) => self.newlines + 1,
+        }
+    }
+
+    /// The raw line of the next row the csv reader returns. AEMO rows never span lines, so
+    /// each row is the next line with content.
+    fn next_row_line(&mut self) -> u64 {
+        self.row_lines.pop_front().unwrap_or_else(|| self.lines())
+    }
+
+    fn scan(&mut self, buf: &[u8]) {
+        let mut start = 0;
+        for end in memchr::memchr_iter(b'\n', buf).chain(std::iter::once(buf.len())) {
+            if !self.line_has_content && buf[start..end].iter().any(|&byte| byte != b'\r') {
+                self.line_has_content = true;
+                self.row_lines.push_back(self.newlines + 1);
+            }
+            if end < buf.len() {
+                self.newlines += 1;
+                self.line_has_content = false;
+            }
+            start = end + 1;
+        }
+    }
+}
+
+impl<R: Read> Read for LineCounter<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        if let Some(&last_byte) = buf[..read].last() {
+            self.scan(&buf[..read]);
+            self.last_byte = Some(last_byte);
+        }
+        Ok(read)
+    }
+}
+
+/// Iterator over the records of an MMS CSV, created by [`MmsCsvReader::records`].
+///
+/// A row that fails to convert is yielded as an error and iteration continues; a failure to
+/// read the underlying CSV or a truncated report ends the iteration.
+pub struct MmsRecords<R, T> {
+    reader: MmsCsvReader<R>,
+    finished: bool,
+    record_type: PhantomData<T>,
+}
+
+impl<R, T> MmsRecords<R, T> {
+    /// The report metadata from the leading `C` row, once iteration has started.
+    pub fn report_header(&self) -> Option<&ReportHeader> {
+        self.reader.report_header.as_ref()
+    }
+
+    /// The raw fields of the row most recently read, e.g. to quarantine a rejected row.
+    pub fn current_record(&self) -> &ByteRecord {
+        &self.reader.record
+    }
+}
+
+impl<R: Read, T: FromDataRow> Iterator for MmsRecords<R, T> {
+    type Item = Result<T, ScraperError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        loop {
+            return match self.reader.next_data_row() {
+                Ok(Some(row)) if !T::reads_table(row.table) => continue,
+                Ok(Some(row)) => Some(T::from_data_row(&row)),
+                Ok(None) => {
+                    self.finished = true;
+                    None
+                }
+                Err(e) => {
+                    self.finished = !e.is_row_error();
+                    Some(Err(e))
+                }
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize)]
+    struct Sample {
+        #[serde(rename = "FILE_SUBTYPE")]
+        file_subtype: String,
+        #[serde(rename = "REGIONID")]
+        region_id: String,
+        #[serde(rename = "RRP")]
+        rrp: Option<f64>,
+    }
+
+    #[test]
+    fn test_data_rows_are_mapped_by_column_name() {
+        let contents = "C,NEMP.WORLD,TEST,AEMO,PUBLIC,2024/03/03,13:30:11,1,TEST,0\n\
+            I,TRADING,PRICE,3,REGIONID,RRP\n\
+            D,TRADING,PRICE,3,NSW1,77.06\n\
+            I,TRADING,PRICE,3,EXTRA,RRP,REGIONID\n\
+            D,TRADING,PRICE,3,ignored,86,QLD1\n\
+            C,\"END OF REPORT\",6\n";
+        let mut reader = MmsCsvReader::new(contents.as_bytes());
+        let mut samples = Vec::new();
+        while let Some(row) = reader.next_data_row().unwrap() {
+            assert!(row.table.is("TRADING", "PRICE"));
+            samples.push(row.deserialize::<Sample>().unwrap());
+        }
+
+        assert_eq!(reader.report_header().unwrap().report_id, "1");
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[0].file_subtype, "PRICE");
+        assert_eq!(samples[0].region_id, "NSW1");
+        assert_eq!(samples[0].rrp, Some(77.06));
+        assert_eq!(samples[1].region_id, "QLD1");
+        assert_eq!(samples[1].rrp, Some(86.0));
+    }
+
+    impl FromDataRow for Sample {
+        fn from_data_row(row: &DataRow) -> Result<Self, ScraperError> {
+            row.deserialize::<Sample>()
+        }
+    }
+
+    #[test]
+    fn test_records_iterator() {
+        let contents = "I,TRADING,PRICE,3,REGIONID,RRP\n\
+            D,TRADING,PRICE,3,NSW1,77.06\n\
+            D,TRADING,PRICE,3,QLD1,not a number\n\
+            D,TRADING,PRICE,3,VIC1,-67.1\n\
+            D,TRADING,INTERCONNECTORRES,2,V-SA,1\n\
+            D,TRADING,PRICE,3,SA1,-63.45\n";
+        let results: Vec<Result<Sample, ScraperError>> =
+            MmsCsvReader::new(contents.as_bytes()).records().collect();
+
+        assert_eq!(results.len(), 6);
+        assert_eq!(results[0].as_ref().unwrap().region_id, "NSW1");
+        match &results[1] {
+            Err(ScraperError::SchemaMismatch { location, .. }) => {
+                assert_eq!(location.line, Some(3));
+                assert_eq!(location.column.as_deref(), Some("RRP"));
+            }
+            other => panic!("Expected a schema mismatch, got {:?}", other),
+        }
+        assert_eq!(results[2].as_ref().unwrap().rrp, Some(-67.1));
+        assert!(results[3].as_ref().unwrap_err().is_row_error());
+        assert_eq!(results[4].as_ref().unwrap().region_id, "SA1");
+        assert!(matches!(
+            results[5],
+            Err(ScraperError::ReportTruncated { .. })
+        ));
+    }
+
+    #[test]
+    fn test_bad_datetime_is_reported_with_column() {
+        #[derive(Debug, Deserialize)]
+        struct Dated {
+            #[serde(rename = "SETTLEMENTDATE")]
+            #[serde(
+                deserialize_with = "crate::time::datetimezone_conversion::deserialize_sydney_datetime_to_utc"
+            )]
+            _settlement_date: chrono::DateTime<chrono::Utc>,
+        }
+
+        let contents = "I,TRADING,PRICE,3,REGIONID,SETTLEMENTDATE\n\
+            D,TRADING,PRICE,3,NSW1,\"2024/13/03 13:35:00\"\n";
+        let mut reader = MmsCsvReader::new(contents.as_bytes());
+        let row = reader.next_data_row().unwrap().unwrap();
+
+        match row.deserialize::<Dated>() {
+            Err(ScraperError::DateTime { location, value }) => {
+                assert_eq!(value, "2024/13/03 13:35:00");
+                assert_eq!(location.line, Some(2));
+                assert_eq!(location.table, Some(TableKey::new("TRADING", "PRICE")));
+                assert_eq!(location.column.as_deref(), Some("SETTLEMENTDATE"));
+            }
+            other => panic!("Expected a datetime error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_bad_datetime_column_is_not_guessed_from_its_value() {
+        #[derive(Debug, Deserialize)]
+        struct Dated {
+            #[serde(rename = "SETTLEMENTDATE")]
+            #[serde(
+                deserialize_with = "crate::time::datetimezone_conversion::deserialize_sydney_datetime_to_utc"
+            )]
+            _settlement_date: chrono::DateTime<chrono::Utc>,
+        }
+
+        // The empty SETTLEMENTDATE matches the empty NOTE column before it
+        let contents = "I,TRADING,PRICE,3,REGIONID,NOTE,SETTLEMENTDATE\n\
+            D,TRADING,PRICE,3,NSW1,,\n";
+        let mut reader = MmsCsvReader::new(contents.as_bytes());
+        let row = reader.next_data_row().unwrap().unwrap();
+
+        match row.deserialize::<Dated>() {
+            Err(ScraperError::DateTime { location, value }) => {
+                assert_eq!(value, "");
+                assert_eq!(location.column.as_deref(), Some("SETTLEMENTDATE"));
+            }
+            other => panic!("Expected a datetime error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_end_of_report_trailer_is_validated() {
+        let contents = "I,TRADING,PRICE,3,REGIONID,RRP\n\
+            D,TRADING,PRICE,3,NSW1,77.06\n\
+            C,\"END OF REPORT\",3\n";
+        let results: Result<Vec<Sample>, _> =
+            MmsCsvReader::new(contents.as_bytes()).records().collect();
+        assert_eq!(results.unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_missing_end_of_report_trailer_is_truncated() {
+        let contents = "I,TRADING,PRICE,3,REGIONID,RRP\n\
+            D,TRADING,PRICE,3,NSW1,77.06\n";
+        let mut reader = MmsCsvReader::new(contents.as_bytes());
+        assert!(reader.next_data_row().unwrap().is_some());
+
+        match reader.next_data_row() {
+            Err(ScraperError::ReportTruncated {
+                expected_lines,
+                actual_lines,
+                ..
+            }) => {
+                assert_eq!(expected_lines, None);
+                assert_eq!(actual_lines, 2);
+            }
+            other => panic!("Expected a truncated report, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_wrong_end_of_report_count_is_truncated() {
+        let contents = "I,TRADING,PRICE,3,REGIONID,RRP\n\
+            D,TRADING,PRICE,3,NSW1,77.06\n\
+            C,\"END OF REPORT\",15\n";
+        let results: Result<Vec<Sample>, _> =
+            MmsCsvReader::new(contents.as_bytes()).records().collect();
+
+        assert_eq!(
+            results.unwrap_err().to_string(),
+            "Report truncated at line 3: END OF REPORT declares 15 lines but 3 were read"
+        );
+    }
+
+    #[test]
+    fn test_blank_lines_count_towards_end_of_report() {
+        let contents = "I,TRADING,PRICE,3,REGIONID,RRP\n\
+            \n\
+            D,TRADING,PRICE,3,NSW1,77.06\n\
+            C,\"END OF REPORT\",3\n";
+        let results: Result<Vec<Sample>, _> =
+            MmsCsvReader::new(contents.as_bytes()).records().collect();
+
+        assert_eq!(
+            results.unwrap_err().to_string(),
+            "Report truncated at line 4: END OF REPORT declares 3 lines but 4 were read"
+        );
+    }
+
+    #[test]
+    fn test_blank_lines_with_matching_end_of_report() {
+        for contents in [
+            "I,TRADING,PRICE,3,REGIONID,RRP\n\nD,TRADING,PRICE,3,NSW1,77.06\nC,\"END OF REPORT\",4\n",
+            "I,TRADING,PRICE,3,REGIONID,RRP\r\n\r\nD,TRADING,PRICE,3,NSW1,77.06\r\nC,\"END OF REPORT\",4",
+        ] {
+            let records: Vec<Sample> = MmsCsvReader::new(contents.as_bytes())
+                .records()
+                .collect::<Result<_, _>>()
+                .expect("Failed to parse");
+            assert_eq!(records.len(), 1);
+        }
+    }
+
+    #[test]
+    fn test_rows_after_end_of_report_are_rejected() {
+        let contents = "I,TRADING,PRICE,3,REGIONID,RRP\n\
+            D,TRADING,PRICE,3,NSW1,77.06\n\
+            C,\"END OF REPORT\",3\n\
+            D,TRADING,PRICE,3,QLD1,80.9\n";
+        let mut reader = MmsCsvReader::new(contents.as_bytes());
+        assert!(reader.next_data_row().unwrap().is_some());
+
+        match reader.next_data_row() {
+            Err(ScraperError::ReportTruncated {
+                location,
+                expected_lines,
+                actual_lines,
+            }) => {
+                assert_eq!(location.line, Some(4));
+                assert_eq!(expected_lines, Some(3));
+                assert_eq!(actual_lines, 4);
+            }
+            other => panic!("Expected a truncated report, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_data_row_version() {
+        let contents = "I,ROOFTOP,ACTUAL,2,REGIONID\n\
+            D,ROOFTOP,ACTUAL,2,NSW1\n\
+            I,ROOFTOP,ACTUAL,x,REGIONID\n\
+            D,ROOFTOP,ACTUAL,x,NSW1\n";
+        let mut reader = MmsCsvReader::new(contents.as_bytes());
+
+        let row = reader.next_data_row().unwrap().unwrap();
+        assert_eq!(row.version().unwrap(), 2);
+        assert_eq!(
+            row.unsupported_version(7).to_string(),
+            "Unsupported table version 7 at line 2, table ROOFTOP.ACTUAL"
+        );
+
+        let row = reader.next_data_row().unwrap().unwrap();
+        assert!(row.version().is_err());
+    }
+
+    #[test]
+    fn test_data_row_without_information_row_is_an_error() {
+        let contents = "I,TRADING,PRICE,3,REGIONID,RRP\n\
+            D,TRADING,INTERCONNECTORRES,2,NSW1-QLD1,1\n";
+        let mut reader = MmsCsvReader::new(contents.as_bytes());
+        let err = reader.next_data_row().unwrap_err();
+        assert!(err.to_string().contains("TRADING.INTERCONNECTORRES"));
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct BorrowedSample<'a> {
+        #[serde(rename = "REGIONID")]
+        region_id: &'a str,
+        #[serde(rename = "RRP")]
+        rrp: Option<f64>,
+    }
+
+    #[test]
+    fn test_data_rows_deserialize_borrowed_fields() {
+        let contents = "I,TRADING,PRICE,3,REGIONID,RRP\n\
+            D,TRADING,PRICE,3,NSW1,77.06\n\
+            D,TRADING,PRICE,3,QLD1,\n\
+            C,\"END OF REPORT\",4\n";
+        let mut reader = MmsCsvReader::new(contents.as_bytes());
+        let mut regions = Vec::new();
+        while let Some(row) = reader.next_data_row().unwrap() {
+            let sample: BorrowedSample = row.deserialize().unwrap();
+            regions.push((sample.region_id.to_string(), sample.rrp));
+        }
+
+        assert_eq!(
+            regions,
+            [
+                ("NSW1".to_string(), Some(77.06)),
+                ("QLD1".to_string(), None)
+            ]
+        );
+    }
+}
@@ -0,0 +1,206 @@
+use chrono::{DateTime, Utc};
+use std::fmt;
+use std::io::Read;
+use std::str::FromStr;
+
+use crate::common::mms_csv::{DataRow, FromDataRow, MmsCsvReader, MmsRecords, TableKey};
+use crate::common::unzip_process::RecordsCollection;
+use crate::error::{Location, ScraperError};
+use crate::time::datetimezone_conversion::parse_sydney_datetime;
+
+/// A `D` row kept as raw strings, for tables that have no typed model yet.
+///
+/// Columns keep the order of the table's `I` row. The four leading descriptor columns are
+/// held in `table` and `version` instead. Empty values read as `None` from the typed
+/// accessors, matching how the models treat blank numeric fields.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MmsRow {
+    pub table: TableKey,
+    pub version: u32,
+    pub line: u64,
+    columns: Vec<(String, String)>,
+}
+
+impl MmsRow {
+    /// Column names and raw values in file order.
+    pub fn columns(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.columns
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    /// The raw value of a column, or `None` if the table has no such column.
+    pub fn get(&self, column: &str) -> Option<&str> {
+        self.columns
+            .iter()
+            .find(|(name, _)| name == column)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The value of a column, `None` if the column is missing or empty.
+    pub fn get_str(&self, column: &str) -> Option<&str> {
+        self.get(column).filter(|value| !value.is_empty())
+    }
+
+    pub fn get_f64(&self, column: &str) -> Result<Option<f64>, ScraperError> {
+        self.parse(column)
+    }
+
+    pub fn get_i64(&self, column: &str) -> Result<Option<i64>, ScraperError> {
+        self.parse(column)
+    }
+
+    /// Reads a NEM timestamp (Sydney local time) as UTC.
+    pub fn get_datetime(&self, column: &str) -> Result<Option<DateTime<Utc>>, ScraperError> {
+        let Some(value) = self.get_str(column) else {
+            return Ok(None);
+        };
+        parse_sydney_datetime(value)
+            .map(Some)
+            .ok_or_else(|| ScraperError::DateTime {
+                location: Box::new(self.location(column)),
+                value: value.to_string(),
+            })
+    }
+
+    fn parse<V: FromStr>(&self, column: &str) -> Result<Option<V>, ScraperError> {
+        let Some(value) = self.get_str(column) else {
+            return Ok(None);
+        };
+        value
+            .parse()
+            .map(Some)
+            .map_err(|_| ScraperError::SchemaMismatch {
+                location: Box::new(self.location(column)),
+                message: format!("{:?} is not a valid {}", value, std::any::type_name::<V>()),
+            })
+    }
+
+    fn location(&self, column: &str) -> Location {
+        Location {
+            line: Some(self.line),
+            table: Some(self.table.clone()),
+            column: Some(column.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+impl FromDataRow for MmsRow {
+    fn from_data_row(row: &DataRow) -> Result<Self, ScraperError> {
+        let columns = row
+            .headers
+            .iter()
+            .zip(row.record.iter())
+            .skip(4)
+            .map(|(name, value)| {
+                (
+                    name.to_string(),
+                    String::from_utf8_lossy(value).into_owned(),
+                )
+            })
+            .collect();
+        Ok(MmsRow {
+            table: row.table.clone(),
+            version: row.version()?,
+            line: row.line,
+            columns,
+        })
+    }
+}
+
+impl fmt::Display for MmsRow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "MmsRow: {{ table: {}, version: {}",
+            self.table, self.version
+        )?;
+        for (name, value) in self.columns() {
+            write!(f, ", {}: {:?}", name, value)?;
+        }
+        write!(f, " }}")
+    }
+}
+
+/// Streams every `D` row of any MMS report as an [`MmsRow`].
+pub fn stream_file_mms_rows<R: Read>(reader: R) -> MmsRecords<R, MmsRow> {
+    MmsCsvReader::new(reader).records()
+}
+
+pub fn process_file_mms_rows<R: Read>(
+    reader: R,
+) -> Result<RecordsCollection<MmsRow>, ScraperError> {
+    RecordsCollection::from_records(stream_file_mms_rows(reader))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::unzip_process::unzip_and_process;
+
+    #[test]
+    fn test_process_file_mms_rows_dispatch_legacy_fixture() {
+        let collection = unzip_and_process(
+            "src/fixtures/PUBLIC_DISPATCH_202403151945_20240315194011_LEGACY.zip",
+            |reader| process_file_mms_rows(reader),
+        )
+        .expect("Failed to process dispatch fixture");
+
+        assert_eq!(collection.records.len(), 881);
+        let region = collection
+            .records
+            .iter()
+            .find(|row| row.table.is("DREGION", ""))
+            .expect("No DREGION rows");
+        assert_eq!(region.version, 3);
+        assert_eq!(region.get_str("REGIONID"), Some("NSW1"));
+        assert!(region.get_f64("RRP").unwrap().is_some());
+        assert_eq!(
+            region
+                .get_datetime("SETTLEMENTDATE")
+                .unwrap()
+                .map(|dt| dt.to_rfc3339()),
+            Some("2024-03-15T08:45:00+00:00".to_string())
+        );
+        assert_eq!(
+            region.columns().next().map(|(name, _)| name),
+            Some("SETTLEMENTDATE")
+        );
+        assert_eq!(region.get("NOT_A_COLUMN"), None);
+    }
+
+    #[test]
+    fn test_process_file_mms_rows_network_fixture() {
+        let collection = unzip_and_process(
+            "src/fixtures/PUBLIC_NETWORK_20240310200009_0000000413336789.zip",
+            |reader| process_file_mms_rows(reader),
+        )
+        .expect("Failed to process network fixture");
+
+        assert_eq!(collection.records.len(), 10914);
+        assert!(collection
+            .records
+            .iter()
+            .any(|row| row.table.is("NETWORK", "OUTAGECONSTRAINTSET")));
+    }
+
+    #[test]
+    fn test_mms_row_accessors_report_column() {
+        let data = "I,TRADING,PRICE,3,SETTLEMENTDATE,RUNNO,RRP\n\
+            D,TRADING,PRICE,3,\"2024/03/03 13:35:00\",x,\n";
+        let row = stream_file_mms_rows(data.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(row.get_f64("RRP").unwrap(), None);
+        match row.get_i64("RUNNO") {
+            Err(ScraperError::SchemaMismatch { location, .. }) => {
+                assert_eq!(location.line, Some(2));
+                assert_eq!(location.column.as_deref(), Some("RUNNO"));
+            }
+            other => panic!("Expected a schema mismatch, got {:?}", other),
+        }
+    }
+}
@@ -1 +1,8 @@
+pub mod forecast;
+pub mod intern;
+pub mod mms_csv;
+pub mod mms_row;
+pub mod report_header;
+pub mod unit;
 pub mod unzip_process;
+pub mod wem;
@@ -0,0 +1,152 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use csv::StringRecord;
+use std::fmt;
+
+use crate::error::{Location, ScraperError};
+use crate::time::datetimezone_conversion::{sydney_to_utc, DATE_FORMAT_FROM};
+
+/// Metadata from the leading `C` row of an MMS report, for example:
+/// `C,NEMP.WORLD,TRADINGIS,AEMO,PUBLIC,2024/03/03,13:30:11,0000000412683134,TRADINGIS,0000000412683133`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportHeader {
+    pub system: String,
+    pub report_name: String,
+    pub from_participant: String,
+    pub to_participant: String,
+    pub published: DateTime<Utc>,
+    pub report_id: String,
+    pub report_subtype: Option<String>,
+    /// ID of the report this one follows, used to chain reports in publication order.
+    pub previous_report_id: Option<String>,
+}
+
+impl ReportHeader {
+    pub fn from_record(record: &StringRecord) -> Result<Self, ScraperError> {
+        let location = Box::new(Location {
+            line: Some(1),
+            ..Default::default()
+        });
+        if record.len() < 8 || record.get(0) != Some("C") {
+            return Err(ScraperError::SchemaMismatch {
+                location: location.clone(),
+                message: format!("invalid report header: {:?}", record),
+            });
+        }
+        let field = |index: usize| record.get(index).unwrap_or_default().to_string();
+        let optional_field = |index: usize| {
+            record
+                .get(index)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+
+        let published_str = format!("{} {}", field(5), field(6));
+        let published = NaiveDateTime::parse_from_str(&published_str, DATE_FORMAT_FROM)
+            .ok()
+            .and_then(|published| sydney_to_utc(&published))
+            .ok_or(ScraperError::DateTime {
+                location,
+                value: published_str,
+            })?;
+
+        Ok(ReportHeader {
+            system: field(1),
+            report_name: field(2),
+            from_participant: field(3),
+            to_participant: field(4),
+            published,
+            report_id: field(7),
+            report_subtype: optional_field(8),
+            previous_report_id: optional_field(9),
+        })
+    }
+}
+
+impl fmt::Display for ReportHeader {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ReportHeader: {{ system: {}, report_name: {}, from_participant: {}, to_participant: {}, published: {:?}, report_id: {}, report_subtype: {:?}, previous_report_id: {:?} }}",
+            self.system,
+            self.report_name,
+            self.from_participant,
+            self.to_participant,
+            self.published,
+            self.report_id,
+            self.report_subtype,
+            self.previous_report_id
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_header_from_record() {
+        let record = StringRecord::from(vec![
+            "C",
+            "NEMP.WORLD",
+            "TRADINGIS",
+            "AEMO",
+            "PUBLIC",
+            "2024/03/03",
+            "13:30:11",
+            "0000000412683134",
+            "TRADINGIS",
+            "0000000412683133",
+        ]);
+
+        let header = ReportHeader::from_record(&record).expect("Failed to parse header");
+
+        assert_eq!(header.system, "NEMP.WORLD");
+        assert_eq!(header.report_name, "TRADINGIS");
+        assert_eq!(header.to_participant, "PUBLIC");
+        assert_eq!(header.published.to_rfc3339(), "2024-03-03T02:30:11+00:00");
+        assert_eq!(header.report_id, "0000000412683134");
+        assert_eq!(header.report_subtype.as_deref(), Some("TRADINGIS"));
+        assert_eq!(
+            header.previous_report_id.as_deref(),
+            Some("0000000412683133")
+        );
+    }
+
+    #[test]
+    fn test_report_header_with_empty_subtype() {
+        let record = StringRecord::from(vec![
+            "C",
+            "NEMP.WORLD",
+            "DISPATCH",
+            "AEMO",
+            "PUBLIC",
+            "2024/03/15",
+            "19:40:11",
+            "0000000413803622",
+            "",
+            "0000000413803622",
+        ]);
+
+        let header = ReportHeader::from_record(&record).expect("Failed to parse header");
+
+        assert_eq!(header.report_subtype, None);
+        assert_eq!(
+            header.previous_report_id.as_deref(),
+            Some("0000000413803622")
+        );
+    }
+
+    #[test]
+    fn test_report_header_rejects_bad_date() {
+        let record = StringRecord::from(vec![
+            "C",
+            "NEMP.WORLD",
+            "TRADINGIS",
+            "AEMO",
+            "PUBLIC",
+            "not a date",
+            "13:30:11",
+            "0000000412683134",
+        ]);
+
+        assert!(ReportHeader::from_record(&record).is_err());
+    }
+}
@@ -0,0 +1,10 @@
+use chrono::{DateTime, Utc};
+
+/// A row of a unit-level report, such as SCADA, dispatch or bids, keyed on DUID. Implemented
+/// so the row can be matched with the unit's registration at the time the row applies to.
+pub trait UnitRow {
+    fn duid(&self) -> &str;
+
+    /// The interval or trading day the row applies to.
+    fn settlement_datetime(&self) -> DateTime<Utc>;
+}
@@ -1,23 +1,130 @@
+use csv::ByteRecord;
 use reqwest;
-use std::error::Error;
 use std::fmt;
 use std::fmt::Display;
 use std::fs::{metadata, File};
-use std::io::Cursor;
-use std::io::Read;
+use std::io::{Cursor, Read, Seek};
+use std::ops::Range;
 use std::path::Path;
 use std::time::Instant;
+use zip::read::ZipFile;
 use zip::ZipArchive;
 
+use crate::common::mms_csv::{DataRow, FromDataRow, MmsCsvReader, MmsRecords};
+use crate::common::report_header::ReportHeader;
+use crate::error::ScraperError;
+
 pub trait ProcessRecord<T> {
-    fn process(line: &str) -> Result<T, Box<dyn Error>>;
+    fn process(row: &DataRow) -> Result<T, ScraperError>;
+}
+
+/// What to do with a row that cannot be parsed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RowErrorPolicy {
+    /// Fail the whole file on the first bad row.
+    Strict,
+    /// Drop bad rows, failing the file once more than `max_rejected_rows` are dropped.
+    Skip { max_rejected_rows: usize },
+    /// Like `Skip`, but keep each bad row and its error in `rejected_rows`.
+    Quarantine { max_rejected_rows: usize },
+}
+
+impl RowErrorPolicy {
+    /// Counts a row that failed and keeps its `fields` under `Quarantine`. Errors that are not
+    /// confined to the row, and a row past the policy's limit, are returned instead.
+    pub(crate) fn reject<F>(
+        self,
+        error: ScraperError,
+        fields: F,
+        rejected_row_count: &mut usize,
+        rejected_rows: &mut Vec<RejectedRow>,
+    ) -> Result<(), ScraperError>
+    where
+        F: FnOnce() -> Vec<String>,
+    {
+        let (max_rejected_rows, quarantine) = match self {
+            RowErrorPolicy::Skip { max_rejected_rows } if error.is_row_error() => {
+                (max_rejected_rows, false)
+            }
+            RowErrorPolicy::Quarantine { max_rejected_rows } if error.is_row_error() => {
+                (max_rejected_rows, true)
+            }
+            _ => return Err(error),
+        };
+
+        *rejected_row_count += 1;
+        if *rejected_row_count > max_rejected_rows {
+            return Err(ScraperError::TooManyRejectedRows {
+                location: Box::new(error.location().cloned().unwrap_or_default()),
+                rejected_rows: *rejected_row_count,
+                max_rejected_rows,
+            });
+        }
+        if quarantine {
+            rejected_rows.push(RejectedRow {
+                line: error.location().and_then(|location| location.line),
+                fields: fields(),
+                error,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// The fields of a raw MMS row, for quarantining it.
+pub(crate) fn record_fields(record: &ByteRecord) -> Vec<String> {
+    record
+        .iter()
+        .map(|field| String::from_utf8_lossy(field).into_owned())
+        .collect()
+}
+
+/// A row set aside under [`RowErrorPolicy::Quarantine`].
+#[derive(Debug)]
+pub struct RejectedRow {
+    pub line: Option<u64>,
+    pub fields: Vec<String>,
+    pub error: ScraperError,
+}
+
+/// What to do with an archive entry that fails as a whole, e.g. a truncated CSV or a corrupt
+/// nested zip.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EntryErrorPolicy {
+    /// Fail the whole archive on the first bad entry.
+    Strict,
+    /// Drop bad entries, keeping each one's name and error in `rejected_entries`, and fail the
+    /// archive once more than `max_rejected_entries` are dropped.
+    Skip { max_rejected_entries: usize },
+}
+
+/// An archive entry dropped under [`EntryErrorPolicy::Skip`].
+#[derive(Debug)]
+pub struct RejectedEntry {
+    pub file_name: String,
+    pub error: ScraperError,
+}
+
+/// The records one CSV contributed to a collection, tagged with the CSV's path inside the
+/// archive, e.g. `PUBLIC_TRADINGIS_20240303.zip/PUBLIC_TRADINGIS_202403031335.CSV`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordSet {
+    pub file_name: String,
+    pub report_header: Option<ReportHeader>,
+    /// Index range of this file's records within `RecordsCollection::records`.
+    pub records: Range<usize>,
 }
 
 /// A generic collection of records with metadata.
 #[derive(Debug)]
 pub struct RecordsCollection<T> {
     pub records: Vec<T>,
+    pub record_sets: Vec<RecordSet>,
     pub source_file: Option<String>,
+    pub report_header: Option<ReportHeader>,
+    pub rejected_row_count: usize,
+    pub rejected_rows: Vec<RejectedRow>,
+    pub rejected_entries: Vec<RejectedEntry>,
     pub processing_time_ms: Option<u128>,
     pub zipfile_size_bytes: Option<u64>,
     pub number_of_files: Option<usize>,
@@ -27,7 +134,12 @@ impl<T> RecordsCollection<T> {
     pub fn new() -> Self {
         RecordsCollection {
             records: Vec::new(),
+            record_sets: Vec::new(),
             source_file: None,
+            report_header: None,
+            rejected_row_count: 0,
+            rejected_rows: Vec::new(),
+            rejected_entries: Vec::new(),
             processing_time_ms: None,
             zipfile_size_bytes: None,
             number_of_files: None,
@@ -44,6 +156,44 @@ impl<T> RecordsCollection<T> {
         self.source_file = Some(file_name);
     }
 
+    /// Sets the report header metadata.
+    pub fn set_report_header(&mut self, header: ReportHeader) {
+        self.report_header = Some(header);
+    }
+
+    /// Moves the records of a processed file into this collection as a record set tagged
+    /// with `file_name`, keeping the first report header seen.
+    pub fn append(&mut self, file_name: &str, other: RecordsCollection<T>) {
+        let offset = self.records.len();
+        if other.record_sets.is_empty() {
+            self.record_sets.push(RecordSet {
+                file_name: file_name.to_string(),
+                report_header: other.report_header.clone(),
+                records: offset..offset + other.records.len(),
+            });
+        } else {
+            self.record_sets
+                .extend(other.record_sets.into_iter().map(|set| RecordSet {
+                    records: set.records.start + offset..set.records.end + offset,
+                    ..set
+                }));
+        }
+        self.add_records(other.records);
+        self.rejected_row_count += other.rejected_row_count;
+        self.rejected_rows.extend(other.rejected_rows);
+        self.rejected_entries.extend(other.rejected_entries);
+        if self.report_header.is_none() {
+            self.report_header = other.report_header;
+        }
+    }
+
+    /// The records of each file, paired with the record set describing that file.
+    pub fn records_by_file(&self) -> impl Iterator<Item = (&RecordSet, &[T])> {
+        self.record_sets
+            .iter()
+            .map(|set| (set, &self.records[set.records.clone()]))
+    }
+
     /// Sets the processing time metadata.
     pub fn set_processing_time(&mut self, time_ms: u128) {
         self.processing_time_ms = Some(time_ms);
@@ -60,6 +210,43 @@ impl<T> RecordsCollection<T> {
     }
 }
 
+impl<T: FromDataRow> RecordsCollection<T> {
+    /// Drains an MMS record iterator into a collection, keeping the report header.
+    pub fn from_records<R: Read>(records: MmsRecords<R, T>) -> Result<Self, ScraperError> {
+        Self::from_records_with_policy(records, RowErrorPolicy::Strict)
+    }
+
+    /// Drains an MMS record iterator into a collection, handling bad rows according to
+    /// `policy`. Errors that affect the whole file, such as a truncated report, always fail.
+    pub fn from_records_with_policy<R: Read>(
+        mut records: MmsRecords<R, T>,
+        policy: RowErrorPolicy,
+    ) -> Result<Self, ScraperError> {
+        let mut collection = RecordsCollection::new();
+        while let Some(record) = records.next() {
+            match record {
+                Ok(record) => collection.records.push(record),
+                Err(error) => policy.reject(
+                    error,
+                    || record_fields(records.current_record()),
+                    &mut collection.rejected_row_count,
+                    &mut collection.rejected_rows,
+                )?,
+            }
+        }
+        if let Some(header) = records.report_header() {
+            collection.set_report_header(header.clone());
+        }
+        Ok(collection)
+    }
+}
+
+impl<T> Default for RecordsCollection<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T: fmt::Display> fmt::Display for RecordsCollection<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         writeln!(f, "Records Collection:")?;
@@ -68,9 +255,15 @@ impl<T: fmt::Display> fmt::Display for RecordsCollection<T> {
             "Source File: {:?}",
             self.source_file.as_ref().unwrap_or(&"None".to_string())
         )?;
+        match &self.report_header {
+            Some(header) => writeln!(f, "Report Header: {}", header)?,
+            None => writeln!(f, "Report Header: None")?,
+        }
         writeln!(f, "Processing Time (ms): {:?}", self.processing_time_ms)?;
         writeln!(f, "Zipfile Size (bytes): {:?}", self.zipfile_size_bytes)?;
         writeln!(f, "Number of Files: {:?}", self.number_of_files)?;
+        writeln!(f, "Rejected Rows: {}", self.rejected_row_count)?;
+        writeln!(f, "Rejected Entries: {}", self.rejected_entries.len())?;
         writeln!(f, "Records:")?;
         for record in &self.records {
             writeln!(f, "{}", record)?;
@@ -83,10 +276,25 @@ impl<T: fmt::Display> fmt::Display for RecordsCollection<T> {
 pub fn unzip_and_process<P, F, T>(
     file_path: P,
     processor: F,
-) -> Result<RecordsCollection<T>, Box<dyn Error>>
+) -> Result<RecordsCollection<T>, ScraperError>
+where
+    P: AsRef<Path>,
+    F: Fn(&mut dyn Read) -> Result<RecordsCollection<T>, ScraperError>,
+    T: 'static + std::fmt::Display,
+{
+    unzip_and_process_with_policy(file_path, processor, EntryErrorPolicy::Strict)
+}
+
+/// Like [`unzip_and_process`], but handles entries that fail according to `policy`, so one
+/// bad file in a daily archive does not throw away the rest.
+pub fn unzip_and_process_with_policy<P, F, T>(
+    file_path: P,
+    processor: F,
+    policy: EntryErrorPolicy,
+) -> Result<RecordsCollection<T>, ScraperError>
 where
     P: AsRef<Path>,
-    F: Fn(&str) -> Result<Vec<T>, Box<dyn Error>>,
+    F: Fn(&mut dyn Read) -> Result<RecordsCollection<T>, ScraperError>,
     T: 'static + std::fmt::Display,
 {
     let start_time = Instant::now();
@@ -94,44 +302,256 @@ where
     let metadata = metadata(file_path.as_ref())?;
     let zipfile_size = metadata.len();
     let mut archive = ZipArchive::new(file)?;
-    let number_of_files = archive.len();
 
     let mut collection = RecordsCollection::<T>::new();
     collection.set_source_file(file_path.as_ref().to_string_lossy().into_owned());
     collection.set_zipfile_size(zipfile_size);
-    collection.set_number_of_files(number_of_files);
+    process_archive(&mut archive, "", &processor, policy, &mut collection)?;
+    collection.set_number_of_files(collection.record_sets.len());
+
+    let processing_time = start_time.elapsed().as_millis();
+    collection.set_processing_time(processing_time);
+
+    Ok(collection)
+}
+
+/// Streams the records of each archive entry to `consumer` as an iterator, so rows are parsed
+/// straight from the decompressing reader and never buffered as a whole file.
+pub fn unzip_and_stream<P, F, T>(file_path: P, consumer: F) -> Result<(), ScraperError>
+where
+    P: AsRef<Path>,
+    F: FnMut(&str, MmsRecords<ZipFile<'_>, T>) -> Result<(), ScraperError>,
+    T: FromDataRow,
+{
+    unzip_and_stream_with_policy(file_path, consumer, EntryErrorPolicy::Strict).map(|_| ())
+}
+
+/// Like [`unzip_and_stream`], but handles entries that fail according to `policy` and returns
+/// the ones it dropped.
+pub fn unzip_and_stream_with_policy<P, F, T>(
+    file_path: P,
+    mut consumer: F,
+    policy: EntryErrorPolicy,
+) -> Result<Vec<RejectedEntry>, ScraperError>
+where
+    P: AsRef<Path>,
+    F: FnMut(&str, MmsRecords<ZipFile<'_>, T>) -> Result<(), ScraperError>,
+    T: FromDataRow,
+{
+    let file = File::open(file_path.as_ref())?;
+    let mut archive = ZipArchive::new(file)?;
+    let mut rejected_entries = Vec::new();
+    stream_archive(
+        &mut archive,
+        "",
+        &mut consumer,
+        policy,
+        &mut rejected_entries,
+    )?;
+    Ok(rejected_entries)
+}
+
+/// NEMWEB `ARCHIVE` bundles are zips of zips, so an entry is either another archive to
+/// descend into or a CSV to process. Anything else, such as a README, is skipped.
+fn is_nested_zip(file_name: &str) -> bool {
+    file_name.to_ascii_lowercase().ends_with(".zip")
+}
+
+fn is_csv(file_name: &str) -> bool {
+    file_name.to_ascii_lowercase().ends_with(".csv")
+}
+
+/// Decompresses a nested zip entry into memory, since `ZipArchive` needs a seekable reader.
+/// Inner archives are single reports, so this stays small even for large bundles. The buffer
+/// is not sized from the entry header, which may be corrupt.
+fn open_nested_zip(file: &mut ZipFile) -> Result<ZipArchive<Cursor<Vec<u8>>>, ScraperError> {
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+    Ok(ZipArchive::new(Cursor::new(bytes))?)
+}
+
+fn entry_path(parent: &str, file_name: &str) -> String {
+    if parent.is_empty() {
+        file_name.to_string()
+    } else {
+        format!("{}/{}", parent, file_name)
+    }
+}
+
+/// Records an entry that failed, or returns the error if `policy` does not allow dropping it.
+fn reject_entry(
+    rejected_entries: &mut Vec<RejectedEntry>,
+    policy: EntryErrorPolicy,
+    file_name: &str,
+    error: ScraperError,
+) -> Result<(), ScraperError> {
+    let error = error.with_file_name(file_name);
+    let EntryErrorPolicy::Skip {
+        max_rejected_entries,
+    } = policy
+    else {
+        return Err(error);
+    };
 
-    for i in 0..number_of_files {
+    rejected_entries.push(RejectedEntry {
+        file_name: file_name.to_string(),
+        error,
+    });
+    if rejected_entries.len() > max_rejected_entries {
+        return Err(ScraperError::TooManyRejectedEntries {
+            rejected_entries: rejected_entries.len(),
+            max_rejected_entries,
+        });
+    }
+    Ok(())
+}
+
+/// The path of entry `index` when it cannot be opened, read from its header without
+/// decompressing, or its position if even the header is unreadable.
+fn unopened_entry_path<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    parent: &str,
+    index: usize,
+) -> String {
+    match archive.by_index_raw(index) {
+        Ok(file) => entry_path(parent, file.name()),
+        Err(_) => entry_path(parent, &format!("entry {}", index)),
+    }
+}
+
+/// Runs `processor` over every CSV in the archive, recursing into nested zips and skipping
+/// other entries.
+pub(crate) fn process_archive<R, F, T>(
+    archive: &mut ZipArchive<R>,
+    parent: &str,
+    processor: &F,
+    policy: EntryErrorPolicy,
+    collection: &mut RecordsCollection<T>,
+) -> Result<(), ScraperError>
+where
+    R: Read + Seek,
+    F: Fn(&mut dyn Read) -> Result<RecordsCollection<T>, ScraperError>,
+{
+    for i in 0..archive.len() {
+        // Checked before opening for real, since a failed `by_index` still holds the borrow
+        // needed to read the entry's name
+        let open_error = archive.by_index(i).err();
+        if let Some(e) = open_error {
+            let file_name = unopened_entry_path(archive, parent, i);
+            reject_entry(
+                &mut collection.rejected_entries,
+                policy,
+                &file_name,
+                e.into(),
+            )?;
+            continue;
+        }
         let mut file = archive.by_index(i)?;
-        let file_name = file.name().to_string();
+        if file.is_dir() {
+            continue;
+        }
+        let file_name = entry_path(parent, file.name());
 
-        println!("Processing file: {}", file_name);
+        if is_nested_zip(&file_name) {
+            match open_nested_zip(&mut file) {
+                Ok(mut nested) => {
+                    process_archive(&mut nested, &file_name, processor, policy, collection)?
+                }
+                Err(e) => reject_entry(&mut collection.rejected_entries, policy, &file_name, e)?,
+            }
+            continue;
+        }
+        if !is_csv(&file_name) {
+            continue;
+        }
 
-        let mut contents = String::new();
-        file.read_to_string(&mut contents)?;
+        println!("Processing file: {}", file_name);
 
-        match processor(&contents) {
+        match processor(&mut file) {
             Ok(result) => {
                 println!("Successfully processed {}", file_name);
-                collection.add_records(result);
+                collection.append(&file_name, result);
             }
-            Err(e) => println!("Error processing {}: {}", file_name, e),
+            Err(e) => reject_entry(&mut collection.rejected_entries, policy, &file_name, e)?,
         }
     }
 
-    let processing_time = start_time.elapsed().as_millis();
-    collection.set_processing_time(processing_time);
+    Ok(())
+}
 
-    Ok(collection)
+/// Passes a record iterator for every CSV in the archive to `consumer`, recursing into
+/// nested zips. Entries that fail are handled according to `policy`; a CSV the consumer fails
+/// on has already been streamed up to the failure.
+fn stream_archive<R, F, T>(
+    archive: &mut ZipArchive<R>,
+    parent: &str,
+    consumer: &mut F,
+    policy: EntryErrorPolicy,
+    rejected_entries: &mut Vec<RejectedEntry>,
+) -> Result<(), ScraperError>
+where
+    R: Read + Seek,
+    F: FnMut(&str, MmsRecords<ZipFile<'_>, T>) -> Result<(), ScraperError>,
+    T: FromDataRow,
+{
+    for i in 0..archive.len() {
+        // Checked before opening for real, since a failed `by_index` still holds the borrow
+        // needed to read the entry's name
+        let open_error = archive.by_index(i).err();
+        if let Some(e) = open_error {
+            let file_name = unopened_entry_path(archive, parent, i);
+            reject_entry(rejected_entries, policy, &file_name, e.into())?;
+            continue;
+        }
+        let mut file = archive.by_index(i)?;
+        if file.is_dir() {
+            continue;
+        }
+        let file_name = entry_path(parent, file.name());
+
+        if is_nested_zip(&file_name) {
+            match open_nested_zip(&mut file) {
+                Ok(mut nested) => {
+                    stream_archive(&mut nested, &file_name, consumer, policy, rejected_entries)?
+                }
+                Err(e) => reject_entry(rejected_entries, policy, &file_name, e)?,
+            }
+            continue;
+        }
+        if !is_csv(&file_name) {
+            continue;
+        }
+
+        if let Err(e) = consumer(&file_name, MmsCsvReader::new(file).records()) {
+            reject_entry(rejected_entries, policy, &file_name, e)?;
+        }
+    }
+
+    Ok(())
 }
 
 pub async fn unzip_and_process_from_url<F, T>(
     base_url: &str,
     path: &str,
     processor: F,
-) -> Result<RecordsCollection<T>, Box<dyn Error>>
+) -> Result<RecordsCollection<T>, ScraperError>
+where
+    F: Fn(&mut dyn Read) -> Result<RecordsCollection<T>, ScraperError> + Send + Sync + 'static,
+    T: 'static + Display + Send + Sync,
+{
+    unzip_and_process_from_url_with_policy(base_url, path, processor, EntryErrorPolicy::Strict)
+        .await
+}
+
+/// Like [`unzip_and_process_from_url`], but handles entries that fail according to `policy`.
+pub async fn unzip_and_process_from_url_with_policy<F, T>(
+    base_url: &str,
+    path: &str,
+    processor: F,
+    policy: EntryErrorPolicy,
+) -> Result<RecordsCollection<T>, ScraperError>
 where
-    F: Fn(&str) -> Result<Vec<T>, Box<dyn Error>> + Send + Sync + 'static,
+    F: Fn(&mut dyn Read) -> Result<RecordsCollection<T>, ScraperError> + Send + Sync + 'static,
     T: 'static + Display + Send + Sync,
 {
     let start_time = Instant::now();
@@ -145,34 +565,368 @@ where
     let reader = Cursor::new(bytes);
     let mut archive = ZipArchive::new(reader)?;
 
-    let number_of_files = archive.len();
-
     let mut collection = RecordsCollection::<T>::new();
     collection.set_source_file(path.to_string());
     collection.set_zipfile_size(zipfile_size);
-    collection.set_number_of_files(number_of_files);
+    process_archive(&mut archive, "", &processor, policy, &mut collection)?;
+    collection.set_number_of_files(collection.record_sets.len());
 
-    for i in 0..number_of_files {
-        let mut file = archive.by_index(i)?;
-        let file_name = file.name().to_string();
+    let processing_time = start_time.elapsed().as_millis();
+    collection.set_processing_time(processing_time);
 
-        println!("Processing file: {}", file_name);
+    Ok(collection)
+}
 
-        let mut contents = String::new();
-        // TODO: stream instead of reading all contents
-        file.read_to_string(&mut contents)?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::nem_current_rooftop_pv_actual::RecordCurrentRooftopPvActual;
+    use crate::models::nem_current_tradingis_report::{
+        process_file_current_trading_is, stream_file_current_trading_is, RecordCurrentTradingIs,
+    };
 
-        match processor(&contents) {
-            Ok(result) => {
-                println!("Successfully processed {}", file_name);
-                collection.add_records(result);
+    const TRADING_IS_WITH_BAD_ROWS: &str = "C,NEMP.WORLD,TRADINGIS,AEMO,PUBLIC,2024/03/03,13:30:11,0000000412683134,TRADINGIS,0000000412683133\n\
+        I,TRADING,INTERCONNECTORRES,2,SETTLEMENTDATE,RUNNO,INTERCONNECTORID,PERIODID,METEREDMWFLOW,MWFLOW,MWLOSSES,LASTCHANGED\n\
+        D,TRADING,INTERCONNECTORRES,2,\"2024/03/03 13:35:00\",1,N-Q-MNSP1,163,36.2,17,1.36,\"2024/03/03 13:30:04\"\n\
+        D,TRADING,INTERCONNECTORRES,2,\"not a date\",1,NSW1-QLD1,163,587,464.75,30.31,\"2024/03/03 13:30:04\"\n\
+        I,TRADING,UNKNOWN,1,VALUE\n\
+        D,TRADING,UNKNOWN,1,x\n\
+        D,TRADING,INTERCONNECTORRES,2,\"2024/03/03 13:35:00\",1,V-SA,163,-224.44,-202.19,4.53,\"2024/03/03 13:30:04\"\n\
+        C,\"END OF REPORT\",8\n";
+
+    #[test]
+    fn test_unzip_and_process_tradingis_fixture() {
+        let path = "src/fixtures/PUBLIC_TRADINGIS_202403031335_0000000412683134.zip";
+        let collection = unzip_and_process(path, |reader| process_file_current_trading_is(reader))
+            .expect("Failed to process zip");
+
+        assert_eq!(collection.records.len(), 11);
+        assert_eq!(collection.number_of_files, Some(1));
+        assert_eq!(
+            collection.report_header.map(|header| header.report_id),
+            Some("0000000412683134".to_string())
+        );
+        assert_eq!(collection.source_file.as_deref(), Some(path));
+    }
+
+    #[test]
+    fn test_strict_policy_fails_on_first_bad_row() {
+        let records = stream_file_current_trading_is(TRADING_IS_WITH_BAD_ROWS.as_bytes());
+        let result = RecordsCollection::from_records_with_policy(records, RowErrorPolicy::Strict);
+
+        match result {
+            Err(ScraperError::DateTime { location, .. }) => assert_eq!(location.line, Some(4)),
+            other => panic!("Expected a datetime error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_skip_policy_drops_bad_rows() {
+        let records = stream_file_current_trading_is(TRADING_IS_WITH_BAD_ROWS.as_bytes());
+        let policy = RowErrorPolicy::Skip {
+            max_rejected_rows: 2,
+        };
+        let collection = RecordsCollection::from_records_with_policy(records, policy).unwrap();
+
+        assert_eq!(collection.records.len(), 2);
+        assert_eq!(collection.rejected_row_count, 2);
+        assert!(collection.rejected_rows.is_empty());
+        assert!(collection.report_header.is_some());
+    }
+
+    #[test]
+    fn test_quarantine_policy_keeps_bad_rows() {
+        let records = stream_file_current_trading_is(TRADING_IS_WITH_BAD_ROWS.as_bytes());
+        let policy = RowErrorPolicy::Quarantine {
+            max_rejected_rows: 2,
+        };
+        let collection = RecordsCollection::from_records_with_policy(records, policy).unwrap();
+
+        assert_eq!(collection.records.len(), 2);
+        assert_eq!(collection.rejected_rows.len(), 2);
+        assert_eq!(collection.rejected_rows[0].line, Some(4));
+        assert_eq!(collection.rejected_rows[0].fields[4], "not a date");
+        assert!(matches!(
+            collection.rejected_rows[1].error,
+            ScraperError::UnknownTable { .. }
+        ));
+    }
+
+    #[test]
+    fn test_rejected_row_threshold_fails_file() {
+        let records = stream_file_current_trading_is(TRADING_IS_WITH_BAD_ROWS.as_bytes());
+        let policy = RowErrorPolicy::Quarantine {
+            max_rejected_rows: 1,
+        };
+        let result = RecordsCollection::from_records_with_policy(records, policy);
+
+        match result {
+            Err(ScraperError::TooManyRejectedRows {
+                location,
+                rejected_rows,
+                max_rejected_rows,
+            }) => {
+                assert_eq!(location.line, Some(6));
+                assert_eq!(rejected_rows, 2);
+                assert_eq!(max_rejected_rows, 1);
             }
-            Err(e) => println!("Error processing {}: {}", file_name, e),
+            other => panic!("Expected too many rejected rows, got {:?}", other),
         }
     }
 
-    let processing_time = start_time.elapsed().as_millis();
-    collection.set_processing_time(processing_time);
+    #[test]
+    fn test_unzip_and_stream_dvd_rooftop_fixture() {
+        let path = "src/fixtures/PUBLIC_DVD_ROOFTOP_PV_ACTUAL_201912010000.zip";
+        let mut file_names = Vec::new();
+        let mut count = 0;
+        unzip_and_stream(path, |file_name, records| {
+            file_names.push(file_name.to_string());
+            for record in records {
+                let _: RecordCurrentRooftopPvActual = record?;
+                count += 1;
+            }
+            Ok(())
+        })
+        .expect("Failed to stream zip");
 
-    Ok(collection)
+        assert_eq!(
+            file_names,
+            ["PUBLIC_DVD_ROOFTOP_PV_ACTUAL_201912010000.CSV"]
+        );
+        assert_eq!(count, 30720);
+    }
+
+    /// Builds an ARCHIVE style bundle in memory: a daily zip of report zips, one of which is
+    /// itself nested, plus a bare CSV, a README and a directory entry.
+    fn nested_archive() -> ZipArchive<Cursor<Vec<u8>>> {
+        use std::io::Write;
+        use zip::write::{FileOptions, ZipWriter};
+
+        let inner_zip =
+            std::fs::read("src/fixtures/PUBLIC_TRADINGIS_202403031335_0000000412683134.zip")
+                .unwrap();
+        let csv = std::fs::read("src/fixtures/PUBLIC_TRADINGIS_202403031335_0000000412683134.CSV")
+            .unwrap();
+
+        let mut middle = ZipWriter::new(Cursor::new(Vec::new()));
+        middle
+            .start_file("PUBLIC_TRADINGIS_INNER.zip", FileOptions::default())
+            .unwrap();
+        middle.write_all(&inner_zip).unwrap();
+        let middle = middle.finish().unwrap().into_inner();
+
+        let mut outer = ZipWriter::new(Cursor::new(Vec::new()));
+        outer
+            .add_directory("reports/", FileOptions::default())
+            .unwrap();
+        outer
+            .start_file("reports/PUBLIC_TRADINGIS_A.zip", FileOptions::default())
+            .unwrap();
+        outer.write_all(&inner_zip).unwrap();
+        outer
+            .start_file("README.txt", FileOptions::default())
+            .unwrap();
+        outer.write_all(b"Daily TradingIS bundle").unwrap();
+        outer
+            .start_file("PUBLIC_TRADINGIS_B.ZIP", FileOptions::default())
+            .unwrap();
+        outer.write_all(&middle).unwrap();
+        outer
+            .start_file("PUBLIC_TRADINGIS_C.CSV", FileOptions::default())
+            .unwrap();
+        outer.write_all(&csv).unwrap();
+        let outer = outer.finish().unwrap();
+
+        ZipArchive::new(outer).unwrap()
+    }
+
+    #[test]
+    fn test_process_nested_archive() {
+        let mut collection = RecordsCollection::new();
+        process_archive(
+            &mut nested_archive(),
+            "",
+            &|reader: &mut dyn Read| process_file_current_trading_is(reader),
+            EntryErrorPolicy::Strict,
+            &mut collection,
+        )
+        .expect("Failed to process nested zip");
+
+        let file_names: Vec<&str> = collection
+            .record_sets
+            .iter()
+            .map(|set| set.file_name.as_str())
+            .collect();
+        assert_eq!(
+            file_names,
+            [
+                "reports/PUBLIC_TRADINGIS_A.zip/PUBLIC_TRADINGIS_202403031335_0000000412683134.CSV",
+                "PUBLIC_TRADINGIS_B.ZIP/PUBLIC_TRADINGIS_INNER.zip/PUBLIC_TRADINGIS_202403031335_0000000412683134.CSV",
+                "PUBLIC_TRADINGIS_C.CSV",
+            ]
+        );
+        assert_eq!(collection.records.len(), 33);
+        for (set, records) in collection.records_by_file() {
+            assert_eq!(records.len(), 11);
+            assert!(set.report_header.is_some());
+        }
+        assert_eq!(collection.record_sets[2].records, 22..33);
+    }
+
+    #[test]
+    fn test_stream_nested_archive() {
+        let mut counts = Vec::new();
+        stream_archive(
+            &mut nested_archive(),
+            "",
+            &mut |file_name, records| {
+                let mut count = 0;
+                for record in records {
+                    let _: RecordCurrentTradingIs = record?;
+                    count += 1;
+                }
+                counts.push((file_name.to_string(), count));
+                Ok(())
+            },
+            EntryErrorPolicy::Strict,
+            &mut Vec::new(),
+        )
+        .expect("Failed to stream nested zip");
+
+        assert_eq!(counts.len(), 3);
+        assert!(counts.iter().all(|(_, count)| *count == 11));
+        assert_eq!(counts[2].0, "PUBLIC_TRADINGIS_C.CSV");
+    }
+
+    /// A bundle with one good report, one truncated CSV, one corrupt nested zip and one CSV
+    /// compressed with a method the zip crate cannot open.
+    fn archive_with_bad_entries() -> ZipArchive<Cursor<Vec<u8>>> {
+        use std::io::Write;
+        use zip::write::{FileOptions, ZipWriter};
+
+        let csv = std::fs::read("src/fixtures/PUBLIC_TRADINGIS_202403031335_0000000412683134.CSV")
+            .unwrap();
+        let truncated = &csv[..csv.len() / 2];
+
+        let mut archive = ZipWriter::new(Cursor::new(Vec::new()));
+        archive
+            .start_file("PUBLIC_TRADINGIS_A.CSV", FileOptions::default())
+            .unwrap();
+        archive.write_all(&csv).unwrap();
+        archive
+            .start_file("PUBLIC_TRADINGIS_B.CSV", FileOptions::default())
+            .unwrap();
+        archive.write_all(truncated).unwrap();
+        archive
+            .start_file("PUBLIC_TRADINGIS_C.zip", FileOptions::default())
+            .unwrap();
+        archive.write_all(b"not a zip").unwrap();
+        archive
+            .start_file("PUBLIC_TRADINGIS_D.CSV", FileOptions::default())
+            .unwrap();
+        archive.write_all(&csv).unwrap();
+        let mut bytes = archive.finish().unwrap().into_inner();
+
+        // Mark the last central directory entry as compressed with unknown method 200
+        let central = bytes
+            .windows(4)
+            .rposition(|window| window == b"PK\x01\x02")
+            .unwrap();
+        bytes[central + 10..central + 12].copy_from_slice(&200u16.to_le_bytes());
+        ZipArchive::new(Cursor::new(bytes)).unwrap()
+    }
+
+    fn process_with_policy(
+        policy: EntryErrorPolicy,
+    ) -> Result<RecordsCollection<RecordCurrentTradingIs>, ScraperError> {
+        let mut collection = RecordsCollection::new();
+        process_archive(
+            &mut archive_with_bad_entries(),
+            "",
+            &|reader: &mut dyn Read| process_file_current_trading_is(reader),
+            policy,
+            &mut collection,
+        )?;
+        Ok(collection)
+    }
+
+    #[test]
+    fn test_strict_entry_policy_fails_archive() {
+        match process_with_policy(EntryErrorPolicy::Strict) {
+            Err(ScraperError::ReportTruncated { location, .. }) => assert_eq!(
+                location.file_name.as_deref(),
+                Some("PUBLIC_TRADINGIS_B.CSV")
+            ),
+            other => panic!("Expected a truncated report, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_skip_entry_policy_keeps_good_entries() {
+        let collection = process_with_policy(EntryErrorPolicy::Skip {
+            max_rejected_entries: 3,
+        })
+        .unwrap();
+
+        assert_eq!(collection.records.len(), 11);
+        assert_eq!(collection.record_sets.len(), 1);
+        let rejected: Vec<&str> = collection
+            .rejected_entries
+            .iter()
+            .map(|entry| entry.file_name.as_str())
+            .collect();
+        assert_eq!(
+            rejected,
+            [
+                "PUBLIC_TRADINGIS_B.CSV",
+                "PUBLIC_TRADINGIS_C.zip",
+                "PUBLIC_TRADINGIS_D.CSV"
+            ]
+        );
+        assert!(matches!(
+            collection.rejected_entries[1].error,
+            ScraperError::Zip(_)
+        ));
+    }
+
+    #[test]
+    fn test_skip_entry_policy_when_streaming() {
+        let mut streamed = Vec::new();
+        let mut rejected_entries = Vec::new();
+        stream_archive(
+            &mut archive_with_bad_entries(),
+            "",
+            &mut |file_name, records| {
+                for record in records {
+                    let _: RecordCurrentTradingIs = record?;
+                }
+                streamed.push(file_name.to_string());
+                Ok(())
+            },
+            EntryErrorPolicy::Skip {
+                max_rejected_entries: 3,
+            },
+            &mut rejected_entries,
+        )
+        .expect("Failed to stream archive");
+
+        assert_eq!(streamed, ["PUBLIC_TRADINGIS_A.CSV"]);
+        assert_eq!(rejected_entries.len(), 3);
+        assert!(matches!(rejected_entries[2].error, ScraperError::Zip(_)));
+    }
+
+    #[test]
+    fn test_rejected_entry_threshold_fails_archive() {
+        let result = process_with_policy(EntryErrorPolicy::Skip {
+            max_rejected_entries: 1,
+        });
+
+        assert!(matches!(
+            result,
+            Err(ScraperError::TooManyRejectedEntries {
+                rejected_entries: 2,
+                max_rejected_entries: 1,
+            })
+        ));
+    }
 }
@@ -0,0 +1,132 @@
+use csv::{DeserializeErrorKind, StringRecord};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::io::Read;
+
+use crate::common::unzip_process::{RecordsCollection, RowErrorPolicy};
+use crate::error::{Location, ScraperError};
+use crate::time::datetimezone_conversion::INVALID_DATETIME;
+
+/// How a WEM data file is encoded. AEMO WA publishes each dataset both as CSV with a header
+/// row and as a JSON array of objects keyed by the same column names.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WemFormat {
+    Csv,
+    Json,
+}
+
+impl WemFormat {
+    /// Picks the format from a file name or URL path, defaulting to CSV.
+    pub fn from_path(path: &str) -> Self {
+        if path.to_ascii_lowercase().ends_with(".json") {
+            WemFormat::Json
+        } else {
+            WemFormat::Csv
+        }
+    }
+}
+
+/// Reads every record of a WEM file. Unlike MMS reports there are no `C`/`I`/`D` rows, so
+/// the whole file is one table.
+pub fn process_wem_file<T, R>(
+    reader: R,
+    format: WemFormat,
+) -> Result<RecordsCollection<T>, ScraperError>
+where
+    T: DeserializeOwned,
+    R: Read,
+{
+    process_wem_file_with_policy(reader, format, RowErrorPolicy::Strict)
+}
+
+/// Like [`process_wem_file`], but handles CSV rows and JSON elements that do not fit `T`
+/// according to `policy`. A file that is not valid CSV or JSON still fails as a whole.
+pub fn process_wem_file_with_policy<T, R>(
+    reader: R,
+    format: WemFormat,
+    policy: RowErrorPolicy,
+) -> Result<RecordsCollection<T>, ScraperError>
+where
+    T: DeserializeOwned,
+    R: Read,
+{
+    let mut collection = RecordsCollection::new();
+    match format {
+        WemFormat::Csv => {
+            let mut csv_reader = csv::Reader::from_reader(reader);
+            let headers = csv_reader.headers()?.clone();
+            for record in csv_reader.records() {
+                let record = record?;
+                match record.deserialize::<T>(Some(&headers)) {
+                    Ok(row) => collection.records.push(row),
+                    Err(err) => policy.reject(
+                        csv_row_error(err, &headers),
+                        || record.iter().map(str::to_string).collect(),
+                        &mut collection.rejected_row_count,
+                        &mut collection.rejected_rows,
+                    )?,
+                }
+            }
+        }
+        WemFormat::Json => {
+            let elements: Vec<Value> = serde_json::from_reader(reader)?;
+            for element in &elements {
+                match T::deserialize(element) {
+                    Ok(row) => collection.records.push(row),
+                    Err(err) => policy.reject(
+                        row_error(Location::default(), err.to_string()),
+                        || json_fields(element),
+                        &mut collection.rejected_row_count,
+                        &mut collection.rejected_rows,
+                    )?,
+                }
+            }
+        }
+    }
+    Ok(collection)
+}
+
+/// Turns a CSV row that does not fit the model into a row error, keeping the row's line and,
+/// when csv knows it, the column.
+fn csv_row_error(err: csv::Error, headers: &StringRecord) -> ScraperError {
+    let line = err.position().map(|position| position.line());
+    let csv::ErrorKind::Deserialize { err: de_err, .. } = err.kind() else {
+        return err.into();
+    };
+    let location = Location {
+        line,
+        column: de_err
+            .field()
+            .and_then(|field| headers.get(field as usize))
+            .map(str::to_string),
+        ..Default::default()
+    };
+    let message = match de_err.kind() {
+        DeserializeErrorKind::Message(message) => message.clone(),
+        kind => kind.to_string(),
+    };
+    row_error(location, message)
+}
+
+fn row_error(location: Location, message: String) -> ScraperError {
+    let location = Box::new(location);
+    match message.strip_prefix(INVALID_DATETIME) {
+        Some(value) => ScraperError::DateTime {
+            location,
+            value: value.to_string(),
+        },
+        None => ScraperError::SchemaMismatch { location, message },
+    }
+}
+
+/// The values of a JSON element, for quarantining it.
+fn json_fields(element: &Value) -> Vec<String> {
+    let field = |value: &Value| match value {
+        Value::String(value) => value.clone(),
+        value => value.to_string(),
+    };
+    match element {
+        Value::Object(object) => object.values().map(field).collect(),
+        element => vec![field(element)],
+    }
+}
@@ -0,0 +1,295 @@
+use reqwest::Error as ReqwestError;
+use std::error::Error;
+use std::fmt;
+use std::io::Error as IoError;
+use zip::result::ZipError;
+
+use crate::common::mms_csv::TableKey;
+use crate::parsers::url::ZipReportUrlParseError;
+
+/// Where in a report an error happened. Every part is optional because errors are raised at
+/// different layers: the CSV reader knows the line and table, deserialization knows the column
+/// and the unzip layer fills in the file name.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Location {
+    pub file_name: Option<String>,
+    pub line: Option<u64>,
+    pub table: Option<TableKey>,
+    pub column: Option<String>,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(file_name) = &self.file_name {
+            parts.push(format!("file {}", file_name));
+        }
+        if let Some(line) = self.line {
+            parts.push(format!("line {}", line));
+        }
+        if let Some(table) = &self.table {
+            parts.push(format!("table {}", table));
+        }
+        if let Some(column) = &self.column {
+            parts.push(format!("column {}", column));
+        }
+        if parts.is_empty() {
+            write!(f, "unknown location")
+        } else {
+            write!(f, "{}", parts.join(", "))
+        }
+    }
+}
+
+/// Crate-wide error type.
+#[derive(Debug)]
+pub enum ScraperError {
+    Http(ReqwestError),
+    Io(IoError),
+    Zip(ZipError),
+    UrlParse(ZipReportUrlParseError),
+    Json(serde_json::Error),
+    Csv {
+        location: Box<Location>,
+        source: csv::Error,
+    },
+    /// A row is well formed CSV but does not fit the model, e.g. a missing column or a value
+    /// that is not a number.
+    SchemaMismatch {
+        location: Box<Location>,
+        message: String,
+    },
+    DateTime {
+        location: Box<Location>,
+        value: String,
+    },
+    /// A `D` row for a table the processor has no model for.
+    UnknownTable {
+        location: Box<Location>,
+    },
+    UnsupportedVersion {
+        location: Box<Location>,
+        version: u32,
+    },
+    /// The `C,"END OF REPORT",<line count>` trailer is missing (`expected_lines` is `None`),
+    /// does not match the number of lines read, or is followed by more rows.
+    ReportTruncated {
+        location: Box<Location>,
+        expected_lines: Option<u64>,
+        actual_lines: u64,
+    },
+    InvalidLine {
+        location: Box<Location>,
+    },
+    /// More rows were rejected under a lenient [`RowErrorPolicy`] than it allows.
+    ///
+    /// [`RowErrorPolicy`]: crate::common::unzip_process::RowErrorPolicy
+    TooManyRejectedRows {
+        location: Box<Location>,
+        rejected_rows: usize,
+        max_rejected_rows: usize,
+    },
+    /// More archive entries were dropped under [`EntryErrorPolicy::Skip`] than it allows.
+    ///
+    /// [`EntryErrorPolicy::Skip`]: crate::common::unzip_process::EntryErrorPolicy::Skip
+    TooManyRejectedEntries {
+        rejected_entries: usize,
+        max_rejected_entries: usize,
+    },
+    /// A market notice is missing a header field or has one that cannot be read.
+    InvalidMarketNotice(String),
+}
+
+impl ScraperError {
+    /// The location of the failure, if the error came from reading a report.
+    pub fn location(&self) -> Option<&Location> {
+        match self {
+            ScraperError::Csv { location, .. }
+            | ScraperError::SchemaMismatch { location, .. }
+            | ScraperError::DateTime { location, .. }
+            | ScraperError::UnknownTable { location }
+            | ScraperError::UnsupportedVersion { location, .. }
+            | ScraperError::ReportTruncated { location, .. }
+            | ScraperError::InvalidLine { location }
+            | ScraperError::TooManyRejectedRows { location, .. } => Some(location.as_ref()),
+            _ => None,
+        }
+    }
+
+    /// Records the archive entry or file the error came from.
+    pub fn with_file_name(mut self, file_name: &str) -> Self {
+        match &mut self {
+            ScraperError::Csv { location, .. }
+            | ScraperError::SchemaMismatch { location, .. }
+            | ScraperError::DateTime { location, .. }
+            | ScraperError::UnknownTable { location }
+            | ScraperError::UnsupportedVersion { location, .. }
+            | ScraperError::ReportTruncated { location, .. }
+            | ScraperError::InvalidLine { location }
+            | ScraperError::TooManyRejectedRows { location, .. } => {
+                location.file_name = Some(file_name.to_string())
+            }
+            _ => {}
+        }
+        self
+    }
+
+    /// True for errors confined to a single row, after which the rest of the file can still
+    /// be read.
+    pub fn is_row_error(&self) -> bool {
+        matches!(
+            self,
+            ScraperError::SchemaMismatch { .. }
+                | ScraperError::DateTime { .. }
+                | ScraperError::UnknownTable { .. }
+                | ScraperError::UnsupportedVersion { .. }
+                | ScraperError::InvalidLine { .. }
+        )
+    }
+}
+
+impl fmt::Display for ScraperError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScraperError::Http(err) => write!(f, "HTTP error: {}", err),
+            ScraperError::Io(err) => write!(f, "IO error: {}", err),
+            ScraperError::Zip(err) => write!(f, "Zip error: {}", err),
+            ScraperError::UrlParse(err) => write!(f, "URL parse error: {}", err),
+            ScraperError::Json(err) => write!(f, "JSON error: {}", err),
+            ScraperError::Csv { location, source } => {
+                write!(f, "CSV error at {}: {}", location, source)
+            }
+            ScraperError::SchemaMismatch { location, message } => {
+                write!(f, "Schema mismatch at {}: {}", location, message)
+            }
+            ScraperError::DateTime { location, value } => {
+                write!(f, "Invalid datetime {:?} at {}", value, location)
+            }
+            ScraperError::UnknownTable { location } => write!(f, "Unknown table at {}", location),
+            ScraperError::UnsupportedVersion { location, version } => {
+                write!(f, "Unsupported table version {} at {}", version, location)
+            }
+            ScraperError::ReportTruncated {
+                location,
+                expected_lines: Some(expected),
+                actual_lines,
+            } => write!(
+                f,
+                "Report truncated at {}: END OF REPORT declares {} lines but {} were read",
+                location, expected, actual_lines
+            ),
+            ScraperError::ReportTruncated {
+                location,
+                expected_lines: None,
+                actual_lines,
+            } => write!(
+                f,
+                "Report truncated at {}: no END OF REPORT trailer after {} lines",
+                location, actual_lines
+            ),
+            ScraperError::InvalidLine { location } => {
+                write!(f, "Invalid line format at {}", location)
+            }
+            ScraperError::TooManyRejectedRows {
+                location,
+                rejected_rows,
+                max_rejected_rows,
+            } => write!(
+                f,
+                "Too many rejected rows at {}: {} rejected, at most {} allowed",
+                location, rejected_rows, max_rejected_rows
+            ),
+            ScraperError::TooManyRejectedEntries {
+                rejected_entries,
+                max_rejected_entries,
+            } => write!(
+                f,
+                "Too many rejected archive entries: {} rejected, at most {} allowed",
+                rejected_entries, max_rejected_entries
+            ),
+            ScraperError::InvalidMarketNotice(message) => {
+                write!(f, "Invalid market notice: {}", message)
+            }
+        }
+    }
+}
+
+impl Error for ScraperError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ScraperError::Http(err) => Some(err),
+            ScraperError::Io(err) => Some(err),
+            ScraperError::Zip(err) => Some(err),
+            ScraperError::UrlParse(err) => Some(err),
+            ScraperError::Json(err) => Some(err),
+            ScraperError::Csv { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<ReqwestError> for ScraperError {
+    fn from(err: ReqwestError) -> ScraperError {
+        ScraperError::Http(err)
+    }
+}
+
+impl From<IoError> for ScraperError {
+    fn from(err: IoError) -> ScraperError {
+        ScraperError::Io(err)
+    }
+}
+
+impl From<ZipError> for ScraperError {
+    fn from(err: ZipError) -> ScraperError {
+        ScraperError::Zip(err)
+    }
+}
+
+impl From<ZipReportUrlParseError> for ScraperError {
+    fn from(err: ZipReportUrlParseError) -> ScraperError {
+        ScraperError::UrlParse(err)
+    }
+}
+
+impl From<serde_json::Error> for ScraperError {
+    fn from(err: serde_json::Error) -> ScraperError {
+        ScraperError::Json(err)
+    }
+}
+
+impl From<csv::Error> for ScraperError {
+    fn from(err: csv::Error) -> ScraperError {
+        let line = err.position().map(|position| position.line());
+        ScraperError::Csv {
+            location: Box::new(Location {
+                line,
+                ..Default::default()
+            }),
+            source: err,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_includes_location() {
+        let err = ScraperError::UnsupportedVersion {
+            location: Box::new(Location {
+                line: Some(2),
+                table: Some(TableKey::new("TRADING", "PRICE")),
+                ..Default::default()
+            }),
+            version: 9,
+        }
+        .with_file_name("PUBLIC_TRADINGIS.CSV");
+
+        assert_eq!(
+            err.to_string(),
+            "Unsupported table version 9 at file PUBLIC_TRADINGIS.CSV, line 2, table TRADING.PRICE"
+        );
+    }
+}
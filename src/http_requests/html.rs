@@ -1,33 +1,14 @@
 use reqwest::Client;
-use reqwest::Error as ReqwestError;
-use std::io::Error as IoError;
 use std::time::Duration;
 
-// Assuming you have a custom error type in your application
-pub enum HttpError {
-    Reqwest(ReqwestError),
-    Io(IoError),
-    MimeType(String),
-}
-
-impl From<ReqwestError> for HttpError {
-    fn from(err: ReqwestError) -> HttpError {
-        HttpError::Reqwest(err)
-    }
-}
-
-impl From<IoError> for HttpError {
-    fn from(err: IoError) -> HttpError {
-        HttpError::Io(err)
-    }
-}
+use crate::error::ScraperError;
 
 // Define an asynchronous function to fetch HTML content with additional parameters
 pub async fn fetch_html_content(
     base_url: &str,
     path: &str,
     user_agent: &str,
-) -> Result<String, ReqwestError> {
+) -> Result<String, ScraperError> {
     // Combine the base URL with the provided path
     let url = format!("{}{}", base_url, path);
 
@@ -44,7 +25,7 @@ pub async fn fetch_html_content(
         let html_content = response.text().await?;
         Ok(html_content)
     } else {
-        Err(response.error_for_status().unwrap_err())
+        Err(response.error_for_status().unwrap_err().into())
     }
 }
 
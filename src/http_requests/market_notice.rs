@@ -0,0 +1,121 @@
+use crate::error::ScraperError;
+use crate::http_requests::html::fetch_html_content;
+use crate::parsers::html::MarketNoticeLinkExtractorFromHtml;
+use crate::parsers::market_notice::{MarketNotice, MarketNoticeLink, MarketNoticeParser};
+
+/// Fetches the notice listing at `path`, e.g. `/Reports/Current/Market_Notice/`, then fetches
+/// and parses each linked notice that `filter` accepts, in listing order.
+///
+/// The listing holds thousands of notices, so `filter` should narrow it, e.g. to one date or
+/// to notice IDs above the last one stored. Only a failure to fetch the listing fails the
+/// call; each notice comes back with its own result, so one bad notice does not lose the rest.
+pub async fn fetch_market_notices<F>(
+    base_url: &str,
+    path: &str,
+    user_agent: &str,
+    filter: F,
+) -> Result<Vec<(MarketNoticeLink, Result<MarketNotice, ScraperError>)>, ScraperError>
+where
+    F: Fn(&MarketNoticeLink) -> bool,
+{
+    let html_content = fetch_html_content(base_url, path, user_agent).await?;
+    let links = MarketNoticeLinkExtractorFromHtml::new().extract_notice_links(&html_content);
+
+    let parser = MarketNoticeParser::new();
+    let mut notices = Vec::new();
+    for link in links.into_iter().filter(|link| filter(link)) {
+        let notice = match fetch_html_content(base_url, &link.path, user_agent).await {
+            Ok(text) => parser.parse(&text),
+            Err(e) => Err(e),
+        };
+        notices.push((link, notice));
+    }
+    Ok(notices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::StatusCode;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn notice(notice_id: u64, subject: &str) -> String {
+        format!(
+            "Creation Date :     03/03/2024     17:45:10\n\
+            Notice ID               :         {}\n\
+            Notice Type ID          :         RESERVE NOTICE\n\
+            External Reference      :         {}\n\
+            Reason :\n\
+            \n\
+            {}\n\
+            END OF REPORT\n",
+            notice_id, subject, subject
+        )
+    }
+
+    #[tokio::test]
+    async fn test_fetch_market_notices() {
+        let mock_server = MockServer::start().await;
+        let listing = "<a HREF=\"/Reports/Current/\">[To Parent Directory]</a>\
+            <a HREF=\"/Reports/Current/Market_Notice/NEMITWEB1_MKTNOTICE_20240303.R116005\">x</a>\
+            <a HREF=\"/Reports/Current/Market_Notice/NEMITWEB1_MKTNOTICE_20240303.R116006\">y</a>\
+            <a HREF=\"/Reports/Current/Market_Notice/NEMITWEB1_MKTNOTICE_20240303.R116007\">z</a>";
+        Mock::given(method("GET"))
+            .and(path("/Reports/Current/Market_Notice/"))
+            .respond_with(ResponseTemplate::new(StatusCode::OK).set_body_string(listing))
+            .mount(&mock_server)
+            .await;
+        for (notice_id, subject) in [
+            (
+                116005,
+                "Actual Lack Of Reserve Level 1 (LOR1) in the NSW Region",
+            ),
+            (
+                116006,
+                "Cancellation of Actual (LOR1) condition in the NSW Region",
+            ),
+        ] {
+            Mock::given(method("GET"))
+                .and(path(format!(
+                    "/Reports/Current/Market_Notice/NEMITWEB1_MKTNOTICE_20240303.R{}",
+                    notice_id
+                )))
+                .respond_with(
+                    ResponseTemplate::new(StatusCode::OK)
+                        .set_body_string(notice(notice_id, subject)),
+                )
+                .mount(&mock_server)
+                .await;
+        }
+        Mock::given(method("GET"))
+            .and(path(
+                "/Reports/Current/Market_Notice/NEMITWEB1_MKTNOTICE_20240303.R116007",
+            ))
+            .respond_with(ResponseTemplate::new(StatusCode::OK).set_body_string("Not a notice"))
+            .mount(&mock_server)
+            .await;
+
+        let notices = fetch_market_notices(
+            &mock_server.uri(),
+            "/Reports/Current/Market_Notice/",
+            "TestAgent",
+            |link| link.notice_id > 116005,
+        )
+        .await
+        .expect("Failed to fetch listing");
+
+        let ids: Vec<u64> = notices.iter().map(|(link, _)| link.notice_id).collect();
+        assert_eq!(ids, [116006, 116007]);
+        let (_, cancellation) = &notices[0];
+        assert_eq!(
+            cancellation
+                .as_ref()
+                .map(|notice| notice.subject.as_str())
+                .ok(),
+            Some("Cancellation of Actual (LOR1) condition in the NSW Region")
+        );
+        let (_, bad) = &notices[1];
+        assert!(matches!(bad, Err(ScraperError::InvalidMarketNotice(_))));
+    }
+}
@@ -1 +1,3 @@
 pub mod html;
+pub mod market_notice;
+pub mod wem;
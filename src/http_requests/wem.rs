@@ -0,0 +1,103 @@
+use std::time::Instant;
+
+use crate::common::unzip_process::RecordsCollection;
+use crate::common::wem::WemFormat;
+use crate::error::ScraperError;
+use crate::http_requests::html::fetch_html_content;
+
+/// Where AEMO WA publishes WEM data files.
+pub const WEM_BASE_URL: &str = "https://data.wa.aemo.com.au";
+
+/// Fetches a WEM data file, e.g. `/datafiles/balancing-summary/balancing-summary-2024.csv`,
+/// and parses it with `processor` in the format given by its extension. `base_url` is
+/// normally [`WEM_BASE_URL`].
+pub async fn fetch_wem_file<F, T>(
+    base_url: &str,
+    path: &str,
+    user_agent: &str,
+    processor: F,
+) -> Result<RecordsCollection<T>, ScraperError>
+where
+    F: FnOnce(&[u8], WemFormat) -> Result<RecordsCollection<T>, ScraperError>,
+{
+    let start_time = Instant::now();
+    let content = fetch_html_content(base_url, path, user_agent).await?;
+
+    let mut collection = processor(content.as_bytes(), WemFormat::from_path(path))?;
+    collection.set_source_file(path.to_string());
+    collection.set_number_of_files(1);
+    collection.set_processing_time(start_time.elapsed().as_millis());
+    Ok(collection)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::wem_balancing_summary::process_file_wem_balancing_summary;
+    use crate::models::wem_facility_scada::process_file_wem_facility_scada;
+    use reqwest::StatusCode;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_fetch_wem_file_csv_and_json() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/datafiles/balancing-summary/balancing-summary-2023.json"))
+            .respond_with(ResponseTemplate::new(StatusCode::OK).set_body_string(
+                r#"[{"Trading Date": "2023-09-30", "Interval Number": 1, "Trading Interval": "2023-09-30 08:00:00", "Final Price ($/MWh)": 51.21, "Total Generation (MW)": 1873.45, "Total Outage (MW)": 612.0, "Load Forecast (MW)": 1850}]"#,
+            ))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/datafiles/facility-scada/facility-scada-2024-03.csv"))
+            .respond_with(ResponseTemplate::new(StatusCode::OK).set_body_string(
+                "Trading Date,Interval Number,Trading Interval,Participant Code,Facility Code,Energy Generated (MWh),EOI Quantity (MW),Extracted At\n\
+                2024-03-01,1,2024-03-01 08:00:00,ALINTA,ALINTA_PNJ_U1,68.4,137.2,2024-03-02 04:00:12\n",
+            ))
+            .mount(&mock_server)
+            .await;
+
+        let balancing = fetch_wem_file(
+            &mock_server.uri(),
+            "/datafiles/balancing-summary/balancing-summary-2023.json",
+            "TestAgent",
+            |bytes, format| process_file_wem_balancing_summary(bytes, format),
+        )
+        .await
+        .expect("Failed to fetch balancing summary");
+        assert_eq!(balancing.records[0].final_price(), Some(51.21));
+        assert_eq!(
+            balancing.source_file.as_deref(),
+            Some("/datafiles/balancing-summary/balancing-summary-2023.json")
+        );
+
+        let scada = fetch_wem_file(
+            &mock_server.uri(),
+            "/datafiles/facility-scada/facility-scada-2024-03.csv",
+            "TestAgent",
+            |bytes, format| process_file_wem_facility_scada(bytes, format),
+        )
+        .await
+        .expect("Failed to fetch facility SCADA");
+        assert_eq!(scada.records[0].facility_code(), "ALINTA_PNJ_U1");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_wem_file_not_found() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(StatusCode::NOT_FOUND))
+            .mount(&mock_server)
+            .await;
+
+        let result = fetch_wem_file(
+            &mock_server.uri(),
+            "/datafiles/facility-scada/facility-scada-1999-01.csv",
+            "TestAgent",
+            |bytes, format| process_file_wem_facility_scada(bytes, format),
+        )
+        .await;
+        assert!(matches!(result, Err(ScraperError::Http(_))));
+    }
+}
@@ -0,0 +1,6 @@
+pub mod common;
+pub mod error;
+pub mod http_requests;
+pub mod models;
+pub mod parsers;
+pub mod time;
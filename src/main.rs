@@ -1,28 +1,24 @@
 use chrono::{NaiveDate, NaiveDateTime};
-use std::error::Error;
 
-mod common;
-mod http_requests;
-mod models;
-mod parsers;
-mod time;
-
-use crate::common::unzip_process::{unzip_and_process, unzip_and_process_from_url};
-use crate::http_requests::html::fetch_html_content;
-use crate::models::{
-    nem_current_rooftop_pv_actual::process_file_current_rooftop_actual,
+use au_energy_scraper::common::unzip_process::{
+    unzip_and_process, unzip_and_process_from_url, unzip_and_stream,
+};
+use au_energy_scraper::error::ScraperError;
+use au_energy_scraper::http_requests::html::fetch_html_content;
+use au_energy_scraper::models::{
+    nem_current_rooftop_pv_actual::{
+        process_file_current_rooftop_actual, RecordCurrentRooftopPvActual,
+    },
     nem_current_tradingis_report::process_file_current_trading_is,
 };
-use crate::parsers::html::ZipLinkExtractorFromHtml;
-use crate::parsers::url::ZipReportUrlPath;
-use crate::time::time_ranges::{Interval, TimestampGenerator};
+use au_energy_scraper::parsers::html::ZipLinkExtractorFromHtml;
+use au_energy_scraper::parsers::url::ZipReportUrlPath;
+use au_energy_scraper::time::time_ranges::{Interval, TimestampGenerator};
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn Error>> {
+async fn main() -> Result<(), ScraperError> {
     let path = "src/fixtures/PUBLIC_TRADINGIS_202403031335_0000000412683134.zip";
-    let result = unzip_and_process(path, |contents: &str| {
-        process_file_current_trading_is(contents.to_string())
-    });
+    let result = unzip_and_process(path, |reader| process_file_current_trading_is(reader));
     match result {
         Ok(records) => {
             print!("{}", &records)
@@ -35,9 +31,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
     let path =
         "src/fixtures/PUBLIC_ROOFTOP_PV_ACTUAL_MEASUREMENT_20240303200000_0000000412707330.zip";
-    let result = unzip_and_process(path, |contents: &str| {
-        process_file_current_rooftop_actual(contents.as_bytes())
-    });
+    let result = unzip_and_process(path, |reader| process_file_current_rooftop_actual(reader));
     match result {
         Ok(records) => {
             print!("{}", &records)
@@ -48,19 +42,20 @@ async fn main() -> Result<(), Box<dyn Error>> {
         }
     }
 
+    // Large DVD archives are streamed rather than collected into memory
     let path = "src/fixtures/PUBLIC_DVD_ROOFTOP_PV_ACTUAL_201912010000.zip";
-    let result = unzip_and_process(path, |contents: &str| {
-        process_file_current_rooftop_actual(contents.as_bytes())
-    });
-    match result {
-        Ok(records) => {
-            print!("{}", &records)
+    unzip_and_stream(path, |file_name, records| {
+        let mut count = 0;
+        for record in records {
+            let record: RecordCurrentRooftopPvActual = record?;
+            if count == 0 {
+                println!("First record in {}: {}", file_name, record);
+            }
+            count += 1;
         }
-        Err(e) => {
-            println!("Error processing file: {:?}", e);
-            return Err(e);
-        }
-    }
+        println!("Streamed {} records from {}", count, file_name);
+        Ok(())
+    })?;
 
     let start_date: NaiveDateTime = NaiveDate::from_ymd_opt(2024, 3, 8)
         .expect("Start date is invalid")
@@ -98,8 +93,8 @@ async fn main() -> Result<(), Box<dyn Error>> {
         }
     };
     for url_path in url_paths {
-        let result = unzip_and_process_from_url(base_url, &url_path, |contents: &str| {
-            process_file_current_rooftop_actual(contents.as_bytes())
+        let result = unzip_and_process_from_url(base_url, &url_path, |reader| {
+            process_file_current_rooftop_actual(reader)
         })
         .await?;
         println!("{}", result);
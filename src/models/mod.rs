@@ -1,2 +1,18 @@
+pub mod nem_bidmove_complete;
 pub mod nem_current_rooftop_pv_actual;
+pub mod nem_current_rooftop_pv_forecast;
+pub mod nem_current_trading_irsr;
 pub mod nem_current_tradingis_report;
+pub mod nem_daily_reports;
+pub mod nem_dispatch_legacy;
+pub mod nem_dispatch_scada;
+pub mod nem_mtpasa;
+pub mod nem_network_outage;
+pub mod nem_next_day_dispatch;
+pub mod nem_operational_demand;
+pub mod nem_p5min;
+pub mod nem_participant_registration;
+pub mod nem_predispatch_is;
+pub mod nem_stpasa;
+pub mod wem_balancing_summary;
+pub mod wem_facility_scada;
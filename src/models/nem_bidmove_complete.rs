@@ -0,0 +1,567 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Read;
+use std::sync::Arc;
+
+use crate::common::intern::deserialize_interned;
+use crate::common::mms_csv::{DataRow, FromDataRow, MmsCsvReader, MmsRecords, TableKey};
+use crate::common::unit::UnitRow;
+use crate::common::unzip_process::{ProcessRecord, RecordsCollection};
+use crate::error::ScraperError;
+use crate::time::datetimezone_conversion::{
+    deserialize_optional_sydney_datetime_to_utc, deserialize_sydney_datetime_to_utc, sydney_to_utc,
+};
+
+/// BIDS.BIDDAYOFFER_D: the price bands a unit offered for a trading day, as they stood at the
+/// end of the day. `BIDTYPE` is `ENERGY` or one of the FCAS services.
+#[derive(Debug, Deserialize)]
+pub struct BidDayOfferData {
+    #[serde(rename = "ROW_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    csv_row_identifier: Arc<str>,
+    #[serde(rename = "FILE_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    category: Arc<str>,
+    #[serde(rename = "FILE_SUBTYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    report_type: Arc<str>,
+    #[serde(rename = "FILE_DESCRIPTOR")]
+    report_type_int: u32,
+    #[serde(rename = "SETTLEMENTDATE")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    settlement_date: DateTime<Utc>,
+    #[serde(rename = "DUID")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    duid: Arc<str>,
+    #[serde(rename = "BIDTYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    bid_type: Arc<str>,
+    #[serde(rename = "DIRECTION")]
+    direction: Option<String>,
+    #[serde(rename = "BIDSETTLEMENTDATE")]
+    #[serde(deserialize_with = "deserialize_optional_sydney_datetime_to_utc")]
+    bid_settlement_date: Option<DateTime<Utc>>,
+    #[serde(rename = "OFFERDATE")]
+    #[serde(deserialize_with = "deserialize_optional_sydney_datetime_to_utc")]
+    offer_date: Option<DateTime<Utc>>,
+    #[serde(rename = "VERSIONNO")]
+    version_no: Option<u64>,
+    #[serde(rename = "PARTICIPANTID")]
+    participant_id: Option<String>,
+    #[serde(rename = "DAILYENERGYCONSTRAINT")]
+    daily_energy_constraint: Option<f64>,
+    #[serde(rename = "REBIDEXPLANATION")]
+    rebid_explanation: Option<String>,
+    #[serde(rename = "PRICEBAND1")]
+    price_band_1: Option<f64>,
+    #[serde(rename = "PRICEBAND2")]
+    price_band_2: Option<f64>,
+    #[serde(rename = "PRICEBAND3")]
+    price_band_3: Option<f64>,
+    #[serde(rename = "PRICEBAND4")]
+    price_band_4: Option<f64>,
+    #[serde(rename = "PRICEBAND5")]
+    price_band_5: Option<f64>,
+    #[serde(rename = "PRICEBAND6")]
+    price_band_6: Option<f64>,
+    #[serde(rename = "PRICEBAND7")]
+    price_band_7: Option<f64>,
+    #[serde(rename = "PRICEBAND8")]
+    price_band_8: Option<f64>,
+    #[serde(rename = "PRICEBAND9")]
+    price_band_9: Option<f64>,
+    #[serde(rename = "PRICEBAND10")]
+    price_band_10: Option<f64>,
+    #[serde(rename = "MINIMUMLOAD")]
+    minimum_load: Option<f64>,
+    #[serde(rename = "T1")]
+    t1: Option<f64>,
+    #[serde(rename = "T2")]
+    t2: Option<f64>,
+    #[serde(rename = "T3")]
+    t3: Option<f64>,
+    #[serde(rename = "T4")]
+    t4: Option<f64>,
+    #[serde(rename = "NORMALSTATUS")]
+    normal_status: Option<String>,
+    #[serde(rename = "LASTCHANGED")]
+    #[serde(deserialize_with = "deserialize_optional_sydney_datetime_to_utc")]
+    last_changed: Option<DateTime<Utc>>,
+    #[serde(rename = "MR_FACTOR")]
+    mr_factor: Option<f64>,
+    #[serde(rename = "ENTRYTYPE")]
+    entry_type: Option<String>,
+}
+
+impl fmt::Display for BidDayOfferData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "BidDayOfferData: {{ csv_row_identifier: {}, category: {}, report_type: {}, report_type_int: {}, settlement_date: {:?}, duid: {:?}, bid_type: {:?}, direction: {:?}, bid_settlement_date: {:?}, offer_date: {:?}, version_no: {:?}, participant_id: {:?}, daily_energy_constraint: {:?}, rebid_explanation: {:?}, price_band_1: {:?}, price_band_2: {:?}, price_band_3: {:?}, price_band_4: {:?}, price_band_5: {:?}, price_band_6: {:?}, price_band_7: {:?}, price_band_8: {:?}, price_band_9: {:?}, price_band_10: {:?}, minimum_load: {:?}, t1: {:?}, t2: {:?}, t3: {:?}, t4: {:?}, normal_status: {:?}, last_changed: {:?}, mr_factor: {:?}, entry_type: {:?} }}",
+            self.csv_row_identifier,
+            self.category,
+            self.report_type,
+            self.report_type_int,
+            self.settlement_date,
+            self.duid,
+            self.bid_type,
+            self.direction,
+            self.bid_settlement_date,
+            self.offer_date,
+            self.version_no,
+            self.participant_id,
+            self.daily_energy_constraint,
+            self.rebid_explanation,
+            self.price_band_1,
+            self.price_band_2,
+            self.price_band_3,
+            self.price_band_4,
+            self.price_band_5,
+            self.price_band_6,
+            self.price_band_7,
+            self.price_band_8,
+            self.price_band_9,
+            self.price_band_10,
+            self.minimum_load,
+            self.t1,
+            self.t2,
+            self.t3,
+            self.t4,
+            self.normal_status,
+            self.last_changed,
+            self.mr_factor,
+            self.entry_type
+        )
+    }
+}
+
+/// BIDS.BIDPEROFFER_D: how much of each price band a unit made available in one dispatch
+/// interval of the trading day.
+#[derive(Debug, Deserialize)]
+pub struct BidPerOfferData {
+    #[serde(rename = "ROW_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    csv_row_identifier: Arc<str>,
+    #[serde(rename = "FILE_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    category: Arc<str>,
+    #[serde(rename = "FILE_SUBTYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    report_type: Arc<str>,
+    #[serde(rename = "FILE_DESCRIPTOR")]
+    report_type_int: u32,
+    #[serde(rename = "SETTLEMENTDATE")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    settlement_date: DateTime<Utc>,
+    #[serde(rename = "DUID")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    duid: Arc<str>,
+    #[serde(rename = "BIDTYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    bid_type: Arc<str>,
+    #[serde(rename = "DIRECTION")]
+    direction: Option<String>,
+    // Not in files published before five-minute settlement
+    #[serde(rename = "INTERVAL_DATETIME")]
+    #[serde(default)]
+    #[serde(deserialize_with = "deserialize_optional_sydney_datetime_to_utc")]
+    interval_datetime: Option<DateTime<Utc>>,
+    #[serde(rename = "BIDSETTLEMENTDATE")]
+    #[serde(deserialize_with = "deserialize_optional_sydney_datetime_to_utc")]
+    bid_settlement_date: Option<DateTime<Utc>>,
+    #[serde(rename = "OFFERDATE")]
+    #[serde(deserialize_with = "deserialize_optional_sydney_datetime_to_utc")]
+    offer_date: Option<DateTime<Utc>>,
+    #[serde(rename = "PERIODID")]
+    period_id: Option<u32>,
+    #[serde(rename = "VERSIONNO")]
+    version_no: Option<u64>,
+    #[serde(rename = "MAXAVAIL")]
+    max_avail: Option<f64>,
+    #[serde(rename = "FIXEDLOAD")]
+    fixed_load: Option<f64>,
+    #[serde(rename = "ROCUP")]
+    roc_up: Option<f64>,
+    #[serde(rename = "ROCDOWN")]
+    roc_down: Option<f64>,
+    #[serde(rename = "ENABLEMENTMIN")]
+    enablement_min: Option<f64>,
+    #[serde(rename = "ENABLEMENTMAX")]
+    enablement_max: Option<f64>,
+    #[serde(rename = "LOWBREAKPOINT")]
+    low_break_point: Option<f64>,
+    #[serde(rename = "HIGHBREAKPOINT")]
+    high_break_point: Option<f64>,
+    #[serde(rename = "BANDAVAIL1")]
+    band_avail_1: Option<f64>,
+    #[serde(rename = "BANDAVAIL2")]
+    band_avail_2: Option<f64>,
+    #[serde(rename = "BANDAVAIL3")]
+    band_avail_3: Option<f64>,
+    #[serde(rename = "BANDAVAIL4")]
+    band_avail_4: Option<f64>,
+    #[serde(rename = "BANDAVAIL5")]
+    band_avail_5: Option<f64>,
+    #[serde(rename = "BANDAVAIL6")]
+    band_avail_6: Option<f64>,
+    #[serde(rename = "BANDAVAIL7")]
+    band_avail_7: Option<f64>,
+    #[serde(rename = "BANDAVAIL8")]
+    band_avail_8: Option<f64>,
+    #[serde(rename = "BANDAVAIL9")]
+    band_avail_9: Option<f64>,
+    #[serde(rename = "BANDAVAIL10")]
+    band_avail_10: Option<f64>,
+    #[serde(rename = "LASTCHANGED")]
+    #[serde(deserialize_with = "deserialize_optional_sydney_datetime_to_utc")]
+    last_changed: Option<DateTime<Utc>>,
+    #[serde(rename = "PASAAVAILABILITY")]
+    pasa_availability: Option<f64>,
+    #[serde(rename = "MR_CAPACITY")]
+    mr_capacity: Option<f64>,
+}
+
+impl fmt::Display for BidPerOfferData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "BidPerOfferData: {{ csv_row_identifier: {}, category: {}, report_type: {}, report_type_int: {}, settlement_date: {:?}, duid: {:?}, bid_type: {:?}, direction: {:?}, interval_datetime: {:?}, bid_settlement_date: {:?}, offer_date: {:?}, period_id: {:?}, version_no: {:?}, max_avail: {:?}, fixed_load: {:?}, roc_up: {:?}, roc_down: {:?}, enablement_min: {:?}, enablement_max: {:?}, low_break_point: {:?}, high_break_point: {:?}, band_avail_1: {:?}, band_avail_2: {:?}, band_avail_3: {:?}, band_avail_4: {:?}, band_avail_5: {:?}, band_avail_6: {:?}, band_avail_7: {:?}, band_avail_8: {:?}, band_avail_9: {:?}, band_avail_10: {:?}, last_changed: {:?}, pasa_availability: {:?}, mr_capacity: {:?} }}",
+            self.csv_row_identifier,
+            self.category,
+            self.report_type,
+            self.report_type_int,
+            self.settlement_date,
+            self.duid,
+            self.bid_type,
+            self.direction,
+            self.interval_datetime,
+            self.bid_settlement_date,
+            self.offer_date,
+            self.period_id,
+            self.version_no,
+            self.max_avail,
+            self.fixed_load,
+            self.roc_up,
+            self.roc_down,
+            self.enablement_min,
+            self.enablement_max,
+            self.low_break_point,
+            self.high_break_point,
+            self.band_avail_1,
+            self.band_avail_2,
+            self.band_avail_3,
+            self.band_avail_4,
+            self.band_avail_5,
+            self.band_avail_6,
+            self.band_avail_7,
+            self.band_avail_8,
+            self.band_avail_9,
+            self.band_avail_10,
+            self.last_changed,
+            self.pasa_availability,
+            self.mr_capacity
+        )
+    }
+}
+
+impl BidDayOfferData {
+    /// `PRICEBAND1` to `PRICEBAND10`, in $/MWh.
+    pub fn price_bands(&self) -> [Option<f64>; 10] {
+        [
+            self.price_band_1,
+            self.price_band_2,
+            self.price_band_3,
+            self.price_band_4,
+            self.price_band_5,
+            self.price_band_6,
+            self.price_band_7,
+            self.price_band_8,
+            self.price_band_9,
+            self.price_band_10,
+        ]
+    }
+}
+
+impl BidPerOfferData {
+    /// `BANDAVAIL1` to `BANDAVAIL10`, in MW.
+    pub fn band_availability(&self) -> [Option<f64>; 10] {
+        [
+            self.band_avail_1,
+            self.band_avail_2,
+            self.band_avail_3,
+            self.band_avail_4,
+            self.band_avail_5,
+            self.band_avail_6,
+            self.band_avail_7,
+            self.band_avail_8,
+            self.band_avail_9,
+            self.band_avail_10,
+        ]
+    }
+}
+
+impl UnitRow for BidPerOfferData {
+    fn duid(&self) -> &str {
+        &self.duid
+    }
+
+    fn settlement_datetime(&self) -> DateTime<Utc> {
+        self.interval_datetime.unwrap_or(self.settlement_date)
+    }
+}
+
+#[derive(Debug)]
+pub enum RecordBidmoveComplete {
+    DayOffer(BidDayOfferData),
+    PerOffer(BidPerOfferData),
+}
+
+impl fmt::Display for RecordBidmoveComplete {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecordBidmoveComplete::DayOffer(day_offer) => write!(f, "{}", day_offer),
+            RecordBidmoveComplete::PerOffer(per_offer) => write!(f, "{}", per_offer),
+        }
+    }
+}
+
+// Version 3 of both tables adds DIRECTION for bidirectional units, which is None for older
+// files.
+impl ProcessRecord<RecordBidmoveComplete> for BidDayOfferData {
+    fn process(row: &DataRow) -> Result<RecordBidmoveComplete, ScraperError> {
+        let record = match row.version()? {
+            2 | 3 => row.deserialize::<BidDayOfferData>()?,
+            version => return Err(row.unsupported_version(version)),
+        };
+        Ok(RecordBidmoveComplete::DayOffer(record))
+    }
+}
+
+impl ProcessRecord<RecordBidmoveComplete> for BidPerOfferData {
+    fn process(row: &DataRow) -> Result<RecordBidmoveComplete, ScraperError> {
+        let record = match row.version()? {
+            2 | 3 => row.deserialize::<BidPerOfferData>()?,
+            version => return Err(row.unsupported_version(version)),
+        };
+        Ok(RecordBidmoveComplete::PerOffer(record))
+    }
+}
+
+impl FromDataRow for RecordBidmoveComplete {
+    fn from_data_row(row: &DataRow) -> Result<Self, ScraperError> {
+        if row.table.is("BIDS", "BIDDAYOFFER_D") {
+            BidDayOfferData::process(row)
+        } else if row.table.is("BIDS", "BIDPEROFFER_D") {
+            BidPerOfferData::process(row)
+        } else {
+            Err(row.unknown_table())
+        }
+    }
+
+    // Bidmove_Complete also carries MNSP offer tables
+    fn reads_table(table: &TableKey) -> bool {
+        table.is("BIDS", "BIDDAYOFFER_D") || table.is("BIDS", "BIDPEROFFER_D")
+    }
+}
+
+/// Streams Bidmove_Complete records one row at a time.
+pub fn stream_file_bidmove_complete<R: Read>(reader: R) -> MmsRecords<R, RecordBidmoveComplete> {
+    MmsCsvReader::new(reader).records()
+}
+
+pub fn process_file_bidmove_complete<R: Read>(
+    reader: R,
+) -> Result<RecordsCollection<RecordBidmoveComplete>, ScraperError> {
+    RecordsCollection::from_records(stream_file_bidmove_complete(reader))
+}
+
+/// One price band of a unit's offer in one interval.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BidBand {
+    pub price: Option<f64>,
+    pub availability: Option<f64>,
+}
+
+/// A unit's offer for one interval: its daily price bands paired with the availability it
+/// gave each band in that interval. Sorting the bands of every unit by price gives the merit
+/// order for the interval.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnitIntervalBid {
+    pub duid: Arc<str>,
+    pub bid_type: Arc<str>,
+    pub direction: Option<String>,
+    pub period_id: Option<u32>,
+    pub interval_datetime: Option<DateTime<Utc>>,
+    pub max_avail: Option<f64>,
+    pub bands: [BidBand; 10],
+}
+
+/// Joins the per-interval availability for the trading day `settlement_date` with the price
+/// bands of the same unit, bid type and direction. Intervals without a matching day offer are
+/// left out. Results are ordered by unit, bid type and period.
+pub fn join_bid_bands(
+    records: &[RecordBidmoveComplete],
+    settlement_date: NaiveDate,
+) -> Vec<UnitIntervalBid> {
+    let Some(settlement_date) = sydney_to_utc(&settlement_date.and_time(Default::default())) else {
+        return Vec::new();
+    };
+
+    let mut day_offers: HashMap<(&str, &str, Option<&str>), &BidDayOfferData> = HashMap::new();
+    for record in records {
+        if let RecordBidmoveComplete::DayOffer(day_offer) = record {
+            if day_offer.settlement_date == settlement_date {
+                day_offers.insert(
+                    (
+                        &day_offer.duid,
+                        &day_offer.bid_type,
+                        day_offer.direction.as_deref(),
+                    ),
+                    day_offer,
+                );
+            }
+        }
+    }
+
+    let mut bids: Vec<UnitIntervalBid> = records
+        .iter()
+        .filter_map(|record| match record {
+            RecordBidmoveComplete::PerOffer(per_offer) => Some(per_offer),
+            RecordBidmoveComplete::DayOffer(_) => None,
+        })
+        .filter(|per_offer| per_offer.settlement_date == settlement_date)
+        .filter_map(|per_offer| {
+            let day_offer = day_offers.get(&(
+                &*per_offer.duid,
+                &*per_offer.bid_type,
+                per_offer.direction.as_deref(),
+            ))?;
+            let prices = day_offer.price_bands();
+            let availability = per_offer.band_availability();
+            Some(UnitIntervalBid {
+                duid: Arc::clone(&per_offer.duid),
+                bid_type: Arc::clone(&per_offer.bid_type),
+                direction: per_offer.direction.clone(),
+                period_id: per_offer.period_id,
+                interval_datetime: per_offer.interval_datetime,
+                max_avail: per_offer.max_avail,
+                bands: std::array::from_fn(|band| BidBand {
+                    price: prices[band],
+                    availability: availability[band],
+                }),
+            })
+        })
+        .collect();
+    bids.sort_by(|a, b| {
+        (&a.duid, &a.bid_type, &a.direction, a.period_id).cmp(&(
+            &b.duid,
+            &b.bid_type,
+            &b.direction,
+            b.period_id,
+        ))
+    });
+    bids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BIDMOVE_COMPLETE: &str = "C,NEMP.WORLD,BIDMOVE_COMPLETE,AEMO,PUBLIC,2024/03/04,04:05:10,0000000412712345,BIDMOVE_COMPLETE,0000000412712344\n\
+        I,BIDS,BIDDAYOFFER_D,3,SETTLEMENTDATE,DUID,BIDTYPE,DIRECTION,BIDSETTLEMENTDATE,OFFERDATE,VERSIONNO,PARTICIPANTID,DAILYENERGYCONSTRAINT,REBIDEXPLANATION,PRICEBAND1,PRICEBAND2,PRICEBAND3,PRICEBAND4,PRICEBAND5,PRICEBAND6,PRICEBAND7,PRICEBAND8,PRICEBAND9,PRICEBAND10,MINIMUMLOAD,T1,T2,T3,T4,NORMALSTATUS,LASTCHANGED,MR_FACTOR,ENTRYTYPE\n\
+        D,BIDS,BIDDAYOFFER_D,3,\"2024/03/03 00:00:00\",BW01,ENERGY,GEN,\"2024/03/03 00:00:00\",\"2024/03/02 12:41:07\",1,AGLHAL,0,\"DAILY BID\",-1000,-50,0,45.5,89.9,150,299.9,1000,5000,17500,,,,,,ON,\"2024/03/02 12:41:09\",,DAILY\n\
+        D,BIDS,BIDDAYOFFER_D,3,\"2024/03/03 00:00:00\",BW01,RAISE6SEC,GEN,\"2024/03/03 00:00:00\",\"2024/03/02 12:41:07\",1,AGLHAL,0,\"DAILY BID\",0.01,0.5,1,5,10,20,50,100,1000,17500,,,,,,ON,\"2024/03/02 12:41:09\",,DAILY\n\
+        I,BIDS,BIDPEROFFER_D,3,SETTLEMENTDATE,DUID,BIDTYPE,DIRECTION,INTERVAL_DATETIME,BIDSETTLEMENTDATE,OFFERDATE,PERIODID,VERSIONNO,MAXAVAIL,FIXEDLOAD,ROCUP,ROCDOWN,ENABLEMENTMIN,ENABLEMENTMAX,LOWBREAKPOINT,HIGHBREAKPOINT,BANDAVAIL1,BANDAVAIL2,BANDAVAIL3,BANDAVAIL4,BANDAVAIL5,BANDAVAIL6,BANDAVAIL7,BANDAVAIL8,BANDAVAIL9,BANDAVAIL10,LASTCHANGED,PASAAVAILABILITY,MR_CAPACITY\n\
+        D,BIDS,BIDPEROFFER_D,3,\"2024/03/03 00:00:00\",BW01,ENERGY,GEN,\"2024/03/03 04:10:00\",\"2024/03/03 00:00:00\",\"2024/03/02 12:41:07\",2,1,660,0,3,3,0,0,0,0,240,0,0,60,0,100,0,0,0,260,\"2024/03/02 12:41:09\",660,0\n\
+        D,BIDS,BIDPEROFFER_D,3,\"2024/03/03 00:00:00\",BW01,ENERGY,GEN,\"2024/03/03 04:05:00\",\"2024/03/03 00:00:00\",\"2024/03/02 12:41:07\",1,1,660,0,3,3,0,0,0,0,240,0,0,60,0,100,0,0,0,260,\"2024/03/02 12:41:09\",660,0\n\
+        D,BIDS,BIDPEROFFER_D,3,\"2024/03/04 00:00:00\",BW01,ENERGY,GEN,\"2024/03/04 04:05:00\",\"2024/03/04 00:00:00\",\"2024/03/03 12:30:55\",1,1,660,0,3,3,0,0,0,0,240,0,0,60,0,100,0,0,0,260,\"2024/03/03 12:30:57\",660,0\n\
+        I,BIDS,MNSP_DAYOFFER,2,SETTLEMENTDATE,OFFERDATE,VERSIONNO,PARTICIPANTID,LINKID,PRICEBAND1,LASTCHANGED\n\
+        D,BIDS,MNSP_DAYOFFER,2,\"2024/03/03 00:00:00\",\"2024/03/02 12:00:00\",1,BASSLINK,BLNKTAS,-1000,\"2024/03/02 12:00:02\"\n\
+        C,\"END OF REPORT\",11\n";
+
+    #[test]
+    fn test_process_file_bidmove_complete() {
+        let records = process_file_bidmove_complete(BIDMOVE_COMPLETE.as_bytes())
+            .expect("Failed to parse")
+            .records;
+
+        assert_eq!(records.len(), 5);
+        match &records[0] {
+            RecordBidmoveComplete::DayOffer(day_offer) => {
+                assert_eq!(&*day_offer.duid, "BW01");
+                assert_eq!(day_offer.direction.as_deref(), Some("GEN"));
+                assert_eq!(day_offer.price_bands()[9], Some(17500.0));
+                assert_eq!(day_offer.minimum_load, None);
+            }
+            other => panic!("Expected BIDDAYOFFER_D, got {}", other),
+        }
+        match &records[2] {
+            RecordBidmoveComplete::PerOffer(per_offer) => {
+                assert_eq!(per_offer.period_id, Some(2));
+                assert_eq!(per_offer.max_avail, Some(660.0));
+                assert_eq!(per_offer.band_availability()[0], Some(240.0));
+            }
+            other => panic!("Expected BIDPEROFFER_D, got {}", other),
+        }
+    }
+
+    #[test]
+    fn test_join_bid_bands() {
+        let records = process_file_bidmove_complete(BIDMOVE_COMPLETE.as_bytes())
+            .expect("Failed to parse")
+            .records;
+
+        let bids = join_bid_bands(&records, NaiveDate::from_ymd_opt(2024, 3, 3).unwrap());
+        assert_eq!(bids.len(), 2);
+        assert_eq!(bids[0].period_id, Some(1));
+        assert_eq!(
+            bids[0].interval_datetime.map(|dt| dt.to_rfc3339()),
+            Some("2024-03-02T17:05:00+00:00".to_string())
+        );
+        assert_eq!(
+            bids[0].bands[0],
+            BidBand {
+                price: Some(-1000.0),
+                availability: Some(240.0),
+            }
+        );
+        assert_eq!(
+            bids[0].bands[9],
+            BidBand {
+                price: Some(17500.0),
+                availability: Some(260.0),
+            }
+        );
+        assert_eq!(
+            bids[0]
+                .bands
+                .iter()
+                .filter_map(|band| band.availability)
+                .sum::<f64>(),
+            660.0
+        );
+
+        let next_day = join_bid_bands(&records, NaiveDate::from_ymd_opt(2024, 3, 4).unwrap());
+        assert!(next_day.is_empty());
+    }
+
+    #[test]
+    fn test_process_file_bidmove_complete_version_2() {
+        let data = "I,BIDS,BIDPEROFFER_D,2,SETTLEMENTDATE,DUID,BIDTYPE,BIDSETTLEMENTDATE,OFFERDATE,PERIODID,VERSIONNO,MAXAVAIL,BANDAVAIL1,LASTCHANGED\n\
+            D,BIDS,BIDPEROFFER_D,2,\"2021/03/03 00:00:00\",BW01,ENERGY,\"2021/03/03 00:00:00\",\"2021/03/02 12:41:07\",1,1,660,240,\"2021/03/02 12:41:09\"\n\
+            C,\"END OF REPORT\",3\n";
+        let records = process_file_bidmove_complete(data.as_bytes())
+            .expect("Failed to parse")
+            .records;
+
+        match &records[0] {
+            RecordBidmoveComplete::PerOffer(per_offer) => {
+                assert_eq!(per_offer.interval_datetime, None);
+                assert_eq!(per_offer.direction, None);
+                assert_eq!(
+                    per_offer.settlement_datetime().to_rfc3339(),
+                    "2021-03-02T13:00:00+00:00"
+                );
+            }
+            other => panic!("Expected BIDPEROFFER_D, got {}", other),
+        }
+    }
+}
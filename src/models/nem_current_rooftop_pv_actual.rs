@@ -1,36 +1,43 @@
 use chrono::{DateTime, Utc};
-use csv::ReaderBuilder;
 use serde::Deserialize;
-use std::error::Error;
 use std::fmt;
-use std::io::{BufRead, BufReader};
+use std::io::Read;
+use std::sync::Arc;
 
-use crate::common::unzip_process::ProcessRecord;
+use crate::common::intern::deserialize_interned;
+use crate::common::mms_csv::{DataRow, FromDataRow, MmsCsvReader, MmsRecords};
+use crate::common::unzip_process::{ProcessRecord, RecordsCollection};
+use crate::error::ScraperError;
 use crate::time::datetimezone_conversion::deserialize_sydney_datetime_to_utc;
 
 // Updated struct to represent the Data row (D row)
 #[derive(Debug, Deserialize)]
 pub struct RooftopPvActualData {
     // The first three fields are constant and represent metadata about the row
-    #[serde(rename = "CSVROWIDENTIFIER")]
-    csv_row_identifier: String, // "I"
-    #[serde(rename = "CATEGORY")]
-    category: String,
-    #[serde(rename = "REPORT_TYPE")]
-    report_type: String,
-    #[serde(rename = "REPORT_TYPE_INT")]
-    report_type_int: String,
-    #[serde(rename = "INTERVAL_DATETIMEZONE")]
+    #[serde(rename = "ROW_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    csv_row_identifier: Arc<str>, // "I"
+    #[serde(rename = "FILE_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    category: Arc<str>,
+    #[serde(rename = "FILE_SUBTYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    report_type: Arc<str>,
+    #[serde(rename = "FILE_DESCRIPTOR")]
+    report_type_int: u32,
+    #[serde(rename = "INTERVAL_DATETIME")]
     #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
-    interval_datetime: DateTime<Utc>,
+    pub(crate) interval_datetime: DateTime<Utc>,
     #[serde(rename = "REGIONID")]
-    regionid: String,
+    #[serde(deserialize_with = "deserialize_interned")]
+    pub(crate) regionid: Arc<str>,
     #[serde(rename = "POWER")]
-    power: Option<f64>, // Assuming power can be a floating-point number
+    pub(crate) power: Option<f64>, // Assuming power can be a floating-point number
     #[serde(rename = "QI")]
     qi: Option<f64>,
     #[serde(rename = "TYPE")]
-    type_: String,
+    #[serde(deserialize_with = "deserialize_interned")]
+    pub(crate) type_: Arc<str>,
     #[serde(rename = "LASTCHANGED")]
     #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
     lastchanged: DateTime<Utc>,
@@ -71,43 +78,62 @@ impl fmt::Display for RecordCurrentRooftopPvActual {
 }
 
 impl ProcessRecord<RecordCurrentRooftopPvActual> for RooftopPvActualData {
-    fn process(line: &str) -> Result<RecordCurrentRooftopPvActual, Box<dyn Error>> {
-        let mut rdr = ReaderBuilder::new()
-            .has_headers(false)
-            .from_reader(line.as_bytes());
-        let record = rdr
-            .deserialize::<RooftopPvActualData>()
-            .next()
-            .ok_or("Error attempting to deserialize InterconnectorData")??;
+    fn process(row: &DataRow) -> Result<RecordCurrentRooftopPvActual, ScraperError> {
+        let record = match row.version()? {
+            2 => row.deserialize::<RooftopPvActualData>()?,
+            version => return Err(row.unsupported_version(version)),
+        };
         Ok(RecordCurrentRooftopPvActual::Variant1(record))
     }
 }
 
-pub fn process_file_current_rooftop_actual(
-    contents: &[u8],
-) -> Result<Vec<RecordCurrentRooftopPvActual>, Box<dyn Error>> {
-    let reader = BufReader::new(contents);
-    let mut records: Vec<RecordCurrentRooftopPvActual> = Vec::new();
-
-    for line_result in reader.lines() {
-        let line = line_result?;
-        if line.starts_with("I,ROOFTOP,ACTUAL") {
-            println!("{:?}", &line);
-        }
-        match line.chars().next() {
-            Some('C') | Some('I') => continue,
-            Some('D') => {
-                if line.starts_with("D,ROOFTOP,ACTUAL") {
-                    // Assuming RooftopPvActualData::process is similar to InterconnectorData::process and PriceData::process
-                    records.push(RooftopPvActualData::process(&line)?);
-                } else {
-                    println!("Parser Error: RooftopPvActualData\n line: {:?}", &line);
-                    return Err("Unknown record type for RooftopPvActualData".into());
-                }
-            }
-            _ => return Err("Invalid line format".into()),
+impl FromDataRow for RecordCurrentRooftopPvActual {
+    fn from_data_row(row: &DataRow) -> Result<Self, ScraperError> {
+        if row.table.is("ROOFTOP", "ACTUAL") {
+            RooftopPvActualData::process(row)
+        } else {
+            Err(row.unknown_table())
         }
     }
+}
 
-    Ok(records)
+/// Streams rooftop PV actual records one row at a time.
+pub fn stream_file_current_rooftop_actual<R: Read>(
+    reader: R,
+) -> MmsRecords<R, RecordCurrentRooftopPvActual> {
+    MmsCsvReader::new(reader).records()
+}
+
+pub fn process_file_current_rooftop_actual<R: Read>(
+    reader: R,
+) -> Result<RecordsCollection<RecordCurrentRooftopPvActual>, ScraperError> {
+    RecordsCollection::from_records(stream_file_current_rooftop_actual(reader))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_process_file_current_rooftop_actual_fixture() {
+        let contents = fs::read(
+            "src/fixtures/PUBLIC_ROOFTOP_PV_ACTUAL_MEASUREMENT_20240303200000_0000000412707330.csv",
+        )
+        .expect("Failed to read rooftop PV fixture");
+
+        let records = process_file_current_rooftop_actual(contents.as_slice())
+            .expect("Failed to parse")
+            .records;
+
+        assert_eq!(records.len(), 10);
+        let RecordCurrentRooftopPvActual::Variant1(sa) = &records[5];
+        assert_eq!(&*sa.regionid, "SA1");
+        assert_eq!(sa.power, Some(6.617));
+        assert_eq!(&*sa.type_, "MEASUREMENT");
+        assert_eq!(
+            sa.interval_datetime.to_rfc3339(),
+            "2024-03-03T08:30:00+00:00"
+        );
+    }
 }
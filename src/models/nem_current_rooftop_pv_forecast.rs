@@ -0,0 +1,246 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Read;
+use std::sync::Arc;
+
+use crate::common::forecast::{latest_by, ForecastRow};
+use crate::common::intern::deserialize_interned;
+use crate::common::mms_csv::{DataRow, FromDataRow, MmsCsvReader, MmsRecords};
+use crate::common::unzip_process::{ProcessRecord, RecordsCollection};
+use crate::error::ScraperError;
+use crate::models::nem_current_rooftop_pv_actual::RecordCurrentRooftopPvActual;
+use crate::time::datetimezone_conversion::deserialize_sydney_datetime_to_utc;
+
+/// ROOFTOP.FORECAST: a half-hourly rooftop PV forecast for a region.
+///
+/// `VERSION_DATETIME` is when the forecast was run and `INTERVAL_DATETIME` the half hour it is
+/// for, so each run forecasts many intervals and each interval is forecast by many runs.
+#[derive(Debug, Deserialize)]
+pub struct RooftopPvForecastData {
+    #[serde(rename = "ROW_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    csv_row_identifier: Arc<str>,
+    #[serde(rename = "FILE_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    category: Arc<str>,
+    #[serde(rename = "FILE_SUBTYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    report_type: Arc<str>,
+    #[serde(rename = "FILE_DESCRIPTOR")]
+    report_type_int: u32,
+    #[serde(rename = "VERSION_DATETIME")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    version_datetime: DateTime<Utc>,
+    #[serde(rename = "REGIONID")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    regionid: Arc<str>,
+    #[serde(rename = "INTERVAL_DATETIME")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    interval_datetime: DateTime<Utc>,
+    #[serde(rename = "POWERMEAN")]
+    power_mean: Option<f64>,
+    #[serde(rename = "POWERPOE50")]
+    power_poe50: Option<f64>,
+    #[serde(rename = "POWERPOELOW")]
+    power_poe_low: Option<f64>,
+    #[serde(rename = "POWERPOEHIGH")]
+    power_poe_high: Option<f64>,
+    #[serde(rename = "LASTCHANGED")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    lastchanged: DateTime<Utc>,
+}
+
+impl fmt::Display for RooftopPvForecastData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "RooftopPvForecastData: {{ csv_row_identifier: {}, category: {}, report_type: {}, report_type_int: {}, version_datetime: {:?}, regionid: {:?}, interval_datetime: {:?}, power_mean: {:?}, power_poe50: {:?}, power_poe_low: {:?}, power_poe_high: {:?}, lastchanged: {:?} }}",
+            self.csv_row_identifier,
+            self.category,
+            self.report_type,
+            self.report_type_int,
+            self.version_datetime,
+            self.regionid,
+            self.interval_datetime,
+            self.power_mean,
+            self.power_poe50,
+            self.power_poe_low,
+            self.power_poe_high,
+            self.lastchanged
+        )
+    }
+}
+
+impl ForecastRow for RooftopPvForecastData {
+    fn run_datetime(&self) -> DateTime<Utc> {
+        self.version_datetime
+    }
+
+    fn interval_datetime(&self) -> DateTime<Utc> {
+        self.interval_datetime
+    }
+}
+
+#[derive(Debug)]
+pub enum RecordCurrentRooftopPvForecast {
+    Forecast(RooftopPvForecastData),
+}
+
+impl fmt::Display for RecordCurrentRooftopPvForecast {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecordCurrentRooftopPvForecast::Forecast(forecast_data) => {
+                write!(f, "{}", forecast_data)
+            }
+        }
+    }
+}
+
+impl ProcessRecord<RecordCurrentRooftopPvForecast> for RooftopPvForecastData {
+    fn process(row: &DataRow) -> Result<RecordCurrentRooftopPvForecast, ScraperError> {
+        let record = match row.version()? {
+            1 => row.deserialize::<RooftopPvForecastData>()?,
+            version => return Err(row.unsupported_version(version)),
+        };
+        Ok(RecordCurrentRooftopPvForecast::Forecast(record))
+    }
+}
+
+impl FromDataRow for RecordCurrentRooftopPvForecast {
+    fn from_data_row(row: &DataRow) -> Result<Self, ScraperError> {
+        if row.table.is("ROOFTOP", "FORECAST") {
+            RooftopPvForecastData::process(row)
+        } else {
+            Err(row.unknown_table())
+        }
+    }
+}
+
+/// Streams rooftop PV forecast records one row at a time.
+pub fn stream_file_current_rooftop_forecast<R: Read>(
+    reader: R,
+) -> MmsRecords<R, RecordCurrentRooftopPvForecast> {
+    MmsCsvReader::new(reader).records()
+}
+
+pub fn process_file_current_rooftop_forecast<R: Read>(
+    reader: R,
+) -> Result<RecordsCollection<RecordCurrentRooftopPvForecast>, ScraperError> {
+    RecordsCollection::from_records(stream_file_current_rooftop_forecast(reader))
+}
+
+/// The latest forecast for a region and interval next to the measured value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RooftopPvForecastComparison {
+    pub regionid: Arc<str>,
+    pub interval_datetime: DateTime<Utc>,
+    /// Run time of the forecast used, the latest one available for the interval.
+    pub forecast_datetime: DateTime<Utc>,
+    pub forecast_power_mean: Option<f64>,
+    pub forecast_power_poe50: Option<f64>,
+    pub actual_power: Option<f64>,
+}
+
+impl RooftopPvForecastComparison {
+    pub fn lead_time(&self) -> Duration {
+        self.interval_datetime - self.forecast_datetime
+    }
+}
+
+/// Pairs each `MEASUREMENT` actual with the most recent forecast run for the same region and
+/// interval. Actuals with no forecast are left out, as are satellite estimates.
+pub fn compare_latest_forecast_to_actuals(
+    forecasts: &[RecordCurrentRooftopPvForecast],
+    actuals: &[RecordCurrentRooftopPvActual],
+) -> Vec<RooftopPvForecastComparison> {
+    let rows = forecasts
+        .iter()
+        .map(|RecordCurrentRooftopPvForecast::Forecast(forecast)| forecast);
+    let latest: HashMap<(&str, DateTime<Utc>), &RooftopPvForecastData> = latest_by(rows, |row| {
+        (Arc::clone(&row.regionid), row.interval_datetime)
+    })
+    .into_iter()
+    .map(|forecast| ((&*forecast.regionid, forecast.interval_datetime), forecast))
+    .collect();
+
+    let mut comparisons: Vec<RooftopPvForecastComparison> = actuals
+        .iter()
+        .filter_map(|RecordCurrentRooftopPvActual::Variant1(actual)| {
+            if &*actual.type_ != "MEASUREMENT" {
+                return None;
+            }
+            let forecast = latest.get(&(&*actual.regionid, actual.interval_datetime))?;
+            Some(RooftopPvForecastComparison {
+                regionid: Arc::clone(&actual.regionid),
+                interval_datetime: actual.interval_datetime,
+                forecast_datetime: forecast.version_datetime,
+                forecast_power_mean: forecast.power_mean,
+                forecast_power_poe50: forecast.power_poe50,
+                actual_power: actual.power,
+            })
+        })
+        .collect();
+    comparisons.sort_by(|a, b| {
+        (a.interval_datetime, &a.regionid).cmp(&(b.interval_datetime, &b.regionid))
+    });
+    comparisons
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::nem_current_rooftop_pv_actual::process_file_current_rooftop_actual;
+    use std::fs;
+
+    const ROOFTOP_PV_FORECAST: &str = "C,NEMP.WORLD,ROOFTOP_PV_FORECAST,AEMO,PUBLIC,2024/03/03,19:00:21,0000000412705650,,0000000412705640\n\
+        I,ROOFTOP,FORECAST,1,VERSION_DATETIME,REGIONID,INTERVAL_DATETIME,POWERMEAN,POWERPOE50,POWERPOELOW,POWERPOEHIGH,LASTCHANGED\n\
+        D,ROOFTOP,FORECAST,1,\"2024/03/03 18:30:00\",SA1,\"2024/03/03 19:30:00\",9.2,9.1,4.5,14.3,\"2024/03/03 18:30:15\"\n\
+        D,ROOFTOP,FORECAST,1,\"2024/03/03 19:00:00\",SA1,\"2024/03/03 19:30:00\",7.1,7,3.8,10.2,\"2024/03/03 19:00:15\"\n\
+        D,ROOFTOP,FORECAST,1,\"2024/03/03 19:00:00\",SA1,\"2024/03/03 20:00:00\",0.4,0.4,0,1.1,\"2024/03/03 19:00:15\"\n\
+        D,ROOFTOP,FORECAST,1,\"2024/03/03 19:00:00\",NSW1,\"2024/03/03 19:30:00\",0,0,0,0,\"2024/03/03 19:00:15\"\n\
+        C,\"END OF REPORT\",7\n";
+
+    #[test]
+    fn test_process_file_current_rooftop_forecast() {
+        let records = process_file_current_rooftop_forecast(ROOFTOP_PV_FORECAST.as_bytes())
+            .expect("Failed to parse")
+            .records;
+
+        assert_eq!(records.len(), 4);
+        let RecordCurrentRooftopPvForecast::Forecast(forecast) = &records[0];
+        assert_eq!(&*forecast.regionid, "SA1");
+        assert_eq!(forecast.power_poe_high, Some(14.3));
+        assert_eq!(forecast.lead_time(), Duration::try_hours(1).unwrap());
+    }
+
+    #[test]
+    fn test_compare_latest_forecast_to_actuals() {
+        let forecasts = process_file_current_rooftop_forecast(ROOFTOP_PV_FORECAST.as_bytes())
+            .expect("Failed to parse forecasts")
+            .records;
+        let contents = fs::read(
+            "src/fixtures/PUBLIC_ROOFTOP_PV_ACTUAL_MEASUREMENT_20240303200000_0000000412707330.csv",
+        )
+        .expect("Failed to read rooftop PV fixture");
+        let actuals = process_file_current_rooftop_actual(contents.as_slice())
+            .expect("Failed to parse actuals")
+            .records;
+
+        let comparisons = compare_latest_forecast_to_actuals(&forecasts, &actuals);
+
+        assert_eq!(comparisons.len(), 2);
+        let sa = &comparisons[1];
+        assert_eq!(&*sa.regionid, "SA1");
+        assert_eq!(
+            sa.interval_datetime.to_rfc3339(),
+            "2024-03-03T08:30:00+00:00"
+        );
+        assert_eq!(
+            sa.forecast_datetime.to_rfc3339(),
+            "2024-03-03T08:00:00+00:00"
+        );
+        assert_eq!(sa.lead_time(), Duration::try_minutes(30).unwrap());
+        assert_eq!(sa.forecast_power_mean, Some(7.1));
+        assert_eq!(sa.actual_power, Some(6.617));
+    }
+}
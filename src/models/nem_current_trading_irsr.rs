@@ -0,0 +1,130 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::fmt;
+use std::io::Read;
+use std::sync::Arc;
+
+use crate::common::intern::deserialize_interned;
+use crate::common::mms_csv::{DataRow, FromDataRow, MmsCsvReader, MmsRecords};
+use crate::common::unzip_process::{ProcessRecord, RecordsCollection};
+use crate::error::ScraperError;
+use crate::time::datetimezone_conversion::deserialize_sydney_datetime_to_utc;
+
+/// TRADING.IRSR: the inter-regional settlement residue accrued on an interconnector in a trading
+/// interval, split by the region the flow came from.
+#[derive(Debug, Deserialize)]
+pub struct IrsrData {
+    #[serde(rename = "ROW_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    csv_row_identifier: Arc<str>,
+    #[serde(rename = "FILE_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    category: Arc<str>,
+    #[serde(rename = "FILE_SUBTYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    report_type: Arc<str>,
+    #[serde(rename = "FILE_DESCRIPTOR")]
+    report_type_int: u32,
+    #[serde(rename = "TRADING_INTERVAL")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    trading_interval: DateTime<Utc>,
+    #[serde(rename = "INTERCONNECTORID")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    interconnector_id: Arc<str>,
+    #[serde(rename = "FROMREGIONID")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    from_region_id: Arc<str>,
+    #[serde(rename = "RESIDUE")]
+    residue: Option<f64>,
+}
+
+impl fmt::Display for IrsrData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "IrsrData: {{ csv_row_identifier: {}, category: {}, report_type: {}, report_type_int: {}, trading_interval: {:?}, interconnector_id: {:?}, from_region_id: {:?}, residue: {:?} }}",
+            self.csv_row_identifier,
+            self.category,
+            self.report_type,
+            self.report_type_int,
+            self.trading_interval,
+            self.interconnector_id,
+            self.from_region_id,
+            self.residue
+        )
+    }
+}
+
+#[derive(Debug)]
+pub enum RecordCurrentTradingIrsr {
+    Irsr(IrsrData),
+}
+
+impl fmt::Display for RecordCurrentTradingIrsr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecordCurrentTradingIrsr::Irsr(irsr_data) => write!(f, "{}", irsr_data),
+        }
+    }
+}
+
+impl ProcessRecord<RecordCurrentTradingIrsr> for IrsrData {
+    fn process(row: &DataRow) -> Result<RecordCurrentTradingIrsr, ScraperError> {
+        let record = match row.version()? {
+            2 => row.deserialize::<IrsrData>()?,
+            version => return Err(row.unsupported_version(version)),
+        };
+        Ok(RecordCurrentTradingIrsr::Irsr(record))
+    }
+}
+
+impl FromDataRow for RecordCurrentTradingIrsr {
+    fn from_data_row(row: &DataRow) -> Result<Self, ScraperError> {
+        if row.table.is("TRADING", "IRSR") {
+            IrsrData::process(row)
+        } else {
+            Err(row.unknown_table())
+        }
+    }
+}
+
+/// Streams TRADING IRSR records one row at a time.
+pub fn stream_file_current_trading_irsr<R: Read>(
+    reader: R,
+) -> MmsRecords<R, RecordCurrentTradingIrsr> {
+    MmsCsvReader::new(reader).records()
+}
+
+pub fn process_file_current_trading_irsr<R: Read>(
+    reader: R,
+) -> Result<RecordsCollection<RecordCurrentTradingIrsr>, ScraperError> {
+    RecordsCollection::from_records(stream_file_current_trading_irsr(reader))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::unzip_process::unzip_and_process;
+
+    #[test]
+    fn test_process_file_current_trading_irsr_fixture() {
+        let collection = unzip_and_process(
+            "src/fixtures/PUBLIC_TRADING_IRSR_202403031935_0000000412705721.zip",
+            |reader| process_file_current_trading_irsr(reader),
+        )
+        .expect("Failed to process IRSR fixture");
+
+        assert_eq!(collection.records.len(), 12);
+        assert_eq!(
+            collection.report_header.map(|header| header.report_name),
+            Some("TRADING_IRSR".to_string())
+        );
+
+        let RecordCurrentTradingIrsr::Irsr(irsr) = &collection.records[6];
+        assert_eq!(&*irsr.interconnector_id, "VIC1-NSW1");
+        assert_eq!(&*irsr.from_region_id, "VIC1");
+        assert_eq!(irsr.residue, Some(19531.53418));
+        assert_eq!(
+            irsr.trading_interval.to_rfc3339(),
+            "2024-03-03T08:35:00+00:00"
+        );
+    }
+}
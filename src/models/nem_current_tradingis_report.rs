@@ -1,23 +1,28 @@
 use chrono::{DateTime, Utc};
-use csv::ReaderBuilder;
 use serde::Deserialize;
-use std::error::Error;
 use std::fmt;
-use std::io::{BufRead, BufReader};
+use std::io::Read;
+use std::sync::Arc;
 
-use crate::common::unzip_process::ProcessRecord;
+use crate::common::intern::deserialize_interned;
+use crate::common::mms_csv::{DataRow, FromDataRow, MmsCsvReader, MmsRecords};
+use crate::common::unzip_process::{ProcessRecord, RecordsCollection};
+use crate::error::ScraperError;
 use crate::time::datetimezone_conversion::deserialize_sydney_datetime_to_utc;
 
 #[derive(Debug, Deserialize, Default)]
 pub struct InterconnectorData {
     #[serde(rename = "ROW_TYPE")]
-    csv_row_identifier: String,
+    #[serde(deserialize_with = "deserialize_interned")]
+    csv_row_identifier: Arc<str>,
     #[serde(rename = "FILE_TYPE")]
-    category: String,
+    #[serde(deserialize_with = "deserialize_interned")]
+    category: Arc<str>,
     #[serde(rename = "FILE_SUBTYPE")]
-    report_type: String,
+    #[serde(deserialize_with = "deserialize_interned")]
+    report_type: Arc<str>,
     #[serde(rename = "FILE_DESCRIPTOR")]
-    report_type_int: String,
+    report_type_int: u32,
     #[serde(rename = "SETTLEMENTDATE")]
     #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
     settlement_date: DateTime<Utc>,
@@ -49,77 +54,188 @@ impl fmt::Display for InterconnectorData {
 #[derive(Debug, Default, Deserialize)]
 pub struct PriceData {
     #[serde(rename = "ROW_TYPE")]
-    csv_row_identifier: String,
+    #[serde(deserialize_with = "deserialize_interned")]
+    csv_row_identifier: Arc<str>,
     #[serde(rename = "FILE_TYPE")]
-    category: String,
+    #[serde(deserialize_with = "deserialize_interned")]
+    category: Arc<str>,
     #[serde(rename = "FILE_SUBTYPE")]
-    report_type: String,
+    #[serde(deserialize_with = "deserialize_interned")]
+    report_type: Arc<str>,
     #[serde(rename = "FILE_DESCRIPTOR")]
-    report_type_int: String,
-    #[serde(rename = "SETTLEMENT_DATE")]
+    report_type_int: u32,
+    #[serde(rename = "SETTLEMENTDATE")]
     #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
     settlement_date: DateTime<Utc>,
-    #[serde(rename = "RUN_NO")]
+    #[serde(rename = "RUNNO")]
     run_no: Option<u32>,
-    #[serde(rename = "REGION_ID")]
+    #[serde(rename = "REGIONID")]
     region_id: Option<String>,
-    #[serde(rename = "PERIOD_ID")]
+    #[serde(rename = "PERIODID")]
     period_id: Option<u32>,
     #[serde(rename = "RRP")]
     rrp: Option<f64>,
     #[serde(rename = "EEP")]
     eep: Option<f64>,
-    #[serde(rename = "INVALID_FLAG")]
+    #[serde(rename = "INVALIDFLAG")]
     invalid_flag: Option<u32>,
-    #[serde(rename = "LAST_CHANGED")]
+    #[serde(rename = "LASTCHANGED")]
     #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
     last_changed: DateTime<Utc>,
     #[serde(rename = "ROP")]
     rop: Option<f64>,
-    #[serde(rename = "RAISE6SEC_RRP")]
+    #[serde(rename = "RAISE6SECRRP")]
     raise_6_sec_rrp: Option<f64>,
-    #[serde(rename = "RAISE6SEC_ROP")]
+    #[serde(rename = "RAISE6SECROP")]
     raise_6_sec_rop: Option<f64>,
-    #[serde(rename = "RAISE60SEC_RRP")]
+    #[serde(rename = "RAISE60SECRRP")]
     raise_60_sec_rrp: Option<f64>,
-    #[serde(rename = "RAISE60SEC_ROP")]
+    #[serde(rename = "RAISE60SECROP")]
     raise_60_sec_rop: Option<f64>,
-    #[serde(rename = "RAISE5MIN_RRP")]
+    #[serde(rename = "RAISE5MINRRP")]
     raise_5_min_rrp: Option<f64>,
-    #[serde(rename = "RAISE5MIN_ROP")]
+    #[serde(rename = "RAISE5MINROP")]
     raise_5_min_rop: Option<f64>,
-    #[serde(rename = "RAISEREG_RRP")]
+    #[serde(rename = "RAISEREGRRP")]
     raise_reg_rrp: Option<f64>,
-    #[serde(rename = "RAISEREG_ROP")]
+    #[serde(rename = "RAISEREGROP")]
     raise_reg_rop: Option<f64>,
-    #[serde(rename = "LOWER6SEC_RRP")]
+    #[serde(rename = "LOWER6SECRRP")]
     lower_6_sec_rrp: Option<f64>,
-    #[serde(rename = "LOWER6SEC_ROP")]
+    #[serde(rename = "LOWER6SECROP")]
     lower_6_sec_rop: Option<f64>,
-    #[serde(rename = "LOWER60SEC_RRP")]
+    #[serde(rename = "LOWER60SECRRP")]
     lower_60_sec_rrp: Option<f64>,
-    #[serde(rename = "LOWER60SEC_ROP")]
+    #[serde(rename = "LOWER60SECROP")]
     lower_60_sec_rop: Option<f64>,
-    #[serde(rename = "LOWER5MIN_RRP")]
+    #[serde(rename = "LOWER5MINRRP")]
     lower_5_min_rrp: Option<f64>,
-    #[serde(rename = "LOWER5MIN_ROP")]
+    #[serde(rename = "LOWER5MINROP")]
     lower_5_min_rop: Option<f64>,
-    #[serde(rename = "LOWERREG_RRP")]
+    #[serde(rename = "LOWERREGRRP")]
     lower_reg_rrp: Option<f64>,
-    #[serde(rename = "LOWERREG_ROP")]
+    #[serde(rename = "LOWERREGROP")]
     lower_reg_rop: Option<f64>,
-    #[serde(rename = "RAISE1SEC_RRP")]
+    #[serde(rename = "RAISE1SECRRP")]
     raise_1_sec_rrp: Option<f64>,
-    #[serde(rename = "RAISE1SEC_ROP")]
+    #[serde(rename = "RAISE1SECROP")]
     raise_1_sec_rop: Option<f64>,
-    #[serde(rename = "LOWER1SEC_RRP")]
+    #[serde(rename = "LOWER1SECRRP")]
     lower_1_sec_rrp: Option<f64>,
-    #[serde(rename = "LOWER1SEC_ROP")]
+    #[serde(rename = "LOWER1SECROP")]
     lower_1_sec_rop: Option<f64>,
     #[serde(rename = "PRICE_STATUS")]
     price_status: Option<String>,
 }
 
+/// TRADING.PRICE version 2, published before the 1-second FCAS markets were added.
+#[derive(Debug, Deserialize)]
+pub struct PriceDataV2 {
+    #[serde(rename = "ROW_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    csv_row_identifier: Arc<str>,
+    #[serde(rename = "FILE_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    category: Arc<str>,
+    #[serde(rename = "FILE_SUBTYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    report_type: Arc<str>,
+    #[serde(rename = "FILE_DESCRIPTOR")]
+    report_type_int: u32,
+    #[serde(rename = "SETTLEMENTDATE")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    settlement_date: DateTime<Utc>,
+    #[serde(rename = "RUNNO")]
+    run_no: Option<u32>,
+    #[serde(rename = "REGIONID")]
+    region_id: Option<String>,
+    #[serde(rename = "PERIODID")]
+    period_id: Option<u32>,
+    #[serde(rename = "RRP")]
+    rrp: Option<f64>,
+    #[serde(rename = "EEP")]
+    eep: Option<f64>,
+    #[serde(rename = "INVALIDFLAG")]
+    invalid_flag: Option<u32>,
+    #[serde(rename = "LASTCHANGED")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    last_changed: DateTime<Utc>,
+    #[serde(rename = "ROP")]
+    rop: Option<f64>,
+    #[serde(rename = "RAISE6SECRRP")]
+    raise_6_sec_rrp: Option<f64>,
+    #[serde(rename = "RAISE6SECROP")]
+    raise_6_sec_rop: Option<f64>,
+    #[serde(rename = "RAISE60SECRRP")]
+    raise_60_sec_rrp: Option<f64>,
+    #[serde(rename = "RAISE60SECROP")]
+    raise_60_sec_rop: Option<f64>,
+    #[serde(rename = "RAISE5MINRRP")]
+    raise_5_min_rrp: Option<f64>,
+    #[serde(rename = "RAISE5MINROP")]
+    raise_5_min_rop: Option<f64>,
+    #[serde(rename = "RAISEREGRRP")]
+    raise_reg_rrp: Option<f64>,
+    #[serde(rename = "RAISEREGROP")]
+    raise_reg_rop: Option<f64>,
+    #[serde(rename = "LOWER6SECRRP")]
+    lower_6_sec_rrp: Option<f64>,
+    #[serde(rename = "LOWER6SECROP")]
+    lower_6_sec_rop: Option<f64>,
+    #[serde(rename = "LOWER60SECRRP")]
+    lower_60_sec_rrp: Option<f64>,
+    #[serde(rename = "LOWER60SECROP")]
+    lower_60_sec_rop: Option<f64>,
+    #[serde(rename = "LOWER5MINRRP")]
+    lower_5_min_rrp: Option<f64>,
+    #[serde(rename = "LOWER5MINROP")]
+    lower_5_min_rop: Option<f64>,
+    #[serde(rename = "LOWERREGRRP")]
+    lower_reg_rrp: Option<f64>,
+    #[serde(rename = "LOWERREGROP")]
+    lower_reg_rop: Option<f64>,
+    #[serde(rename = "PRICE_STATUS")]
+    price_status: Option<String>,
+}
+
+impl From<PriceDataV2> for PriceData {
+    fn from(v2: PriceDataV2) -> Self {
+        PriceData {
+            csv_row_identifier: v2.csv_row_identifier,
+            category: v2.category,
+            report_type: v2.report_type,
+            report_type_int: v2.report_type_int,
+            settlement_date: v2.settlement_date,
+            run_no: v2.run_no,
+            region_id: v2.region_id,
+            period_id: v2.period_id,
+            rrp: v2.rrp,
+            eep: v2.eep,
+            invalid_flag: v2.invalid_flag,
+            last_changed: v2.last_changed,
+            rop: v2.rop,
+            raise_6_sec_rrp: v2.raise_6_sec_rrp,
+            raise_6_sec_rop: v2.raise_6_sec_rop,
+            raise_60_sec_rrp: v2.raise_60_sec_rrp,
+            raise_60_sec_rop: v2.raise_60_sec_rop,
+            raise_5_min_rrp: v2.raise_5_min_rrp,
+            raise_5_min_rop: v2.raise_5_min_rop,
+            raise_reg_rrp: v2.raise_reg_rrp,
+            raise_reg_rop: v2.raise_reg_rop,
+            lower_6_sec_rrp: v2.lower_6_sec_rrp,
+            lower_6_sec_rop: v2.lower_6_sec_rop,
+            lower_60_sec_rrp: v2.lower_60_sec_rrp,
+            lower_60_sec_rop: v2.lower_60_sec_rop,
+            lower_5_min_rrp: v2.lower_5_min_rrp,
+            lower_5_min_rop: v2.lower_5_min_rop,
+            lower_reg_rrp: v2.lower_reg_rrp,
+            lower_reg_rop: v2.lower_reg_rop,
+            price_status: v2.price_status,
+            ..Default::default()
+        }
+    }
+}
+
 impl fmt::Display for PriceData {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "PriceData: {{ csv_row_identifier: {}, category: {}, report_type: {}, report_type_int: {}, settlement_date: {:?}, run_no: {:?}, region_id: {:?}, period_id: {:?}, rrp: {:?}, eep: {:?}, invalid_flag: {:?}, last_changed: {:?}, rop: {:?}, raise_6_sec_rrp: {:?}, raise_6_sec_rop: {:?}, raise_60_sec_rrp: {:?}, raise_60_sec_rop: {:?}, raise_5_min_rrp: {:?}, raise_5_min_rop: {:?}, raise_reg_rrp: {:?}, raise_reg_rop: {:?}, lower_6_sec_rrp: {:?}, lower_6_sec_rop: {:?}, lower_60_sec_rrp: {:?}, lower_60_sec_rop: {:?}, lower_5_min_rrp: {:?}, lower_5_min_rop: {:?}, lower_reg_rrp: {:?}, lower_reg_rop: {:?}, raise_1_sec_rrp: {:?}, raise_1_sec_rop: {:?}, lower_1_sec_rrp: {:?}, lower_1_sec_rop: {:?}, price_status: {:?} }}",
@@ -181,58 +297,133 @@ impl fmt::Display for RecordCurrentTradingIs {
 }
 
 impl ProcessRecord<RecordCurrentTradingIs> for InterconnectorData {
-    fn process(line: &str) -> Result<RecordCurrentTradingIs, Box<dyn Error>> {
-        let mut rdr = ReaderBuilder::new()
-            .has_headers(false)
-            .from_reader(line.as_bytes());
-        let record = rdr
-            .deserialize::<InterconnectorData>()
-            .next()
-            .ok_or("Error attempting to deserialize InterconnectorData")??;
+    fn process(row: &DataRow) -> Result<RecordCurrentTradingIs, ScraperError> {
+        let record = match row.version()? {
+            2 => row.deserialize::<InterconnectorData>()?,
+            version => return Err(row.unsupported_version(version)),
+        };
         Ok(RecordCurrentTradingIs::Variant1(Box::new(record)))
     }
 }
 
 impl ProcessRecord<RecordCurrentTradingIs> for PriceData {
-    fn process(line: &str) -> Result<RecordCurrentTradingIs, Box<dyn Error>> {
-        let mut rdr = ReaderBuilder::new()
-            .has_headers(false)
-            .from_reader(line.as_bytes());
-        let record = rdr
-            .deserialize::<PriceData>()
-            .next()
-            .ok_or("Error attempting to deserialize PriceData")??;
+    fn process(row: &DataRow) -> Result<RecordCurrentTradingIs, ScraperError> {
+        let record = match row.version()? {
+            2 => row.deserialize_upgraded::<PriceDataV2, PriceData>()?,
+            3 => row.deserialize::<PriceData>()?,
+            version => return Err(row.unsupported_version(version)),
+        };
         Ok(RecordCurrentTradingIs::Variant2(Box::new(record)))
     }
 }
 
-pub fn process_file_current_trading_is(
-    contents: String,
-) -> Result<Vec<RecordCurrentTradingIs>, Box<dyn Error>> {
-    let byte_data = contents.as_bytes();
-    let reader = BufReader::new(byte_data);
-    let mut records: Vec<RecordCurrentTradingIs> = Vec::new();
-
-    for line_result in reader.lines() {
-        let line = line_result?;
-        if line.starts_with("I,TRADING,INTERCONNECTORRES") || line.starts_with("I,TRADING,PRICE") {
-            println!("Information Row (header): {:?}", &line);
+impl FromDataRow for RecordCurrentTradingIs {
+    fn from_data_row(row: &DataRow) -> Result<Self, ScraperError> {
+        if row.table.is("TRADING", "INTERCONNECTORRES") {
+            InterconnectorData::process(row)
+        } else if row.table.is("TRADING", "PRICE") {
+            PriceData::process(row)
+        } else {
+            Err(row.unknown_table())
+        }
+    }
+}
+
+/// Streams TradingIS records one row at a time.
+pub fn stream_file_current_trading_is<R: Read>(reader: R) -> MmsRecords<R, RecordCurrentTradingIs> {
+    MmsCsvReader::new(reader).records()
+}
+
+pub fn process_file_current_trading_is<R: Read>(
+    reader: R,
+) -> Result<RecordsCollection<RecordCurrentTradingIs>, ScraperError> {
+    RecordsCollection::from_records(stream_file_current_trading_is(reader))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+
+    #[test]
+    fn test_process_file_current_trading_is_fixture() {
+        let file = File::open("src/fixtures/PUBLIC_TRADINGIS_202403031335_0000000412683134.CSV")
+            .expect("Failed to open TradingIS fixture");
+
+        let collection = process_file_current_trading_is(file).expect("Failed to parse");
+        let header = collection.report_header.expect("Missing report header");
+        assert_eq!(header.report_name, "TRADINGIS");
+        assert_eq!(
+            header.previous_report_id.as_deref(),
+            Some("0000000412683133")
+        );
+
+        let records = collection.records;
+
+        assert_eq!(records.len(), 11);
+        match &records[1] {
+            RecordCurrentTradingIs::Variant1(interconnector) => {
+                assert_eq!(
+                    interconnector.interconnector_id.as_deref(),
+                    Some("NSW1-QLD1")
+                );
+                assert_eq!(interconnector.metered_mw_flow, Some(587.0));
+                assert_eq!(
+                    interconnector.last_changed.as_deref(),
+                    Some("2024/03/03 13:30:04")
+                );
+            }
+            other => panic!("Expected InterconnectorData, got {}", other),
         }
-        match line.chars().next() {
-            Some('C') | Some('I') => continue,
-            Some('D') => {
-                if line.starts_with("D,TRADING,INTERCONNECTORRES") {
-                    records.push(InterconnectorData::process(&line)?);
-                } else if line.starts_with("D,TRADING,PRICE") {
-                    records.push(PriceData::process(&line)?);
-                } else {
-                    println!("Parser Error: RecordCurrentTradingIs\n line: {:?}", &line);
-                    return Err("Unknown record type for RecordCurrentTradingIs Variants".into());
-                }
+        match &records[10] {
+            RecordCurrentTradingIs::Variant2(price) => {
+                assert_eq!(price.region_id.as_deref(), Some("VIC1"));
+                assert_eq!(price.rrp, Some(-67.1));
+                assert_eq!(price.raise_reg_rrp, Some(0.91));
+                assert_eq!(price.lower_reg_rop, Some(3.76));
+                assert_eq!(price.price_status.as_deref(), Some("FIRM"));
+                assert_eq!(
+                    price.settlement_date.to_rfc3339(),
+                    "2024-03-03T02:35:00+00:00"
+                );
             }
-            _ => return Err("Invalid line format".into()),
+            other => panic!("Expected PriceData, got {}", other),
         }
     }
 
-    Ok(records)
+    #[test]
+    fn test_price_version_2_is_upgraded() {
+        let contents = "I,TRADING,PRICE,2,SETTLEMENTDATE,RUNNO,REGIONID,PERIODID,RRP,EEP,INVALIDFLAG,LASTCHANGED,ROP,RAISE6SECRRP,RAISE6SECROP,RAISE60SECRRP,RAISE60SECROP,RAISE5MINRRP,RAISE5MINROP,RAISEREGRRP,RAISEREGROP,LOWER6SECRRP,LOWER6SECROP,LOWER60SECRRP,LOWER60SECROP,LOWER5MINRRP,LOWER5MINROP,LOWERREGRRP,LOWERREGROP,PRICE_STATUS\n\
+            D,TRADING,PRICE,2,\"2019/12/01 00:30:00\",1,NSW1,1,45.5,0,0,\"2019/12/01 00:25:04\",45.5,0.1,0.1,0.2,0.2,0.3,0.3,9,9,0.4,0.4,0.5,0.5,0.6,0.6,7,7,FIRM\n\
+            C,\"END OF REPORT\",3\n";
+
+        let records = process_file_current_trading_is(contents.as_bytes())
+            .unwrap()
+            .records;
+
+        match &records[0] {
+            RecordCurrentTradingIs::Variant2(price) => {
+                assert_eq!(price.report_type_int, 2);
+                assert_eq!(price.rrp, Some(45.5));
+                assert_eq!(price.lower_reg_rop, Some(7.0));
+                assert_eq!(price.raise_1_sec_rrp, None);
+                assert_eq!(price.lower_1_sec_rop, None);
+            }
+            other => panic!("Expected PriceData, got {}", other),
+        }
+    }
+
+    #[test]
+    fn test_unknown_price_version_is_rejected() {
+        let contents = "I,TRADING,PRICE,9,SETTLEMENTDATE,REGIONID,LASTCHANGED\n\
+            D,TRADING,PRICE,9,\"2024/03/03 13:35:00\",NSW1,\"2024/03/03 13:30:04\"\n\
+            C,\"END OF REPORT\",3\n";
+
+        let err = process_file_current_trading_is(contents.as_bytes()).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Unsupported table version 9 at line 2, table TRADING.PRICE"
+        );
+    }
 }
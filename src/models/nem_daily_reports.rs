@@ -0,0 +1,486 @@
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::fmt;
+use std::io::Read;
+use std::ops::Range;
+use std::sync::Arc;
+
+use crate::common::intern::deserialize_interned;
+use crate::common::mms_csv::{DataRow, FromDataRow, MmsCsvReader, MmsRecords, TableKey};
+use crate::common::report_header::ReportHeader;
+use crate::common::unit::UnitRow;
+use crate::common::unzip_process::{
+    record_fields, ProcessRecord, RecordsCollection, RejectedEntry, RejectedRow, RowErrorPolicy,
+};
+use crate::error::ScraperError;
+use crate::models::nem_dispatch_legacy::{InterconnectorDispatchData, RegionDispatchData};
+use crate::time::datetimezone_conversion::{
+    deserialize_optional_sydney_datetime_to_utc, deserialize_sydney_datetime_to_utc,
+};
+
+// PUBLIC_DAILY files hold every dispatch interval of a market day. DREGION and DINT use the
+// same layout as the legacy 5-minute dispatch file, so those models are shared.
+
+/// DUNIT: dispatch target and FCAS enablement of a unit for a 5-minute interval.
+#[derive(Debug, Deserialize)]
+pub struct UnitDispatchData {
+    #[serde(rename = "ROW_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    csv_row_identifier: Arc<str>,
+    #[serde(rename = "FILE_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    category: Arc<str>,
+    #[serde(rename = "FILE_SUBTYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    report_type: Arc<str>,
+    #[serde(rename = "FILE_DESCRIPTOR")]
+    report_type_int: u32,
+    #[serde(rename = "SETTLEMENTDATE")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    settlement_date: DateTime<Utc>,
+    #[serde(rename = "RUNNO")]
+    run_no: Option<u32>,
+    #[serde(rename = "DUID")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    duid: Arc<str>,
+    #[serde(rename = "INTERVENTION")]
+    intervention: Option<u32>,
+    #[serde(rename = "DISPATCHMODE")]
+    dispatch_mode: Option<u32>,
+    #[serde(rename = "AGCSTATUS")]
+    agc_status: Option<u32>,
+    #[serde(rename = "INITIALMW")]
+    initial_mw: Option<f64>,
+    #[serde(rename = "TOTALCLEARED")]
+    total_cleared: Option<f64>,
+    #[serde(rename = "RAMPDOWNRATE")]
+    ramp_down_rate: Option<f64>,
+    #[serde(rename = "RAMPUPRATE")]
+    ramp_up_rate: Option<f64>,
+    #[serde(rename = "LOWER5MIN")]
+    lower_5_min: Option<f64>,
+    #[serde(rename = "LOWER60SEC")]
+    lower_60_sec: Option<f64>,
+    #[serde(rename = "LOWER6SEC")]
+    lower_6_sec: Option<f64>,
+    #[serde(rename = "RAISE5MIN")]
+    raise_5_min: Option<f64>,
+    #[serde(rename = "RAISE60SEC")]
+    raise_60_sec: Option<f64>,
+    #[serde(rename = "RAISE6SEC")]
+    raise_6_sec: Option<f64>,
+    #[serde(rename = "LOWERREG")]
+    lower_reg: Option<f64>,
+    #[serde(rename = "RAISEREG")]
+    raise_reg: Option<f64>,
+    #[serde(rename = "AVAILABILITY")]
+    availability: Option<f64>,
+    #[serde(rename = "SEMIDISPATCHCAP")]
+    semi_dispatch_cap: Option<u32>,
+}
+
+impl fmt::Display for UnitDispatchData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "UnitDispatchData: {{ csv_row_identifier: {}, category: {}, report_type: {}, report_type_int: {}, settlement_date: {:?}, run_no: {:?}, duid: {:?}, intervention: {:?}, dispatch_mode: {:?}, agc_status: {:?}, initial_mw: {:?}, total_cleared: {:?}, ramp_down_rate: {:?}, ramp_up_rate: {:?}, lower_5_min: {:?}, lower_60_sec: {:?}, lower_6_sec: {:?}, raise_5_min: {:?}, raise_60_sec: {:?}, raise_6_sec: {:?}, lower_reg: {:?}, raise_reg: {:?}, availability: {:?}, semi_dispatch_cap: {:?} }}",
+            self.csv_row_identifier,
+            self.category,
+            self.report_type,
+            self.report_type_int,
+            self.settlement_date,
+            self.run_no,
+            self.duid,
+            self.intervention,
+            self.dispatch_mode,
+            self.agc_status,
+            self.initial_mw,
+            self.total_cleared,
+            self.ramp_down_rate,
+            self.ramp_up_rate,
+            self.lower_5_min,
+            self.lower_60_sec,
+            self.lower_6_sec,
+            self.raise_5_min,
+            self.raise_60_sec,
+            self.raise_6_sec,
+            self.lower_reg,
+            self.raise_reg,
+            self.availability,
+            self.semi_dispatch_cap
+        )
+    }
+}
+
+/// MARKET_SUSPEND.REGIME_SUM: the pricing regime applied to a region during a market
+/// suspension. `END_INTERVAL` is empty while the suspension is in progress.
+#[derive(Debug, Deserialize)]
+pub struct MarketSuspensionData {
+    #[serde(rename = "ROW_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    csv_row_identifier: Arc<str>,
+    #[serde(rename = "FILE_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    category: Arc<str>,
+    #[serde(rename = "FILE_SUBTYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    report_type: Arc<str>,
+    #[serde(rename = "FILE_DESCRIPTOR")]
+    report_type_int: u32,
+    #[serde(rename = "SUSPENSION_ID")]
+    suspension_id: String,
+    #[serde(rename = "REGIONID")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    region_id: Arc<str>,
+    #[serde(rename = "START_INTERVAL")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    start_interval: DateTime<Utc>,
+    #[serde(rename = "END_INTERVAL")]
+    #[serde(deserialize_with = "deserialize_optional_sydney_datetime_to_utc")]
+    end_interval: Option<DateTime<Utc>>,
+    #[serde(rename = "PRICING_REGIME")]
+    pricing_regime: Option<String>,
+    #[serde(rename = "LASTCHANGED")]
+    #[serde(deserialize_with = "deserialize_optional_sydney_datetime_to_utc")]
+    last_changed: Option<DateTime<Utc>>,
+}
+
+impl fmt::Display for MarketSuspensionData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "MarketSuspensionData: {{ csv_row_identifier: {}, category: {}, report_type: {}, report_type_int: {}, suspension_id: {:?}, region_id: {:?}, start_interval: {:?}, end_interval: {:?}, pricing_regime: {:?}, last_changed: {:?} }}",
+            self.csv_row_identifier,
+            self.category,
+            self.report_type,
+            self.report_type_int,
+            self.suspension_id,
+            self.region_id,
+            self.start_interval,
+            self.end_interval,
+            self.pricing_regime,
+            self.last_changed
+        )
+    }
+}
+
+impl UnitRow for UnitDispatchData {
+    fn duid(&self) -> &str {
+        &self.duid
+    }
+
+    fn settlement_datetime(&self) -> DateTime<Utc> {
+        self.settlement_date
+    }
+}
+
+#[derive(Debug)]
+pub enum RecordDailyReports {
+    // Region is boxed to comply with cargo clippy warning for large enums
+    Region(Box<RegionDispatchData>),
+    Unit(UnitDispatchData),
+    Interconnector(InterconnectorDispatchData),
+    MarketSuspension(MarketSuspensionData),
+}
+
+impl fmt::Display for RecordDailyReports {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecordDailyReports::Region(region) => write!(f, "{}", region),
+            RecordDailyReports::Unit(unit) => write!(f, "{}", unit),
+            RecordDailyReports::Interconnector(interconnector) => write!(f, "{}", interconnector),
+            RecordDailyReports::MarketSuspension(suspension) => write!(f, "{}", suspension),
+        }
+    }
+}
+
+impl ProcessRecord<RecordDailyReports> for RegionDispatchData {
+    fn process(row: &DataRow) -> Result<RecordDailyReports, ScraperError> {
+        let record = deserialize_version::<RegionDispatchData>(row, 3)?;
+        Ok(RecordDailyReports::Region(Box::new(record)))
+    }
+}
+
+impl ProcessRecord<RecordDailyReports> for UnitDispatchData {
+    fn process(row: &DataRow) -> Result<RecordDailyReports, ScraperError> {
+        let record = deserialize_version::<UnitDispatchData>(row, 3)?;
+        Ok(RecordDailyReports::Unit(record))
+    }
+}
+
+impl ProcessRecord<RecordDailyReports> for InterconnectorDispatchData {
+    fn process(row: &DataRow) -> Result<RecordDailyReports, ScraperError> {
+        let record = deserialize_version::<InterconnectorDispatchData>(row, 4)?;
+        Ok(RecordDailyReports::Interconnector(record))
+    }
+}
+
+impl ProcessRecord<RecordDailyReports> for MarketSuspensionData {
+    fn process(row: &DataRow) -> Result<RecordDailyReports, ScraperError> {
+        let record = deserialize_version::<MarketSuspensionData>(row, 1)?;
+        Ok(RecordDailyReports::MarketSuspension(record))
+    }
+}
+
+impl FromDataRow for RecordDailyReports {
+    fn from_data_row(row: &DataRow) -> Result<Self, ScraperError> {
+        if row.table.is("DREGION", "") {
+            RegionDispatchData::process(row)
+        } else if row.table.is("DUNIT", "") {
+            UnitDispatchData::process(row)
+        } else if row.table.is("DINT", "") {
+            InterconnectorDispatchData::process(row)
+        } else if row.table.is("MARKET_SUSPEND", "REGIME_SUM") {
+            MarketSuspensionData::process(row)
+        } else {
+            Err(row.unknown_table())
+        }
+    }
+
+    // The daily file also carries trading interval (TREGION, TUNIT, TINT) tables
+    fn reads_table(table: &TableKey) -> bool {
+        table.is("DREGION", "")
+            || table.is("DUNIT", "")
+            || table.is("DINT", "")
+            || table.is("MARKET_SUSPEND", "REGIME_SUM")
+    }
+}
+
+/// Streams Daily_Reports records one row at a time.
+pub fn stream_file_daily_reports<R: Read>(reader: R) -> MmsRecords<R, RecordDailyReports> {
+    MmsCsvReader::new(reader).records()
+}
+
+pub fn process_file_daily_reports<R: Read>(
+    reader: R,
+) -> Result<RecordsCollection<RecordDailyReports>, ScraperError> {
+    RecordsCollection::from_records(stream_file_daily_reports(reader))
+}
+
+/// Deserializes a row of a table the daily file publishes in a single version.
+fn deserialize_version<T: DeserializeOwned>(
+    row: &DataRow,
+    version: u32,
+) -> Result<T, ScraperError> {
+    match row.version()? {
+        found if found == version => row.deserialize::<T>(),
+        found => Err(row.unsupported_version(found)),
+    }
+}
+
+/// A Daily_Reports file, or an archive of them, with each table in its own vector in file
+/// order. Rows dropped under a lenient [`RowErrorPolicy`] or [`EntryErrorPolicy`] are counted
+/// and kept just as in a [`RecordsCollection`].
+///
+/// [`EntryErrorPolicy`]: crate::common::unzip_process::EntryErrorPolicy
+#[derive(Debug, Default)]
+pub struct DailyReport {
+    pub report_header: Option<ReportHeader>,
+    pub regions: Vec<RegionDispatchData>,
+    pub units: Vec<UnitDispatchData>,
+    pub interconnectors: Vec<InterconnectorDispatchData>,
+    pub market_suspensions: Vec<MarketSuspensionData>,
+    pub record_sets: Vec<DailyRecordSet>,
+    pub source_file: Option<String>,
+    pub rejected_row_count: usize,
+    pub rejected_rows: Vec<RejectedRow>,
+    pub rejected_entries: Vec<RejectedEntry>,
+}
+
+/// The rows one file of an archive contributed to a [`DailyReport`], as index ranges into
+/// each of its vectors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DailyRecordSet {
+    pub file_name: String,
+    pub report_header: Option<ReportHeader>,
+    pub regions: Range<usize>,
+    pub units: Range<usize>,
+    pub interconnectors: Range<usize>,
+    pub market_suspensions: Range<usize>,
+}
+
+impl DailyReport {
+    /// Reads a Daily_Reports file straight into its table vectors, failing on the first bad row.
+    pub fn from_reader<R: Read>(reader: R) -> Result<Self, ScraperError> {
+        Self::from_reader_with_policy(reader, RowErrorPolicy::Strict)
+    }
+
+    /// Reads a Daily_Reports file straight into its table vectors, handling bad rows according
+    /// to `policy`.
+    pub fn from_reader_with_policy<R: Read>(
+        reader: R,
+        policy: RowErrorPolicy,
+    ) -> Result<Self, ScraperError> {
+        let mut reader = MmsCsvReader::new(reader);
+        let mut report = DailyReport::default();
+        loop {
+            let result = match reader.next_data_row() {
+                Ok(Some(row)) => report.read_row(&row),
+                Ok(None) => break,
+                Err(e) => Err(e),
+            };
+            if let Err(error) = result {
+                policy.reject(
+                    error,
+                    || record_fields(reader.current_record()),
+                    &mut report.rejected_row_count,
+                    &mut report.rejected_rows,
+                )?;
+            }
+        }
+        report.report_header = reader.report_header().cloned();
+        Ok(report)
+    }
+
+    // The daily file also carries trading interval (TREGION, TUNIT, TINT) tables, which are
+    // skipped
+    fn read_row(&mut self, row: &DataRow) -> Result<(), ScraperError> {
+        if row.table.is("DREGION", "") {
+            self.regions.push(deserialize_version(row, 3)?);
+        } else if row.table.is("DUNIT", "") {
+            self.units.push(deserialize_version(row, 3)?);
+        } else if row.table.is("DINT", "") {
+            self.interconnectors.push(deserialize_version(row, 4)?);
+        } else if row.table.is("MARKET_SUSPEND", "REGIME_SUM") {
+            self.market_suspensions.push(deserialize_version(row, 1)?);
+        }
+        Ok(())
+    }
+
+    fn push(&mut self, record: RecordDailyReports) {
+        match record {
+            RecordDailyReports::Region(region) => self.regions.push(*region),
+            RecordDailyReports::Unit(unit) => self.units.push(unit),
+            RecordDailyReports::Interconnector(interconnector) => {
+                self.interconnectors.push(interconnector)
+            }
+            RecordDailyReports::MarketSuspension(suspension) => {
+                self.market_suspensions.push(suspension)
+            }
+        }
+    }
+
+    fn lengths(&self) -> [usize; 4] {
+        [
+            self.regions.len(),
+            self.units.len(),
+            self.interconnectors.len(),
+            self.market_suspensions.len(),
+        ]
+    }
+}
+
+/// Splits the records of an archive processed with [`process_file_daily_reports`] into their
+/// table vectors, keeping each file's share as a [`DailyRecordSet`].
+impl From<RecordsCollection<RecordDailyReports>> for DailyReport {
+    fn from(collection: RecordsCollection<RecordDailyReports>) -> Self {
+        let mut report = DailyReport {
+            report_header: collection.report_header,
+            source_file: collection.source_file,
+            rejected_row_count: collection.rejected_row_count,
+            rejected_rows: collection.rejected_rows,
+            rejected_entries: collection.rejected_entries,
+            ..Default::default()
+        };
+        let mut records = collection.records.into_iter();
+        for set in collection.record_sets {
+            let start = report.lengths();
+            for record in records.by_ref().take(set.records.len()) {
+                report.push(record);
+            }
+            let end = report.lengths();
+            report.record_sets.push(DailyRecordSet {
+                file_name: set.file_name,
+                report_header: set.report_header,
+                regions: start[0]..end[0],
+                units: start[1]..end[1],
+                interconnectors: start[2]..end[2],
+                market_suspensions: start[3]..end[3],
+            });
+        }
+        for record in records {
+            report.push(record);
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAILY: &str = "C,NEMP.WORLD,DAILY,AEMO,PUBLIC,2024/03/16,04:05:12,0000000413812345,DAILY,0000000413812344\n\
+        I,DREGION,,3,SETTLEMENTDATE,RUNNO,REGIONID,INTERVENTION,RRP,EEP,ROP,APCFLAG,MARKETSUSPENDEDFLAG,TOTALDEMAND\n\
+        D,DREGION,,3,\"2024/03/15 04:05:00\",1,NSW1,0,61.5,0,61.5,0,0,6512.37\n\
+        D,DREGION,,3,\"2024/03/15 04:05:00\",1,SA1,0,12.1,0,12.1,0,0,1104.82\n\
+        I,DUNIT,,3,SETTLEMENTDATE,RUNNO,DUID,INTERVENTION,DISPATCHMODE,AGCSTATUS,INITIALMW,TOTALCLEARED,RAMPDOWNRATE,RAMPUPRATE,LOWER5MIN,LOWER60SEC,LOWER6SEC,RAISE5MIN,RAISE60SEC,RAISE6SEC,LOWERREG,RAISEREG,AVAILABILITY,SEMIDISPATCHCAP\n\
+        D,DUNIT,,3,\"2024/03/15 04:05:00\",1,BAYSW1,0,0,1,471.2,470,180,180,0,0,0,0,0,0,0,0,660,0\n\
+        I,TREGION,,2,SETTLEMENTDATE,RUNNO,REGIONID,PERIODID,RRP\n\
+        D,TREGION,,2,\"2024/03/15 04:30:00\",1,NSW1,1,63.2\n\
+        I,DINT,,4,SETTLEMENTDATE,RUNNO,INTERCONNECTORID,INTERVENTION,METEREDMWFLOW,MWFLOW,MWLOSSES,MARGINALVALUE,VIOLATIONDEGREE,IMPORTLIMIT,EXPORTLIMIT,MARGINALLOSS,EXPORTGENCONID,IMPORTGENCONID,FCASEXPORTLIMIT,FCASIMPORTLIMIT\n\
+        D,DINT,,4,\"2024/03/15 04:05:00\",1,N-Q-MNSP1,0,11.8,7.65142,0.5167,0,0,-68.2,31.2,1.06941,N>N_LSDU_9U6_1,QNTE_ROC,31.2,-68.2\n\
+        I,MARKET_SUSPEND,REGIME_SUM,1,SUSPENSION_ID,REGIONID,START_INTERVAL,END_INTERVAL,PRICING_REGIME,LASTCHANGED\n\
+        D,MARKET_SUSPEND,REGIME_SUM,1,MS_20240315_1,SA1,\"2024/03/15 04:05:00\",,SCHEDULE,\"2024/03/15 04:00:12\"\n\
+        C,\"END OF REPORT\",13\n";
+
+    #[test]
+    fn test_daily_report_routes_tables() {
+        let report = DailyReport::from_reader(DAILY.as_bytes()).expect("Failed to parse");
+
+        assert_eq!(
+            report
+                .report_header
+                .as_ref()
+                .map(|header| header.report_name.as_str()),
+            Some("DAILY")
+        );
+        assert_eq!(report.regions.len(), 2);
+        assert_eq!(report.units.len(), 1);
+        assert_eq!(report.interconnectors.len(), 1);
+        assert_eq!(report.market_suspensions.len(), 1);
+
+        let unit = &report.units[0];
+        assert_eq!(unit.duid(), "BAYSW1");
+        assert_eq!(unit.total_cleared, Some(470.0));
+        assert_eq!(
+            unit.settlement_datetime().to_rfc3339(),
+            "2024-03-14T17:05:00+00:00"
+        );
+
+        let suspension = &report.market_suspensions[0];
+        assert_eq!(&*suspension.region_id, "SA1");
+        assert_eq!(suspension.end_interval, None);
+        assert_eq!(suspension.pricing_regime.as_deref(), Some("SCHEDULE"));
+    }
+
+    #[test]
+    fn test_daily_report_keeps_rejected_rows() {
+        let contents = DAILY.replace(",BAYSW1,0,0,1,471.2,470,", ",BAYSW1,0,0,1,471.2,lots,");
+        let policy = RowErrorPolicy::Quarantine {
+            max_rejected_rows: 1,
+        };
+
+        let report = DailyReport::from_reader_with_policy(contents.as_bytes(), policy)
+            .expect("Failed to parse");
+        assert_eq!(report.regions.len(), 2);
+        assert!(report.units.is_empty());
+        assert_eq!(report.rejected_row_count, 1);
+        assert_eq!(report.rejected_rows[0].line, Some(6));
+        assert_eq!(report.rejected_rows[0].fields[6], "BAYSW1");
+
+        let mut archive = RecordsCollection::new();
+        for file_name in ["DAY_1.CSV", "DAY_2.CSV"] {
+            let collection = RecordsCollection::from_records_with_policy(
+                stream_file_daily_reports(contents.as_bytes()),
+                policy,
+            )
+            .expect("Failed to parse");
+            archive.append(file_name, collection);
+        }
+        let report = DailyReport::from(archive);
+        assert_eq!(report.regions.len(), 4);
+        assert_eq!(report.rejected_row_count, 2);
+        assert_eq!(report.rejected_rows.len(), 2);
+        assert_eq!(report.record_sets[1].file_name, "DAY_2.CSV");
+        assert_eq!(report.record_sets[1].regions, 2..4);
+        assert_eq!(report.record_sets[1].units, 0..0);
+        assert_eq!(report.record_sets[1].market_suspensions, 1..2);
+    }
+}
@@ -0,0 +1,640 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::fmt;
+use std::io::Read;
+use std::sync::Arc;
+
+use crate::common::intern::deserialize_interned;
+use crate::common::mms_csv::{DataRow, FromDataRow, MmsCsvReader, MmsRecords};
+use crate::common::unzip_process::{ProcessRecord, RecordsCollection};
+use crate::error::ScraperError;
+use crate::time::datetimezone_conversion::deserialize_sydney_datetime_to_utc;
+
+/// DISPATCH.CASESOLUTION: the outcome of one dispatch run, including constraint
+/// violation totals.
+#[derive(Debug, Deserialize)]
+pub struct CaseSolutionData {
+    #[serde(rename = "ROW_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    csv_row_identifier: Arc<str>,
+    #[serde(rename = "FILE_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    category: Arc<str>,
+    #[serde(rename = "FILE_SUBTYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    report_type: Arc<str>,
+    #[serde(rename = "FILE_DESCRIPTOR")]
+    report_type_int: u32,
+    #[serde(rename = "SETTLEMENTDATE")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    settlement_date: DateTime<Utc>,
+    #[serde(rename = "RUNNO")]
+    run_no: Option<u32>,
+    #[serde(rename = "INTERVENTION")]
+    intervention: Option<u32>,
+    #[serde(rename = "CASESUBTYPE")]
+    case_subtype: Option<String>,
+    #[serde(rename = "SOLUTIONSTATUS")]
+    solution_status: Option<u32>,
+    #[serde(rename = "SPDVERSION")]
+    spd_version: Option<String>,
+    #[serde(rename = "NONPHYSICALLOSSES")]
+    non_physical_losses: Option<u32>,
+    #[serde(rename = "TOTALOBJECTIVE")]
+    total_objective: Option<f64>,
+    #[serde(rename = "TOTALAREAGENVIOLATION")]
+    total_area_gen_violation: Option<f64>,
+    #[serde(rename = "TOTALINTERCONNECTORVIOLATION")]
+    total_interconnector_violation: Option<f64>,
+    #[serde(rename = "TOTALGENERICVIOLATION")]
+    total_generic_violation: Option<f64>,
+    #[serde(rename = "TOTALRAMPRATEVIOLATION")]
+    total_ramp_rate_violation: Option<f64>,
+    #[serde(rename = "TOTALUNITMWCAPACITYVIOLATION")]
+    total_unit_mw_capacity_violation: Option<f64>,
+    #[serde(rename = "TOTAL5MINVIOLATION")]
+    total_5_min_violation: Option<f64>,
+    #[serde(rename = "TOTALREGVIOLATION")]
+    total_reg_violation: Option<f64>,
+    #[serde(rename = "TOTAL6SECVIOLATION")]
+    total_6_sec_violation: Option<f64>,
+    #[serde(rename = "TOTAL60SECVIOLATION")]
+    total_60_sec_violation: Option<f64>,
+    #[serde(rename = "TOTALASPROFILEVIOLATION")]
+    total_as_profile_violation: Option<f64>,
+    #[serde(rename = "TOTALFASTSTARTVIOLATION")]
+    total_fast_start_violation: Option<f64>,
+    #[serde(rename = "TOTALENERGYOFFERVIOLATION")]
+    total_energy_offer_violation: Option<f64>,
+    #[serde(rename = "LASTCHANGED")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    last_changed: DateTime<Utc>,
+}
+
+impl fmt::Display for CaseSolutionData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CaseSolutionData: {{ csv_row_identifier: {}, category: {}, report_type: {}, report_type_int: {}, settlement_date: {:?}, run_no: {:?}, intervention: {:?}, case_subtype: {:?}, solution_status: {:?}, spd_version: {:?}, non_physical_losses: {:?}, total_objective: {:?}, total_area_gen_violation: {:?}, total_interconnector_violation: {:?}, total_generic_violation: {:?}, total_ramp_rate_violation: {:?}, total_unit_mw_capacity_violation: {:?}, total_5_min_violation: {:?}, total_reg_violation: {:?}, total_6_sec_violation: {:?}, total_60_sec_violation: {:?}, total_as_profile_violation: {:?}, total_fast_start_violation: {:?}, total_energy_offer_violation: {:?}, last_changed: {:?} }}",
+            self.csv_row_identifier,
+            self.category,
+            self.report_type,
+            self.report_type_int,
+            self.settlement_date,
+            self.run_no,
+            self.intervention,
+            self.case_subtype,
+            self.solution_status,
+            self.spd_version,
+            self.non_physical_losses,
+            self.total_objective,
+            self.total_area_gen_violation,
+            self.total_interconnector_violation,
+            self.total_generic_violation,
+            self.total_ramp_rate_violation,
+            self.total_unit_mw_capacity_violation,
+            self.total_5_min_violation,
+            self.total_reg_violation,
+            self.total_6_sec_violation,
+            self.total_60_sec_violation,
+            self.total_as_profile_violation,
+            self.total_fast_start_violation,
+            self.total_energy_offer_violation,
+            self.last_changed
+        )
+    }
+}
+
+/// DREGION: regional energy and FCAS prices, demand and availability for a dispatch interval.
+/// The per-service dispatch, import and requirement columns are not modelled.
+#[derive(Debug, Deserialize)]
+pub struct RegionDispatchData {
+    #[serde(rename = "ROW_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    csv_row_identifier: Arc<str>,
+    #[serde(rename = "FILE_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    category: Arc<str>,
+    #[serde(rename = "FILE_SUBTYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    report_type: Arc<str>,
+    #[serde(rename = "FILE_DESCRIPTOR")]
+    report_type_int: u32,
+    #[serde(rename = "SETTLEMENTDATE")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    settlement_date: DateTime<Utc>,
+    #[serde(rename = "RUNNO")]
+    run_no: Option<u32>,
+    #[serde(rename = "REGIONID")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    region_id: Arc<str>,
+    #[serde(rename = "INTERVENTION")]
+    intervention: Option<u32>,
+    #[serde(rename = "RRP")]
+    rrp: Option<f64>,
+    #[serde(rename = "EEP")]
+    eep: Option<f64>,
+    #[serde(rename = "ROP")]
+    rop: Option<f64>,
+    #[serde(rename = "APCFLAG")]
+    apc_flag: Option<u32>,
+    #[serde(rename = "MARKETSUSPENDEDFLAG")]
+    market_suspended_flag: Option<u32>,
+    #[serde(rename = "TOTALDEMAND")]
+    total_demand: Option<f64>,
+    #[serde(rename = "DEMANDFORECAST")]
+    demand_forecast: Option<f64>,
+    #[serde(rename = "DISPATCHABLEGENERATION")]
+    dispatchable_generation: Option<f64>,
+    #[serde(rename = "DISPATCHABLELOAD")]
+    dispatchable_load: Option<f64>,
+    #[serde(rename = "NETINTERCHANGE")]
+    net_interchange: Option<f64>,
+    #[serde(rename = "EXCESSGENERATION")]
+    excess_generation: Option<f64>,
+    #[serde(rename = "AGGREGATEDISPATCHERROR")]
+    aggregate_dispatch_error: Option<f64>,
+    #[serde(rename = "AVAILABLEGENERATION")]
+    available_generation: Option<f64>,
+    #[serde(rename = "AVAILABLELOAD")]
+    available_load: Option<f64>,
+    #[serde(rename = "INITIALSUPPLY")]
+    initial_supply: Option<f64>,
+    #[serde(rename = "CLEAREDSUPPLY")]
+    cleared_supply: Option<f64>,
+    #[serde(rename = "RAISE6SECRRP")]
+    raise_6_sec_rrp: Option<f64>,
+    #[serde(rename = "RAISE6SECROP")]
+    raise_6_sec_rop: Option<f64>,
+    #[serde(rename = "RAISE6SECAPCFLAG")]
+    raise_6_sec_apc_flag: Option<u32>,
+    #[serde(rename = "RAISE6SECACTUALAVAILABILITY")]
+    raise_6_sec_actual_availability: Option<f64>,
+    #[serde(rename = "RAISE60SECRRP")]
+    raise_60_sec_rrp: Option<f64>,
+    #[serde(rename = "RAISE60SECROP")]
+    raise_60_sec_rop: Option<f64>,
+    #[serde(rename = "RAISE60SECAPCFLAG")]
+    raise_60_sec_apc_flag: Option<u32>,
+    #[serde(rename = "RAISE60SECACTUALAVAILABILITY")]
+    raise_60_sec_actual_availability: Option<f64>,
+    #[serde(rename = "RAISE5MINRRP")]
+    raise_5_min_rrp: Option<f64>,
+    #[serde(rename = "RAISE5MINROP")]
+    raise_5_min_rop: Option<f64>,
+    #[serde(rename = "RAISE5MINAPCFLAG")]
+    raise_5_min_apc_flag: Option<u32>,
+    #[serde(rename = "RAISE5MINACTUALAVAILABILITY")]
+    raise_5_min_actual_availability: Option<f64>,
+    #[serde(rename = "RAISEREGRRP")]
+    raise_reg_rrp: Option<f64>,
+    #[serde(rename = "RAISEREGROP")]
+    raise_reg_rop: Option<f64>,
+    #[serde(rename = "RAISEREGAPCFLAG")]
+    raise_reg_apc_flag: Option<u32>,
+    #[serde(rename = "RAISEREGACTUALAVAILABILITY")]
+    raise_reg_actual_availability: Option<f64>,
+    #[serde(rename = "LOWER6SECRRP")]
+    lower_6_sec_rrp: Option<f64>,
+    #[serde(rename = "LOWER6SECROP")]
+    lower_6_sec_rop: Option<f64>,
+    #[serde(rename = "LOWER6SECAPCFLAG")]
+    lower_6_sec_apc_flag: Option<u32>,
+    #[serde(rename = "LOWER6SECACTUALAVAILABILITY")]
+    lower_6_sec_actual_availability: Option<f64>,
+    #[serde(rename = "LOWER60SECRRP")]
+    lower_60_sec_rrp: Option<f64>,
+    #[serde(rename = "LOWER60SECROP")]
+    lower_60_sec_rop: Option<f64>,
+    #[serde(rename = "LOWER60SECAPCFLAG")]
+    lower_60_sec_apc_flag: Option<u32>,
+    #[serde(rename = "LOWER60SECACTUALAVAILABILITY")]
+    lower_60_sec_actual_availability: Option<f64>,
+    #[serde(rename = "LOWER5MINRRP")]
+    lower_5_min_rrp: Option<f64>,
+    #[serde(rename = "LOWER5MINROP")]
+    lower_5_min_rop: Option<f64>,
+    #[serde(rename = "LOWER5MINAPCFLAG")]
+    lower_5_min_apc_flag: Option<u32>,
+    #[serde(rename = "LOWER5MINACTUALAVAILABILITY")]
+    lower_5_min_actual_availability: Option<f64>,
+    #[serde(rename = "LOWERREGRRP")]
+    lower_reg_rrp: Option<f64>,
+    #[serde(rename = "LOWERREGROP")]
+    lower_reg_rop: Option<f64>,
+    #[serde(rename = "LOWERREGAPCFLAG")]
+    lower_reg_apc_flag: Option<u32>,
+    #[serde(rename = "LOWERREGACTUALAVAILABILITY")]
+    lower_reg_actual_availability: Option<f64>,
+    #[serde(rename = "LORSURPLUS")]
+    lor_surplus: Option<f64>,
+    #[serde(rename = "LRCSURPLUS")]
+    lrc_surplus: Option<f64>,
+}
+
+impl fmt::Display for RegionDispatchData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "RegionDispatchData: {{ csv_row_identifier: {}, category: {}, report_type: {}, report_type_int: {}, settlement_date: {:?}, run_no: {:?}, region_id: {:?}, intervention: {:?}, rrp: {:?}, eep: {:?}, rop: {:?}, apc_flag: {:?}, market_suspended_flag: {:?}, total_demand: {:?}, demand_forecast: {:?}, dispatchable_generation: {:?}, dispatchable_load: {:?}, net_interchange: {:?}, excess_generation: {:?}, aggregate_dispatch_error: {:?}, available_generation: {:?}, available_load: {:?}, initial_supply: {:?}, cleared_supply: {:?}, raise_6_sec_rrp: {:?}, raise_6_sec_rop: {:?}, raise_6_sec_apc_flag: {:?}, raise_6_sec_actual_availability: {:?}, raise_60_sec_rrp: {:?}, raise_60_sec_rop: {:?}, raise_60_sec_apc_flag: {:?}, raise_60_sec_actual_availability: {:?}, raise_5_min_rrp: {:?}, raise_5_min_rop: {:?}, raise_5_min_apc_flag: {:?}, raise_5_min_actual_availability: {:?}, raise_reg_rrp: {:?}, raise_reg_rop: {:?}, raise_reg_apc_flag: {:?}, raise_reg_actual_availability: {:?}, lower_6_sec_rrp: {:?}, lower_6_sec_rop: {:?}, lower_6_sec_apc_flag: {:?}, lower_6_sec_actual_availability: {:?}, lower_60_sec_rrp: {:?}, lower_60_sec_rop: {:?}, lower_60_sec_apc_flag: {:?}, lower_60_sec_actual_availability: {:?}, lower_5_min_rrp: {:?}, lower_5_min_rop: {:?}, lower_5_min_apc_flag: {:?}, lower_5_min_actual_availability: {:?}, lower_reg_rrp: {:?}, lower_reg_rop: {:?}, lower_reg_apc_flag: {:?}, lower_reg_actual_availability: {:?}, lor_surplus: {:?}, lrc_surplus: {:?} }}",
+            self.csv_row_identifier,
+            self.category,
+            self.report_type,
+            self.report_type_int,
+            self.settlement_date,
+            self.run_no,
+            self.region_id,
+            self.intervention,
+            self.rrp,
+            self.eep,
+            self.rop,
+            self.apc_flag,
+            self.market_suspended_flag,
+            self.total_demand,
+            self.demand_forecast,
+            self.dispatchable_generation,
+            self.dispatchable_load,
+            self.net_interchange,
+            self.excess_generation,
+            self.aggregate_dispatch_error,
+            self.available_generation,
+            self.available_load,
+            self.initial_supply,
+            self.cleared_supply,
+            self.raise_6_sec_rrp,
+            self.raise_6_sec_rop,
+            self.raise_6_sec_apc_flag,
+            self.raise_6_sec_actual_availability,
+            self.raise_60_sec_rrp,
+            self.raise_60_sec_rop,
+            self.raise_60_sec_apc_flag,
+            self.raise_60_sec_actual_availability,
+            self.raise_5_min_rrp,
+            self.raise_5_min_rop,
+            self.raise_5_min_apc_flag,
+            self.raise_5_min_actual_availability,
+            self.raise_reg_rrp,
+            self.raise_reg_rop,
+            self.raise_reg_apc_flag,
+            self.raise_reg_actual_availability,
+            self.lower_6_sec_rrp,
+            self.lower_6_sec_rop,
+            self.lower_6_sec_apc_flag,
+            self.lower_6_sec_actual_availability,
+            self.lower_60_sec_rrp,
+            self.lower_60_sec_rop,
+            self.lower_60_sec_apc_flag,
+            self.lower_60_sec_actual_availability,
+            self.lower_5_min_rrp,
+            self.lower_5_min_rop,
+            self.lower_5_min_apc_flag,
+            self.lower_5_min_actual_availability,
+            self.lower_reg_rrp,
+            self.lower_reg_rop,
+            self.lower_reg_apc_flag,
+            self.lower_reg_actual_availability,
+            self.lor_surplus,
+            self.lrc_surplus
+        )
+    }
+}
+
+/// TINT: interconnector flows for the trading interval.
+#[derive(Debug, Deserialize)]
+pub struct InterconnectorTradingData {
+    #[serde(rename = "ROW_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    csv_row_identifier: Arc<str>,
+    #[serde(rename = "FILE_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    category: Arc<str>,
+    #[serde(rename = "FILE_SUBTYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    report_type: Arc<str>,
+    #[serde(rename = "FILE_DESCRIPTOR")]
+    report_type_int: u32,
+    #[serde(rename = "SETTLEMENTDATE")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    settlement_date: DateTime<Utc>,
+    #[serde(rename = "RUNNO")]
+    run_no: Option<u32>,
+    #[serde(rename = "INTERCONNECTORID")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    interconnector_id: Arc<str>,
+    #[serde(rename = "METEREDMWFLOW")]
+    metered_mw_flow: Option<f64>,
+    #[serde(rename = "MWFLOW")]
+    mw_flow: Option<f64>,
+    #[serde(rename = "MWLOSSES")]
+    mw_losses: Option<f64>,
+}
+
+impl fmt::Display for InterconnectorTradingData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "InterconnectorTradingData: {{ csv_row_identifier: {}, category: {}, report_type: {}, report_type_int: {}, settlement_date: {:?}, run_no: {:?}, interconnector_id: {:?}, metered_mw_flow: {:?}, mw_flow: {:?}, mw_losses: {:?} }}",
+            self.csv_row_identifier,
+            self.category,
+            self.report_type,
+            self.report_type_int,
+            self.settlement_date,
+            self.run_no,
+            self.interconnector_id,
+            self.metered_mw_flow,
+            self.mw_flow,
+            self.mw_losses
+        )
+    }
+}
+
+/// DINT: interconnector flows and limits for the dispatch interval.
+#[derive(Debug, Deserialize)]
+pub struct InterconnectorDispatchData {
+    #[serde(rename = "ROW_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    csv_row_identifier: Arc<str>,
+    #[serde(rename = "FILE_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    category: Arc<str>,
+    #[serde(rename = "FILE_SUBTYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    report_type: Arc<str>,
+    #[serde(rename = "FILE_DESCRIPTOR")]
+    report_type_int: u32,
+    #[serde(rename = "SETTLEMENTDATE")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    settlement_date: DateTime<Utc>,
+    #[serde(rename = "RUNNO")]
+    run_no: Option<u32>,
+    #[serde(rename = "INTERCONNECTORID")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    interconnector_id: Arc<str>,
+    #[serde(rename = "INTERVENTION")]
+    intervention: Option<u32>,
+    #[serde(rename = "METEREDMWFLOW")]
+    metered_mw_flow: Option<f64>,
+    #[serde(rename = "MWFLOW")]
+    mw_flow: Option<f64>,
+    #[serde(rename = "MWLOSSES")]
+    mw_losses: Option<f64>,
+    #[serde(rename = "MARGINALVALUE")]
+    marginal_value: Option<f64>,
+    #[serde(rename = "VIOLATIONDEGREE")]
+    violation_degree: Option<f64>,
+    #[serde(rename = "IMPORTLIMIT")]
+    import_limit: Option<f64>,
+    #[serde(rename = "EXPORTLIMIT")]
+    export_limit: Option<f64>,
+    #[serde(rename = "MARGINALLOSS")]
+    marginal_loss: Option<f64>,
+    #[serde(rename = "EXPORTGENCONID")]
+    export_gen_con_id: Option<String>,
+    #[serde(rename = "IMPORTGENCONID")]
+    import_gen_con_id: Option<String>,
+    #[serde(rename = "FCASEXPORTLIMIT")]
+    fcas_export_limit: Option<f64>,
+    #[serde(rename = "FCASIMPORTLIMIT")]
+    fcas_import_limit: Option<f64>,
+}
+
+impl fmt::Display for InterconnectorDispatchData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "InterconnectorDispatchData: {{ csv_row_identifier: {}, category: {}, report_type: {}, report_type_int: {}, settlement_date: {:?}, run_no: {:?}, interconnector_id: {:?}, intervention: {:?}, metered_mw_flow: {:?}, mw_flow: {:?}, mw_losses: {:?}, marginal_value: {:?}, violation_degree: {:?}, import_limit: {:?}, export_limit: {:?}, marginal_loss: {:?}, export_gen_con_id: {:?}, import_gen_con_id: {:?}, fcas_export_limit: {:?}, fcas_import_limit: {:?} }}",
+            self.csv_row_identifier,
+            self.category,
+            self.report_type,
+            self.report_type_int,
+            self.settlement_date,
+            self.run_no,
+            self.interconnector_id,
+            self.intervention,
+            self.metered_mw_flow,
+            self.mw_flow,
+            self.mw_losses,
+            self.marginal_value,
+            self.violation_degree,
+            self.import_limit,
+            self.export_limit,
+            self.marginal_loss,
+            self.export_gen_con_id,
+            self.import_gen_con_id,
+            self.fcas_export_limit,
+            self.fcas_import_limit
+        )
+    }
+}
+
+/// DCONS: the right-hand side and marginal value of each constraint in the dispatch run.
+#[derive(Debug, Deserialize)]
+pub struct ConstraintDispatchData {
+    #[serde(rename = "ROW_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    csv_row_identifier: Arc<str>,
+    #[serde(rename = "FILE_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    category: Arc<str>,
+    #[serde(rename = "FILE_SUBTYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    report_type: Arc<str>,
+    #[serde(rename = "FILE_DESCRIPTOR")]
+    report_type_int: u32,
+    #[serde(rename = "SETTLEMENTDATE")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    settlement_date: DateTime<Utc>,
+    #[serde(rename = "RUNNO")]
+    run_no: Option<u32>,
+    #[serde(rename = "CONSTRAINTID")]
+    constraint_id: String,
+    #[serde(rename = "INTERVENTION")]
+    intervention: Option<u32>,
+    #[serde(rename = "RHS")]
+    rhs: Option<f64>,
+    #[serde(rename = "MARGINALVALUE")]
+    marginal_value: Option<f64>,
+    #[serde(rename = "VIOLATIONDEGREE")]
+    violation_degree: Option<f64>,
+}
+
+impl fmt::Display for ConstraintDispatchData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ConstraintDispatchData: {{ csv_row_identifier: {}, category: {}, report_type: {}, report_type_int: {}, settlement_date: {:?}, run_no: {:?}, constraint_id: {:?}, intervention: {:?}, rhs: {:?}, marginal_value: {:?}, violation_degree: {:?} }}",
+            self.csv_row_identifier,
+            self.category,
+            self.report_type,
+            self.report_type_int,
+            self.settlement_date,
+            self.run_no,
+            self.constraint_id,
+            self.intervention,
+            self.rhs,
+            self.marginal_value,
+            self.violation_degree
+        )
+    }
+}
+
+/// A row of the legacy DISPATCH report (`PUBLIC_DISPATCH_*_LEGACY.zip`).
+#[derive(Debug)]
+// Region is boxed to comply with cargo clippy warning for large enums
+pub enum RecordDispatchLegacy {
+    CaseSolution(CaseSolutionData),
+    Region(Box<RegionDispatchData>),
+    InterconnectorTrading(InterconnectorTradingData),
+    Interconnector(InterconnectorDispatchData),
+    Constraint(ConstraintDispatchData),
+}
+
+impl fmt::Display for RecordDispatchLegacy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecordDispatchLegacy::CaseSolution(case_solution) => write!(f, "{}", case_solution),
+            RecordDispatchLegacy::Region(region) => write!(f, "{}", region),
+            RecordDispatchLegacy::InterconnectorTrading(interconnector) => {
+                write!(f, "{}", interconnector)
+            }
+            RecordDispatchLegacy::Interconnector(interconnector) => write!(f, "{}", interconnector),
+            RecordDispatchLegacy::Constraint(constraint) => write!(f, "{}", constraint),
+        }
+    }
+}
+
+impl ProcessRecord<RecordDispatchLegacy> for CaseSolutionData {
+    fn process(row: &DataRow) -> Result<RecordDispatchLegacy, ScraperError> {
+        let record = match row.version()? {
+            1 => row.deserialize::<CaseSolutionData>()?,
+            version => return Err(row.unsupported_version(version)),
+        };
+        Ok(RecordDispatchLegacy::CaseSolution(record))
+    }
+}
+
+impl ProcessRecord<RecordDispatchLegacy> for RegionDispatchData {
+    fn process(row: &DataRow) -> Result<RecordDispatchLegacy, ScraperError> {
+        let record = match row.version()? {
+            3 => row.deserialize::<RegionDispatchData>()?,
+            version => return Err(row.unsupported_version(version)),
+        };
+        Ok(RecordDispatchLegacy::Region(Box::new(record)))
+    }
+}
+
+impl ProcessRecord<RecordDispatchLegacy> for InterconnectorTradingData {
+    fn process(row: &DataRow) -> Result<RecordDispatchLegacy, ScraperError> {
+        let record = match row.version()? {
+            1 => row.deserialize::<InterconnectorTradingData>()?,
+            version => return Err(row.unsupported_version(version)),
+        };
+        Ok(RecordDispatchLegacy::InterconnectorTrading(record))
+    }
+}
+
+impl ProcessRecord<RecordDispatchLegacy> for InterconnectorDispatchData {
+    fn process(row: &DataRow) -> Result<RecordDispatchLegacy, ScraperError> {
+        let record = match row.version()? {
+            4 => row.deserialize::<InterconnectorDispatchData>()?,
+            version => return Err(row.unsupported_version(version)),
+        };
+        Ok(RecordDispatchLegacy::Interconnector(record))
+    }
+}
+
+impl ProcessRecord<RecordDispatchLegacy> for ConstraintDispatchData {
+    fn process(row: &DataRow) -> Result<RecordDispatchLegacy, ScraperError> {
+        let record = match row.version()? {
+            1 => row.deserialize::<ConstraintDispatchData>()?,
+            version => return Err(row.unsupported_version(version)),
+        };
+        Ok(RecordDispatchLegacy::Constraint(record))
+    }
+}
+
+impl FromDataRow for RecordDispatchLegacy {
+    fn from_data_row(row: &DataRow) -> Result<Self, ScraperError> {
+        // Apart from CASESOLUTION the legacy tables have an empty subtype, e.g. `DREGION,`
+        if row.table.is("DISPATCH", "CASESOLUTION") {
+            CaseSolutionData::process(row)
+        } else if row.table.is("DREGION", "") {
+            RegionDispatchData::process(row)
+        } else if row.table.is("TINT", "") {
+            InterconnectorTradingData::process(row)
+        } else if row.table.is("DINT", "") {
+            InterconnectorDispatchData::process(row)
+        } else if row.table.is("DCONS", "") {
+            ConstraintDispatchData::process(row)
+        } else {
+            Err(row.unknown_table())
+        }
+    }
+}
+
+/// Streams legacy DISPATCH records one row at a time.
+pub fn stream_file_dispatch_legacy<R: Read>(reader: R) -> MmsRecords<R, RecordDispatchLegacy> {
+    MmsCsvReader::new(reader).records()
+}
+
+pub fn process_file_dispatch_legacy<R: Read>(
+    reader: R,
+) -> Result<RecordsCollection<RecordDispatchLegacy>, ScraperError> {
+    RecordsCollection::from_records(stream_file_dispatch_legacy(reader))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::unzip_process::unzip_and_process;
+
+    #[test]
+    fn test_process_file_dispatch_legacy_fixture() {
+        let collection = unzip_and_process(
+            "src/fixtures/PUBLIC_DISPATCH_202403151945_20240315194011_LEGACY.zip",
+            |reader| process_file_dispatch_legacy(reader),
+        )
+        .expect("Failed to process dispatch fixture");
+        let records = collection.records;
+
+        assert_eq!(records.len(), 881);
+        let count = |matches: fn(&RecordDispatchLegacy) -> bool| {
+            records.iter().filter(|record| matches(record)).count()
+        };
+        assert_eq!(
+            count(|r| matches!(r, RecordDispatchLegacy::CaseSolution(_))),
+            1
+        );
+        assert_eq!(count(|r| matches!(r, RecordDispatchLegacy::Region(_))), 5);
+        assert_eq!(
+            count(|r| matches!(r, RecordDispatchLegacy::InterconnectorTrading(_))),
+            6
+        );
+        assert_eq!(
+            count(|r| matches!(r, RecordDispatchLegacy::Interconnector(_))),
+            6
+        );
+        assert_eq!(
+            count(|r| matches!(r, RecordDispatchLegacy::Constraint(_))),
+            863
+        );
+
+        match &records[1] {
+            RecordDispatchLegacy::Region(region) => {
+                assert_eq!(&*region.region_id, "NSW1");
+                assert_eq!(region.rrp, Some(88.88002));
+                assert_eq!(region.total_demand, Some(8025.43));
+                assert_eq!(region.raise_reg_rrp, Some(3.25));
+                assert_eq!(region.lor_surplus, None);
+                assert_eq!(
+                    region.settlement_date.to_rfc3339(),
+                    "2024-03-15T08:45:00+00:00"
+                );
+            }
+            other => panic!("Expected DREGION, got {}", other),
+        }
+
+        match &records[13] {
+            RecordDispatchLegacy::Interconnector(interconnector) => {
+                assert_eq!(&*interconnector.interconnector_id, "NSW1-QLD1");
+                assert_eq!(interconnector.import_limit, Some(-912.15862));
+                assert_eq!(interconnector.export_limit, Some(582.65376));
+                assert_eq!(
+                    interconnector.export_gen_con_id.as_deref(),
+                    Some("N^^Q_NIL_KPP_1")
+                );
+            }
+            other => panic!("Expected DINT, got {}", other),
+        }
+    }
+}
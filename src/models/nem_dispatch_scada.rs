@@ -0,0 +1,148 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::fmt;
+use std::io::Read;
+use std::sync::Arc;
+
+use crate::common::intern::deserialize_interned;
+use crate::common::mms_csv::{DataRow, FromDataRow, MmsCsvReader, MmsRecords};
+use crate::common::unit::UnitRow;
+use crate::common::unzip_process::{ProcessRecord, RecordsCollection};
+use crate::error::ScraperError;
+use crate::time::datetimezone_conversion::{
+    deserialize_optional_sydney_datetime_to_utc, deserialize_sydney_datetime_to_utc,
+};
+
+/// DISPATCH.UNIT_SCADA: the SCADA MW reading for a dispatchable unit at the start of the
+/// dispatch interval. Batteries report negative values while charging.
+#[derive(Debug, Deserialize)]
+pub struct UnitScadaData {
+    #[serde(rename = "ROW_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    csv_row_identifier: Arc<str>,
+    #[serde(rename = "FILE_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    category: Arc<str>,
+    #[serde(rename = "FILE_SUBTYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    report_type: Arc<str>,
+    #[serde(rename = "FILE_DESCRIPTOR")]
+    report_type_int: u32,
+    #[serde(rename = "SETTLEMENTDATE")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    settlement_date: DateTime<Utc>,
+    #[serde(rename = "DUID")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    duid: Arc<str>,
+    #[serde(rename = "SCADAVALUE")]
+    scada_value: Option<f64>,
+    #[serde(rename = "LASTCHANGED")]
+    #[serde(deserialize_with = "deserialize_optional_sydney_datetime_to_utc")]
+    last_changed: Option<DateTime<Utc>>,
+}
+
+impl fmt::Display for UnitScadaData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "UnitScadaData: {{ csv_row_identifier: {}, category: {}, report_type: {}, report_type_int: {}, settlement_date: {:?}, duid: {:?}, scada_value: {:?}, last_changed: {:?} }}",
+            self.csv_row_identifier,
+            self.category,
+            self.report_type,
+            self.report_type_int,
+            self.settlement_date,
+            self.duid,
+            self.scada_value,
+            self.last_changed
+        )
+    }
+}
+
+impl UnitRow for UnitScadaData {
+    fn duid(&self) -> &str {
+        &self.duid
+    }
+
+    fn settlement_datetime(&self) -> DateTime<Utc> {
+        self.settlement_date
+    }
+}
+
+#[derive(Debug)]
+pub enum RecordDispatchScada {
+    UnitScada(UnitScadaData),
+}
+
+impl fmt::Display for RecordDispatchScada {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecordDispatchScada::UnitScada(unit_scada_data) => write!(f, "{}", unit_scada_data),
+        }
+    }
+}
+
+impl ProcessRecord<RecordDispatchScada> for UnitScadaData {
+    fn process(row: &DataRow) -> Result<RecordDispatchScada, ScraperError> {
+        let record = match row.version()? {
+            1 => row.deserialize::<UnitScadaData>()?,
+            version => return Err(row.unsupported_version(version)),
+        };
+        Ok(RecordDispatchScada::UnitScada(record))
+    }
+}
+
+impl FromDataRow for RecordDispatchScada {
+    fn from_data_row(row: &DataRow) -> Result<Self, ScraperError> {
+        if row.table.is("DISPATCH", "UNIT_SCADA") {
+            UnitScadaData::process(row)
+        } else {
+            Err(row.unknown_table())
+        }
+    }
+}
+
+/// Streams Dispatch_SCADA records one row at a time.
+pub fn stream_file_dispatch_scada<R: Read>(reader: R) -> MmsRecords<R, RecordDispatchScada> {
+    MmsCsvReader::new(reader).records()
+}
+
+pub fn process_file_dispatch_scada<R: Read>(
+    reader: R,
+) -> Result<RecordsCollection<RecordDispatchScada>, ScraperError> {
+    RecordsCollection::from_records(stream_file_dispatch_scada(reader))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::unzip_process::unzip_and_process;
+
+    #[test]
+    fn test_process_file_dispatch_scada_fixture() {
+        let collection = unzip_and_process(
+            "src/fixtures/PUBLIC_DISPATCHSCADA_202403031335_0000000412683170.zip",
+            |reader| process_file_dispatch_scada(reader),
+        )
+        .expect("Failed to process Dispatch_SCADA fixture");
+        let header = collection.report_header.expect("Missing report header");
+        assert_eq!(header.report_name, "DISPATCHSCADA");
+        assert_eq!(
+            header.previous_report_id.as_deref(),
+            Some("0000000412683165")
+        );
+
+        let records = collection.records;
+
+        assert_eq!(records.len(), 12);
+        let RecordDispatchScada::UnitScada(bayswater) = &records[8];
+        assert_eq!(&*bayswater.duid, "BAYSW1");
+        assert_eq!(bayswater.scada_value, Some(472.65002));
+        assert_eq!(
+            bayswater.settlement_date.to_rfc3339(),
+            "2024-03-03T02:35:00+00:00"
+        );
+
+        let RecordDispatchScada::UnitScada(battery) = &records[4];
+        assert_eq!(battery.scada_value, Some(-2.5));
+        let RecordDispatchScada::UnitScada(missing) = &records[11];
+        assert_eq!(missing.scada_value, None);
+    }
+}
@@ -0,0 +1,212 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::fmt;
+use std::io::Read;
+use std::sync::Arc;
+
+use crate::common::forecast::{latest_by, ForecastRow};
+use crate::common::intern::deserialize_interned;
+use crate::common::mms_csv::{DataRow, FromDataRow, MmsCsvReader, MmsRecords, TableKey};
+use crate::common::unzip_process::{ProcessRecord, RecordsCollection};
+use crate::error::ScraperError;
+use crate::time::datetimezone_conversion::{
+    deserialize_optional_sydney_datetime_to_utc, deserialize_sydney_datetime_to_utc,
+};
+
+// Each MT PASA region availability file is one publication covering every day of the next
+// two years. PUBLISH_DATETIME stands in for the run time.
+
+/// MTPASA.REGIONAVAILABILITY: aggregate scheduled availability and demand for a region and
+/// day, up to two years ahead. `DAY` is the start of the day in market time.
+#[derive(Debug, Deserialize)]
+pub struct RegionAvailabilityData {
+    #[serde(rename = "ROW_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    csv_row_identifier: Arc<str>,
+    #[serde(rename = "FILE_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    category: Arc<str>,
+    #[serde(rename = "FILE_SUBTYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    report_type: Arc<str>,
+    #[serde(rename = "FILE_DESCRIPTOR")]
+    report_type_int: u32,
+    #[serde(rename = "PUBLISH_DATETIME")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    publish_datetime: DateTime<Utc>,
+    #[serde(rename = "DAY")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    day: DateTime<Utc>,
+    #[serde(rename = "REGIONID")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    region_id: Arc<str>,
+    #[serde(rename = "PASAAVAILABILITY_SCHEDULED")]
+    pasa_availability_scheduled: Option<f64>,
+    #[serde(rename = "LATEST_OFFER_DATETIME")]
+    #[serde(deserialize_with = "deserialize_optional_sydney_datetime_to_utc")]
+    latest_offer_datetime: Option<DateTime<Utc>>,
+    #[serde(rename = "ENERGYUNCONSTRAINEDCAPACITY")]
+    energy_unconstrained_capacity: Option<f64>,
+    #[serde(rename = "ENERGYCONSTRAINEDCAPACITY")]
+    energy_constrained_capacity: Option<f64>,
+    #[serde(rename = "NONSCHEDULEDGENERATION")]
+    non_scheduled_generation: Option<f64>,
+    #[serde(rename = "DEMAND10")]
+    demand_10: Option<f64>,
+    #[serde(rename = "DEMAND50")]
+    demand_50: Option<f64>,
+    #[serde(rename = "ENERGYREQDEMAND10")]
+    energy_req_demand_10: Option<f64>,
+    #[serde(rename = "ENERGYREQDEMAND50")]
+    energy_req_demand_50: Option<f64>,
+    #[serde(rename = "LASTCHANGED")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    last_changed: DateTime<Utc>,
+    #[serde(rename = "DEMAND10MIN")]
+    demand_10_min: Option<f64>,
+    #[serde(rename = "DEMAND10MAX")]
+    demand_10_max: Option<f64>,
+    #[serde(rename = "DEMAND50MIN")]
+    demand_50_min: Option<f64>,
+    #[serde(rename = "DEMAND50MAX")]
+    demand_50_max: Option<f64>,
+    #[serde(rename = "CARRYOVERCAPACITY")]
+    carryover_capacity: Option<f64>,
+}
+
+impl fmt::Display for RegionAvailabilityData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "RegionAvailabilityData: {{ csv_row_identifier: {}, category: {}, report_type: {}, report_type_int: {}, publish_datetime: {:?}, day: {:?}, region_id: {:?}, pasa_availability_scheduled: {:?}, latest_offer_datetime: {:?}, energy_unconstrained_capacity: {:?}, energy_constrained_capacity: {:?}, non_scheduled_generation: {:?}, demand_10: {:?}, demand_50: {:?}, energy_req_demand_10: {:?}, energy_req_demand_50: {:?}, last_changed: {:?}, demand_10_min: {:?}, demand_10_max: {:?}, demand_50_min: {:?}, demand_50_max: {:?}, carryover_capacity: {:?} }}",
+            self.csv_row_identifier,
+            self.category,
+            self.report_type,
+            self.report_type_int,
+            self.publish_datetime,
+            self.day,
+            self.region_id,
+            self.pasa_availability_scheduled,
+            self.latest_offer_datetime,
+            self.energy_unconstrained_capacity,
+            self.energy_constrained_capacity,
+            self.non_scheduled_generation,
+            self.demand_10,
+            self.demand_50,
+            self.energy_req_demand_10,
+            self.energy_req_demand_50,
+            self.last_changed,
+            self.demand_10_min,
+            self.demand_10_max,
+            self.demand_50_min,
+            self.demand_50_max,
+            self.carryover_capacity
+        )
+    }
+}
+
+impl RegionAvailabilityData {
+    pub fn region_id(&self) -> &str {
+        &self.region_id
+    }
+}
+
+impl ForecastRow for RegionAvailabilityData {
+    fn run_datetime(&self) -> DateTime<Utc> {
+        self.publish_datetime
+    }
+
+    fn interval_datetime(&self) -> DateTime<Utc> {
+        self.day
+    }
+}
+
+#[derive(Debug)]
+pub enum RecordMtPasa {
+    RegionAvailability(RegionAvailabilityData),
+}
+
+impl fmt::Display for RecordMtPasa {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecordMtPasa::RegionAvailability(region_availability) => {
+                write!(f, "{}", region_availability)
+            }
+        }
+    }
+}
+
+impl ProcessRecord<RecordMtPasa> for RegionAvailabilityData {
+    fn process(row: &DataRow) -> Result<RecordMtPasa, ScraperError> {
+        let record = match row.version()? {
+            2 => row.deserialize::<RegionAvailabilityData>()?,
+            version => return Err(row.unsupported_version(version)),
+        };
+        Ok(RecordMtPasa::RegionAvailability(record))
+    }
+}
+
+impl FromDataRow for RecordMtPasa {
+    fn from_data_row(row: &DataRow) -> Result<Self, ScraperError> {
+        if row.table.is("MTPASA", "REGIONAVAILABILITY") {
+            RegionAvailabilityData::process(row)
+        } else {
+            Err(row.unknown_table())
+        }
+    }
+
+    // The full MT PASA solution also carries region, interconnector and LOLP result tables
+    fn reads_table(table: &TableKey) -> bool {
+        table.is("MTPASA", "REGIONAVAILABILITY")
+    }
+}
+
+/// Streams MT PASA records one row at a time.
+pub fn stream_file_mtpasa<R: Read>(reader: R) -> MmsRecords<R, RecordMtPasa> {
+    MmsCsvReader::new(reader).records()
+}
+
+pub fn process_file_mtpasa<R: Read>(
+    reader: R,
+) -> Result<RecordsCollection<RecordMtPasa>, ScraperError> {
+    RecordsCollection::from_records(stream_file_mtpasa(reader))
+}
+
+/// The latest publication's availability for each region and day, in day order.
+pub fn latest_outlook<'a, I>(records: I) -> Vec<&'a RegionAvailabilityData>
+where
+    I: IntoIterator<Item = &'a RecordMtPasa>,
+{
+    let rows = records.into_iter().map(|record| match record {
+        RecordMtPasa::RegionAvailability(region_availability) => region_availability,
+    });
+    latest_by(rows, |row| (Arc::clone(&row.region_id), row.day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MTPASA: &str = "C,NEMP.WORLD,MTPASA_REGIONAVAILABILITY,AEMO,PUBLIC,2024/03/05,12:05:11,0000000412801210,MTPASA_REGIONAVAILABILITY,0000000412801209\n\
+        I,MTPASA,REGIONAVAILABILITY,2,PUBLISH_DATETIME,DAY,REGIONID,PASAAVAILABILITY_SCHEDULED,LATEST_OFFER_DATETIME,ENERGYUNCONSTRAINEDCAPACITY,ENERGYCONSTRAINEDCAPACITY,NONSCHEDULEDGENERATION,DEMAND10,DEMAND50,ENERGYREQDEMAND10,ENERGYREQDEMAND50,LASTCHANGED,DEMAND10MIN,DEMAND10MAX,DEMAND50MIN,DEMAND50MAX,CARRYOVERCAPACITY\n\
+        D,MTPASA,REGIONAVAILABILITY,2,\"2024/03/05 12:00:00\",\"2024/03/06 00:00:00\",NSW1,13240,\"2024/03/05 11:58:31\",,,312,12120,11460,0,0,\"2024/03/05 12:01:42\",6950,12120,6790,11460,0\n\
+        D,MTPASA,REGIONAVAILABILITY,2,\"2024/03/05 12:00:00\",\"2024/03/07 00:00:00\",NSW1,13010,\"2024/03/05 11:58:31\",,,298,12410,11720,0,0,\"2024/03/05 12:01:42\",7020,12410,6870,11720,0\n\
+        D,MTPASA,REGIONAVAILABILITY,2,\"2024/03/05 12:00:00\",\"2026/03/05 00:00:00\",NSW1,12620,,,,305,12680,11930,0,0,\"2024/03/05 12:01:42\",7110,12680,6940,11930,0\n\
+        C,\"END OF REPORT\",6\n";
+
+    #[test]
+    fn test_process_file_mtpasa() {
+        let collection = process_file_mtpasa(MTPASA.as_bytes()).expect("Failed to parse");
+
+        assert_eq!(collection.records.len(), 3);
+        let outlook = latest_outlook(&collection.records);
+        let last = outlook.last().expect("No rows");
+        assert_eq!(last.region_id(), "NSW1");
+        assert_eq!(last.pasa_availability_scheduled, Some(12620.0));
+        assert_eq!(last.latest_offer_datetime, None);
+        assert_eq!(last.day.to_rfc3339(), "2026-03-04T13:00:00+00:00");
+        assert_eq!(last.lead_time().num_days(), 729);
+        assert_eq!(
+            outlook[0].latest_offer_datetime.map(|dt| dt.to_rfc3339()),
+            Some("2024-03-05T00:58:31+00:00".to_string())
+        );
+    }
+}
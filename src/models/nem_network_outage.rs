@@ -0,0 +1,266 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::fmt;
+use std::io::Read;
+use std::sync::Arc;
+
+use crate::common::intern::deserialize_interned;
+use crate::common::mms_csv::{DataRow, FromDataRow, MmsCsvReader, MmsRecords};
+use crate::common::unzip_process::{ProcessRecord, RecordsCollection};
+use crate::error::ScraperError;
+use crate::time::datetimezone_conversion::{
+    deserialize_optional_sydney_datetime_to_utc, deserialize_sydney_datetime_to_utc,
+};
+
+/// NETWORK.OUTAGEDETAIL: a planned network outage submitted by a TNSP, one row per piece of
+/// equipment taken out.
+///
+/// `RECALLTIMEDAY` and `RECALLTIMENIGHT` are in minutes, with -1 meaning the equipment cannot be
+/// recalled.
+#[derive(Debug, Deserialize)]
+pub struct OutageDetail {
+    #[serde(rename = "ROW_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    csv_row_identifier: Arc<str>,
+    #[serde(rename = "FILE_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    category: Arc<str>,
+    #[serde(rename = "FILE_SUBTYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    report_type: Arc<str>,
+    #[serde(rename = "FILE_DESCRIPTOR")]
+    report_type_int: u32,
+    #[serde(rename = "OUTAGEID")]
+    outage_id: u64,
+    #[serde(rename = "SUBSTATIONID")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    substation_id: Arc<str>,
+    #[serde(rename = "EQUIPMENTTYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    equipment_type: Arc<str>,
+    #[serde(rename = "EQUIPMENTID")]
+    equipment_id: String,
+    #[serde(rename = "STARTTIME")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    start_time: DateTime<Utc>,
+    #[serde(rename = "ENDTIME")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    end_time: DateTime<Utc>,
+    #[serde(rename = "SUBMITTEDDATE")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    submitted_date: DateTime<Utc>,
+    #[serde(rename = "OUTAGESTATUSCODE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    outage_status_code: Arc<str>,
+    #[serde(rename = "RESUBMITREASON")]
+    resubmit_reason: Option<String>,
+    #[serde(rename = "RESUBMITOUTAGEID")]
+    resubmit_outage_id: Option<u64>,
+    #[serde(rename = "RECALLTIMEDAY")]
+    recall_time_day: Option<i32>,
+    #[serde(rename = "RECALLTIMENIGHT")]
+    recall_time_night: Option<i32>,
+    #[serde(rename = "LASTCHANGED")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    last_changed: DateTime<Utc>,
+    #[serde(rename = "REASON")]
+    reason: Option<String>,
+    #[serde(rename = "ISSECONDARY")]
+    is_secondary: Option<u32>,
+    #[serde(rename = "ACTUAL_STARTTIME")]
+    #[serde(deserialize_with = "deserialize_optional_sydney_datetime_to_utc")]
+    actual_start_time: Option<DateTime<Utc>>,
+    #[serde(rename = "ACTUAL_ENDTIME")]
+    #[serde(deserialize_with = "deserialize_optional_sydney_datetime_to_utc")]
+    actual_end_time: Option<DateTime<Utc>>,
+    #[serde(rename = "COMPANYREFCODE")]
+    company_ref_code: Option<String>,
+    #[serde(rename = "ELEMENTID")]
+    element_id: Option<u64>,
+}
+
+impl fmt::Display for OutageDetail {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "OutageDetail: {{ csv_row_identifier: {}, category: {}, report_type: {}, report_type_int: {}, outage_id: {:?}, substation_id: {:?}, equipment_type: {:?}, equipment_id: {:?}, start_time: {:?}, end_time: {:?}, submitted_date: {:?}, outage_status_code: {:?}, resubmit_reason: {:?}, resubmit_outage_id: {:?}, recall_time_day: {:?}, recall_time_night: {:?}, last_changed: {:?}, reason: {:?}, is_secondary: {:?}, actual_start_time: {:?}, actual_end_time: {:?}, company_ref_code: {:?}, element_id: {:?} }}",
+            self.csv_row_identifier,
+            self.category,
+            self.report_type,
+            self.report_type_int,
+            self.outage_id,
+            self.substation_id,
+            self.equipment_type,
+            self.equipment_id,
+            self.start_time,
+            self.end_time,
+            self.submitted_date,
+            self.outage_status_code,
+            self.resubmit_reason,
+            self.resubmit_outage_id,
+            self.recall_time_day,
+            self.recall_time_night,
+            self.last_changed,
+            self.reason,
+            self.is_secondary,
+            self.actual_start_time,
+            self.actual_end_time,
+            self.company_ref_code,
+            self.element_id
+        )
+    }
+}
+
+/// NETWORK.OUTAGECONSTRAINTSET: the constraint sets invoked for an outage and when.
+#[derive(Debug, Deserialize)]
+pub struct OutageConstraintSet {
+    #[serde(rename = "ROW_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    csv_row_identifier: Arc<str>,
+    #[serde(rename = "FILE_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    category: Arc<str>,
+    #[serde(rename = "FILE_SUBTYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    report_type: Arc<str>,
+    #[serde(rename = "FILE_DESCRIPTOR")]
+    report_type_int: u32,
+    #[serde(rename = "OUTAGEID")]
+    outage_id: u64,
+    #[serde(rename = "GENCONSETID")]
+    gen_con_set_id: String,
+    #[serde(rename = "STARTINTERVAL")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    start_interval: DateTime<Utc>,
+    #[serde(rename = "ENDINTERVAL")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    end_interval: DateTime<Utc>,
+}
+
+impl fmt::Display for OutageConstraintSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "OutageConstraintSet: {{ csv_row_identifier: {}, category: {}, report_type: {}, report_type_int: {}, outage_id: {:?}, gen_con_set_id: {:?}, start_interval: {:?}, end_interval: {:?} }}",
+            self.csv_row_identifier,
+            self.category,
+            self.report_type,
+            self.report_type_int,
+            self.outage_id,
+            self.gen_con_set_id,
+            self.start_interval,
+            self.end_interval
+        )
+    }
+}
+
+/// A row of the NETWORK outage report (`PUBLIC_NETWORK_*.zip`).
+#[derive(Debug)]
+// OutageDetail is boxed to comply with cargo clippy warning for large enums
+pub enum RecordNetworkOutage {
+    OutageDetail(Box<OutageDetail>),
+    OutageConstraintSet(OutageConstraintSet),
+}
+
+impl fmt::Display for RecordNetworkOutage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecordNetworkOutage::OutageDetail(outage_detail) => write!(f, "{}", outage_detail),
+            RecordNetworkOutage::OutageConstraintSet(constraint_set) => {
+                write!(f, "{}", constraint_set)
+            }
+        }
+    }
+}
+
+impl ProcessRecord<RecordNetworkOutage> for OutageDetail {
+    fn process(row: &DataRow) -> Result<RecordNetworkOutage, ScraperError> {
+        let record = match row.version()? {
+            4 => row.deserialize::<OutageDetail>()?,
+            version => return Err(row.unsupported_version(version)),
+        };
+        Ok(RecordNetworkOutage::OutageDetail(Box::new(record)))
+    }
+}
+
+impl ProcessRecord<RecordNetworkOutage> for OutageConstraintSet {
+    fn process(row: &DataRow) -> Result<RecordNetworkOutage, ScraperError> {
+        let record = match row.version()? {
+            1 => row.deserialize::<OutageConstraintSet>()?,
+            version => return Err(row.unsupported_version(version)),
+        };
+        Ok(RecordNetworkOutage::OutageConstraintSet(record))
+    }
+}
+
+impl FromDataRow for RecordNetworkOutage {
+    fn from_data_row(row: &DataRow) -> Result<Self, ScraperError> {
+        if row.table.is("NETWORK", "OUTAGEDETAIL") {
+            OutageDetail::process(row)
+        } else if row.table.is("NETWORK", "OUTAGECONSTRAINTSET") {
+            OutageConstraintSet::process(row)
+        } else {
+            Err(row.unknown_table())
+        }
+    }
+}
+
+/// Streams network outage records one row at a time.
+pub fn stream_file_network_outage<R: Read>(reader: R) -> MmsRecords<R, RecordNetworkOutage> {
+    MmsCsvReader::new(reader).records()
+}
+
+pub fn process_file_network_outage<R: Read>(
+    reader: R,
+) -> Result<RecordsCollection<RecordNetworkOutage>, ScraperError> {
+    RecordsCollection::from_records(stream_file_network_outage(reader))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::unzip_process::unzip_and_process;
+
+    #[test]
+    fn test_process_file_network_outage_fixture() {
+        let collection = unzip_and_process(
+            "src/fixtures/PUBLIC_NETWORK_20240310200009_0000000413336789.zip",
+            |reader| process_file_network_outage(reader),
+        )
+        .expect("Failed to process network fixture");
+        let records = collection.records;
+
+        assert_eq!(records.len(), 10914);
+        let constraint_sets = records
+            .iter()
+            .filter(|record| matches!(record, RecordNetworkOutage::OutageConstraintSet(_)))
+            .count();
+        assert_eq!(constraint_sets, 367);
+
+        match &records[0] {
+            RecordNetworkOutage::OutageDetail(outage) => {
+                assert_eq!(outage.outage_id, 208883);
+                assert_eq!(&*outage.substation_id, "COFFS");
+                assert_eq!(&*outage.equipment_type, "LINE");
+                assert_eq!(outage.equipment_id, "89");
+                assert_eq!(&*outage.outage_status_code, "WDRAWN");
+                assert_eq!(
+                    outage.resubmit_reason.as_deref(),
+                    Some("Information Update")
+                );
+                assert_eq!(outage.resubmit_outage_id, Some(208902));
+                assert_eq!(outage.recall_time_day, Some(60));
+                assert_eq!(outage.recall_time_night, Some(-1));
+                assert_eq!(outage.actual_start_time, None);
+                assert_eq!(outage.start_time.to_rfc3339(), "2099-12-31T20:00:00+00:00");
+                assert_eq!(
+                    outage.last_changed.to_rfc3339(),
+                    "2011-01-27T23:29:12.250+00:00"
+                );
+            }
+            other => panic!("Expected OUTAGEDETAIL, got {}", other),
+        }
+
+        let completed = records.iter().find_map(|record| match record {
+            RecordNetworkOutage::OutageDetail(outage) => outage.actual_end_time,
+            _ => None,
+        });
+        assert!(completed.is_some());
+    }
+}
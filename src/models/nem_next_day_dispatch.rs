@@ -0,0 +1,361 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::fmt;
+use std::io::Read;
+use std::sync::Arc;
+
+use crate::common::intern::deserialize_interned;
+use crate::common::mms_csv::{DataRow, FromDataRow, MmsCsvReader, MmsRecords, TableKey};
+use crate::common::unit::UnitRow;
+use crate::common::unzip_process::{ProcessRecord, RecordsCollection};
+use crate::error::ScraperError;
+use crate::time::datetimezone_conversion::deserialize_sydney_datetime_to_utc;
+
+/// DISPATCH.UNIT_SOLUTION (DISPATCHLOAD in the MMS data model): the dispatch target, ramp
+/// rates and FCAS enablement of each unit for every dispatch interval of the day.
+///
+/// Columns added in later table versions, such as the 1-second FCAS markets in version 5, are
+/// `None` when reading an older version.
+#[derive(Debug, Deserialize)]
+pub struct UnitSolutionData {
+    #[serde(rename = "ROW_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    csv_row_identifier: Arc<str>,
+    #[serde(rename = "FILE_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    category: Arc<str>,
+    #[serde(rename = "FILE_SUBTYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    report_type: Arc<str>,
+    #[serde(rename = "FILE_DESCRIPTOR")]
+    report_type_int: u32,
+    #[serde(rename = "SETTLEMENTDATE")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    settlement_date: DateTime<Utc>,
+    #[serde(rename = "RUNNO")]
+    run_no: Option<u32>,
+    #[serde(rename = "DUID")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    duid: Arc<str>,
+    #[serde(rename = "TRADETYPE")]
+    trade_type: Option<u32>,
+    #[serde(rename = "DISPATCHINTERVAL")]
+    dispatch_interval: Option<u64>,
+    #[serde(rename = "INTERVENTION")]
+    intervention: Option<u32>,
+    #[serde(rename = "CONNECTIONPOINTID")]
+    connection_point_id: Option<String>,
+    #[serde(rename = "DISPATCHMODE")]
+    dispatch_mode: Option<u32>,
+    #[serde(rename = "AGCSTATUS")]
+    agc_status: Option<u32>,
+    #[serde(rename = "INITIALMW")]
+    initial_mw: Option<f64>,
+    #[serde(rename = "TOTALCLEARED")]
+    total_cleared: Option<f64>,
+    #[serde(rename = "RAMPDOWNRATE")]
+    ramp_down_rate: Option<f64>,
+    #[serde(rename = "RAMPUPRATE")]
+    ramp_up_rate: Option<f64>,
+    #[serde(rename = "LOWER5MIN")]
+    lower_5_min: Option<f64>,
+    #[serde(rename = "LOWER60SEC")]
+    lower_60_sec: Option<f64>,
+    #[serde(rename = "LOWER6SEC")]
+    lower_6_sec: Option<f64>,
+    #[serde(rename = "RAISE5MIN")]
+    raise_5_min: Option<f64>,
+    #[serde(rename = "RAISE60SEC")]
+    raise_60_sec: Option<f64>,
+    #[serde(rename = "RAISE6SEC")]
+    raise_6_sec: Option<f64>,
+    #[serde(rename = "DOWNEPF")]
+    down_epf: Option<f64>,
+    #[serde(rename = "UPEPF")]
+    up_epf: Option<f64>,
+    #[serde(rename = "MARGINAL5MINVALUE")]
+    marginal_5_min_value: Option<f64>,
+    #[serde(rename = "MARGINAL60SECVALUE")]
+    marginal_60_sec_value: Option<f64>,
+    #[serde(rename = "MARGINAL6SECVALUE")]
+    marginal_6_sec_value: Option<f64>,
+    #[serde(rename = "MARGINALVALUE")]
+    marginal_value: Option<f64>,
+    #[serde(rename = "VIOLATION5MINDEGREE")]
+    violation_5_min_degree: Option<f64>,
+    #[serde(rename = "VIOLATION60SECDEGREE")]
+    violation_60_sec_degree: Option<f64>,
+    #[serde(rename = "VIOLATION6SECDEGREE")]
+    violation_6_sec_degree: Option<f64>,
+    #[serde(rename = "VIOLATIONDEGREE")]
+    violation_degree: Option<f64>,
+    #[serde(rename = "LASTCHANGED")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    last_changed: DateTime<Utc>,
+    #[serde(rename = "LOWERREG")]
+    lower_reg: Option<f64>,
+    #[serde(rename = "RAISEREG")]
+    raise_reg: Option<f64>,
+    #[serde(rename = "AVAILABILITY")]
+    availability: Option<f64>,
+    #[serde(rename = "RAISE6SECFLAGS")]
+    raise_6_sec_flags: Option<u32>,
+    #[serde(rename = "RAISE60SECFLAGS")]
+    raise_60_sec_flags: Option<u32>,
+    #[serde(rename = "RAISE5MINFLAGS")]
+    raise_5_min_flags: Option<u32>,
+    #[serde(rename = "RAISEREGFLAGS")]
+    raise_reg_flags: Option<u32>,
+    #[serde(rename = "LOWER6SECFLAGS")]
+    lower_6_sec_flags: Option<u32>,
+    #[serde(rename = "LOWER60SECFLAGS")]
+    lower_60_sec_flags: Option<u32>,
+    #[serde(rename = "LOWER5MINFLAGS")]
+    lower_5_min_flags: Option<u32>,
+    #[serde(rename = "LOWERREGFLAGS")]
+    lower_reg_flags: Option<u32>,
+    #[serde(rename = "RAISEREGAVAILABILITY")]
+    raise_reg_availability: Option<f64>,
+    #[serde(rename = "RAISEREGENABLEMENTMAX")]
+    raise_reg_enablement_max: Option<f64>,
+    #[serde(rename = "RAISEREGENABLEMENTMIN")]
+    raise_reg_enablement_min: Option<f64>,
+    #[serde(rename = "LOWERREGAVAILABILITY")]
+    lower_reg_availability: Option<f64>,
+    #[serde(rename = "LOWERREGENABLEMENTMAX")]
+    lower_reg_enablement_max: Option<f64>,
+    #[serde(rename = "LOWERREGENABLEMENTMIN")]
+    lower_reg_enablement_min: Option<f64>,
+    #[serde(rename = "RAISE6SECACTUALAVAILABILITY")]
+    raise_6_sec_actual_availability: Option<f64>,
+    #[serde(rename = "RAISE60SECACTUALAVAILABILITY")]
+    raise_60_sec_actual_availability: Option<f64>,
+    #[serde(rename = "RAISE5MINACTUALAVAILABILITY")]
+    raise_5_min_actual_availability: Option<f64>,
+    #[serde(rename = "RAISEREGACTUALAVAILABILITY")]
+    raise_reg_actual_availability: Option<f64>,
+    #[serde(rename = "LOWER6SECACTUALAVAILABILITY")]
+    lower_6_sec_actual_availability: Option<f64>,
+    #[serde(rename = "LOWER60SECACTUALAVAILABILITY")]
+    lower_60_sec_actual_availability: Option<f64>,
+    #[serde(rename = "LOWER5MINACTUALAVAILABILITY")]
+    lower_5_min_actual_availability: Option<f64>,
+    #[serde(rename = "LOWERREGACTUALAVAILABILITY")]
+    lower_reg_actual_availability: Option<f64>,
+    #[serde(rename = "SEMIDISPATCHCAP")]
+    semi_dispatch_cap: Option<u32>,
+    #[serde(rename = "CONFORMANCE_MODE")]
+    conformance_mode: Option<u32>,
+    #[serde(rename = "UIGF")]
+    uigf: Option<f64>,
+    #[serde(rename = "RAISE1SEC")]
+    raise_1_sec: Option<f64>,
+    #[serde(rename = "RAISE1SECFLAGS")]
+    raise_1_sec_flags: Option<u32>,
+    #[serde(rename = "LOWER1SEC")]
+    lower_1_sec: Option<f64>,
+    #[serde(rename = "LOWER1SECFLAGS")]
+    lower_1_sec_flags: Option<u32>,
+    #[serde(rename = "RAISE1SECACTUALAVAILABILITY")]
+    raise_1_sec_actual_availability: Option<f64>,
+    #[serde(rename = "LOWER1SECACTUALAVAILABILITY")]
+    lower_1_sec_actual_availability: Option<f64>,
+}
+
+impl fmt::Display for UnitSolutionData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "UnitSolutionData: {{ csv_row_identifier: {}, category: {}, report_type: {}, report_type_int: {}, settlement_date: {:?}, run_no: {:?}, duid: {:?}, trade_type: {:?}, dispatch_interval: {:?}, intervention: {:?}, connection_point_id: {:?}, dispatch_mode: {:?}, agc_status: {:?}, initial_mw: {:?}, total_cleared: {:?}, ramp_down_rate: {:?}, ramp_up_rate: {:?}, lower_5_min: {:?}, lower_60_sec: {:?}, lower_6_sec: {:?}, raise_5_min: {:?}, raise_60_sec: {:?}, raise_6_sec: {:?}, down_epf: {:?}, up_epf: {:?}, marginal_5_min_value: {:?}, marginal_60_sec_value: {:?}, marginal_6_sec_value: {:?}, marginal_value: {:?}, violation_5_min_degree: {:?}, violation_60_sec_degree: {:?}, violation_6_sec_degree: {:?}, violation_degree: {:?}, last_changed: {:?}, lower_reg: {:?}, raise_reg: {:?}, availability: {:?}, raise_6_sec_flags: {:?}, raise_60_sec_flags: {:?}, raise_5_min_flags: {:?}, raise_reg_flags: {:?}, lower_6_sec_flags: {:?}, lower_60_sec_flags: {:?}, lower_5_min_flags: {:?}, lower_reg_flags: {:?}, raise_reg_availability: {:?}, raise_reg_enablement_max: {:?}, raise_reg_enablement_min: {:?}, lower_reg_availability: {:?}, lower_reg_enablement_max: {:?}, lower_reg_enablement_min: {:?}, raise_6_sec_actual_availability: {:?}, raise_60_sec_actual_availability: {:?}, raise_5_min_actual_availability: {:?}, raise_reg_actual_availability: {:?}, lower_6_sec_actual_availability: {:?}, lower_60_sec_actual_availability: {:?}, lower_5_min_actual_availability: {:?}, lower_reg_actual_availability: {:?}, semi_dispatch_cap: {:?}, conformance_mode: {:?}, uigf: {:?}, raise_1_sec: {:?}, raise_1_sec_flags: {:?}, lower_1_sec: {:?}, lower_1_sec_flags: {:?}, raise_1_sec_actual_availability: {:?}, lower_1_sec_actual_availability: {:?} }}",
+            self.csv_row_identifier,
+            self.category,
+            self.report_type,
+            self.report_type_int,
+            self.settlement_date,
+            self.run_no,
+            self.duid,
+            self.trade_type,
+            self.dispatch_interval,
+            self.intervention,
+            self.connection_point_id,
+            self.dispatch_mode,
+            self.agc_status,
+            self.initial_mw,
+            self.total_cleared,
+            self.ramp_down_rate,
+            self.ramp_up_rate,
+            self.lower_5_min,
+            self.lower_60_sec,
+            self.lower_6_sec,
+            self.raise_5_min,
+            self.raise_60_sec,
+            self.raise_6_sec,
+            self.down_epf,
+            self.up_epf,
+            self.marginal_5_min_value,
+            self.marginal_60_sec_value,
+            self.marginal_6_sec_value,
+            self.marginal_value,
+            self.violation_5_min_degree,
+            self.violation_60_sec_degree,
+            self.violation_6_sec_degree,
+            self.violation_degree,
+            self.last_changed,
+            self.lower_reg,
+            self.raise_reg,
+            self.availability,
+            self.raise_6_sec_flags,
+            self.raise_60_sec_flags,
+            self.raise_5_min_flags,
+            self.raise_reg_flags,
+            self.lower_6_sec_flags,
+            self.lower_60_sec_flags,
+            self.lower_5_min_flags,
+            self.lower_reg_flags,
+            self.raise_reg_availability,
+            self.raise_reg_enablement_max,
+            self.raise_reg_enablement_min,
+            self.lower_reg_availability,
+            self.lower_reg_enablement_max,
+            self.lower_reg_enablement_min,
+            self.raise_6_sec_actual_availability,
+            self.raise_60_sec_actual_availability,
+            self.raise_5_min_actual_availability,
+            self.raise_reg_actual_availability,
+            self.lower_6_sec_actual_availability,
+            self.lower_60_sec_actual_availability,
+            self.lower_5_min_actual_availability,
+            self.lower_reg_actual_availability,
+            self.semi_dispatch_cap,
+            self.conformance_mode,
+            self.uigf,
+            self.raise_1_sec,
+            self.raise_1_sec_flags,
+            self.lower_1_sec,
+            self.lower_1_sec_flags,
+            self.raise_1_sec_actual_availability,
+            self.lower_1_sec_actual_availability
+        )
+    }
+}
+
+impl UnitRow for UnitSolutionData {
+    fn duid(&self) -> &str {
+        &self.duid
+    }
+
+    fn settlement_datetime(&self) -> DateTime<Utc> {
+        self.settlement_date
+    }
+}
+
+#[derive(Debug)]
+// Boxed like RecordCurrentTradingIs, as UNIT_SOLUTION rows carry around seventy columns
+pub enum RecordNextDayDispatch {
+    UnitSolution(Box<UnitSolutionData>),
+}
+
+impl fmt::Display for RecordNextDayDispatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecordNextDayDispatch::UnitSolution(unit_solution) => write!(f, "{}", unit_solution),
+        }
+    }
+}
+
+impl ProcessRecord<RecordNextDayDispatch> for UnitSolutionData {
+    fn process(row: &DataRow) -> Result<RecordNextDayDispatch, ScraperError> {
+        let record = match row.version()? {
+            // Versions only ever add columns, which read as None when absent
+            3..=5 => row.deserialize::<UnitSolutionData>()?,
+            version => return Err(row.unsupported_version(version)),
+        };
+        Ok(RecordNextDayDispatch::UnitSolution(Box::new(record)))
+    }
+}
+
+impl FromDataRow for RecordNextDayDispatch {
+    fn from_data_row(row: &DataRow) -> Result<Self, ScraperError> {
+        if row.table.is("DISPATCH", "UNIT_SOLUTION") {
+            UnitSolutionData::process(row)
+        } else {
+            Err(row.unknown_table())
+        }
+    }
+
+    // Next_Day_Dispatch also carries LOCAL_PRICE, OFFERTRK, CONSTRAINT and other tables
+    fn reads_table(table: &TableKey) -> bool {
+        table.is("DISPATCH", "UNIT_SOLUTION")
+    }
+}
+
+/// Streams Next_Day_Dispatch unit solutions one row at a time.
+pub fn stream_file_next_day_dispatch<R: Read>(reader: R) -> MmsRecords<R, RecordNextDayDispatch> {
+    MmsCsvReader::new(reader).records()
+}
+
+/// Parses a Next_Day_Dispatch CSV. Use with `unzip_and_process` for a single day's zip or an
+/// ARCHIVE bundle of daily zips.
+pub fn process_file_next_day_dispatch<R: Read>(
+    reader: R,
+) -> Result<RecordsCollection<RecordNextDayDispatch>, ScraperError> {
+    RecordsCollection::from_records(stream_file_next_day_dispatch(reader))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::unzip_process::unzip_and_process;
+
+    const NEXT_DAY_DISPATCH: &str = "C,NEMP.WORLD,NEXT_DAY_DISPATCH,AEMO,PUBLIC,2024/03/03,04:05:30,0000000412599371,NEXT_DAY_DISPATCH,0000000412599370\n\
+        I,DISPATCH,UNIT_SOLUTION,5,SETTLEMENTDATE,RUNNO,DUID,TRADETYPE,DISPATCHINTERVAL,INTERVENTION,CONNECTIONPOINTID,DISPATCHMODE,AGCSTATUS,INITIALMW,TOTALCLEARED,RAMPDOWNRATE,RAMPUPRATE,LOWER5MIN,LOWER60SEC,LOWER6SEC,RAISE5MIN,RAISE60SEC,RAISE6SEC,DOWNEPF,UPEPF,MARGINAL5MINVALUE,MARGINAL60SECVALUE,MARGINAL6SECVALUE,MARGINALVALUE,VIOLATION5MINDEGREE,VIOLATION60SECDEGREE,VIOLATION6SECDEGREE,VIOLATIONDEGREE,LASTCHANGED,LOWERREG,RAISEREG,AVAILABILITY,RAISE6SECFLAGS,RAISE60SECFLAGS,RAISE5MINFLAGS,RAISEREGFLAGS,LOWER6SECFLAGS,LOWER60SECFLAGS,LOWER5MINFLAGS,LOWERREGFLAGS,RAISEREGAVAILABILITY,RAISEREGENABLEMENTMAX,RAISEREGENABLEMENTMIN,LOWERREGAVAILABILITY,LOWERREGENABLEMENTMAX,LOWERREGENABLEMENTMIN,RAISE6SECACTUALAVAILABILITY,RAISE60SECACTUALAVAILABILITY,RAISE5MINACTUALAVAILABILITY,RAISEREGACTUALAVAILABILITY,LOWER6SECACTUALAVAILABILITY,LOWER60SECACTUALAVAILABILITY,LOWER5MINACTUALAVAILABILITY,LOWERREGACTUALAVAILABILITY,SEMIDISPATCHCAP,CONFORMANCE_MODE,UIGF,RAISE1SEC,RAISE1SECFLAGS,LOWER1SEC,LOWER1SECFLAGS,RAISE1SECACTUALAVAILABILITY,LOWER1SECACTUALAVAILABILITY\n\
+        D,DISPATCH,UNIT_SOLUTION,5,\"2024/03/02 04:05:00\",1,BAYSW1,1,20240301288,0,NBAY1,0,1,470.2,472,240,240,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,\"2024/03/02 04:00:04\",0,10,660,0,0,0,1,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,5.5,0,0,0,0,0\n\
+        I,DISPATCH,LOCAL_PRICE,1,SETTLEMENTDATE,DUID,LOCAL_PRICE_ADJUSTMENT,LOCALLY_CONSTRAINED\n\
+        D,DISPATCH,LOCAL_PRICE,1,\"2024/03/02 04:05:00\",BAYSW1,0,0\n\
+        I,DISPATCH,UNIT_SOLUTION,4,SETTLEMENTDATE,RUNNO,DUID,TRADETYPE,DISPATCHINTERVAL,INTERVENTION,CONNECTIONPOINTID,DISPATCHMODE,AGCSTATUS,INITIALMW,TOTALCLEARED,RAMPDOWNRATE,RAMPUPRATE,LOWER5MIN,LOWER60SEC,LOWER6SEC,RAISE5MIN,RAISE60SEC,RAISE6SEC,DOWNEPF,UPEPF,MARGINAL5MINVALUE,MARGINAL60SECVALUE,MARGINAL6SECVALUE,MARGINALVALUE,VIOLATION5MINDEGREE,VIOLATION60SECDEGREE,VIOLATION6SECDEGREE,VIOLATIONDEGREE,LASTCHANGED,LOWERREG,RAISEREG,AVAILABILITY,RAISE6SECFLAGS,RAISE60SECFLAGS,RAISE5MINFLAGS,RAISEREGFLAGS,LOWER6SECFLAGS,LOWER60SECFLAGS,LOWER5MINFLAGS,LOWERREGFLAGS,RAISEREGAVAILABILITY,RAISEREGENABLEMENTMAX,RAISEREGENABLEMENTMIN,LOWERREGAVAILABILITY,LOWERREGENABLEMENTMAX,LOWERREGENABLEMENTMIN,RAISE6SECACTUALAVAILABILITY,RAISE60SECACTUALAVAILABILITY,RAISE5MINACTUALAVAILABILITY,RAISEREGACTUALAVAILABILITY,LOWER6SECACTUALAVAILABILITY,LOWER60SECACTUALAVAILABILITY,LOWER5MINACTUALAVAILABILITY,LOWERREGACTUALAVAILABILITY,SEMIDISPATCHCAP,CONFORMANCE_MODE,UIGF\n\
+        D,DISPATCH,UNIT_SOLUTION,4,\"2024/03/02 04:05:00\",1,ARWF1,1,20240301288,0,VARW1,0,1,0,117.4,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,\"2024/03/02 04:00:04\",0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,1,0,117.4\n\
+        C,\"END OF REPORT\",8\n";
+
+    #[test]
+    fn test_process_file_next_day_dispatch() {
+        let records = process_file_next_day_dispatch(NEXT_DAY_DISPATCH.as_bytes())
+            .expect("Failed to parse")
+            .records;
+
+        assert_eq!(records.len(), 2);
+        let RecordNextDayDispatch::UnitSolution(bayswater) = &records[0];
+        assert_eq!(bayswater.report_type_int, 5);
+        assert_eq!(&*bayswater.duid, "BAYSW1");
+        assert_eq!(bayswater.total_cleared, Some(472.0));
+        assert_eq!(bayswater.ramp_up_rate, Some(240.0));
+        assert_eq!(bayswater.raise_reg, Some(10.0));
+        assert_eq!(bayswater.raise_reg_flags, Some(1));
+        assert_eq!(bayswater.raise_1_sec, Some(5.5));
+        assert_eq!(bayswater.dispatch_interval, Some(20240301288));
+
+        let RecordNextDayDispatch::UnitSolution(wind_farm) = &records[1];
+        assert_eq!(wind_farm.report_type_int, 4);
+        assert_eq!(wind_farm.uigf, Some(117.4));
+        assert_eq!(wind_farm.raise_1_sec, None);
+    }
+
+    #[test]
+    fn test_process_next_day_dispatch_archive_fixture() {
+        let collection = unzip_and_process(
+            "src/fixtures/PUBLIC_NEXT_DAY_DISPATCH_20240302.zip",
+            |reader| process_file_next_day_dispatch(reader),
+        )
+        .expect("Failed to process Next_Day_Dispatch archive");
+
+        assert_eq!(collection.records.len(), 4);
+        assert_eq!(collection.record_sets.len(), 2);
+        assert_eq!(
+            collection.record_sets[1].file_name,
+            "PUBLIC_NEXT_DAY_DISPATCH_20240303_0000000412627834.zip/PUBLIC_NEXT_DAY_DISPATCH_20240303_0000000412627834.CSV"
+        );
+        let RecordNextDayDispatch::UnitSolution(bayswater) = &collection.records[2];
+        assert_eq!(&*bayswater.duid, "BAYSW1");
+        assert_eq!(
+            bayswater.settlement_date.to_rfc3339(),
+            "2024-03-02T17:05:00+00:00"
+        );
+    }
+}
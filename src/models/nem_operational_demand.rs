@@ -0,0 +1,411 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Read;
+use std::sync::Arc;
+
+use crate::common::forecast::ForecastRow;
+use crate::common::intern::deserialize_interned;
+use crate::common::mms_csv::{DataRow, FromDataRow, MmsCsvReader, MmsRecords};
+use crate::common::unzip_process::{ProcessRecord, RecordsCollection};
+use crate::error::ScraperError;
+use crate::models::nem_current_rooftop_pv_actual::RecordCurrentRooftopPvActual;
+use crate::time::datetimezone_conversion::deserialize_sydney_datetime_to_utc;
+
+/// OPERATIONAL_DEMAND.ACTUAL: average operational demand for a region over the half hour
+/// ending at `INTERVAL_DATETIME`.
+#[derive(Debug, Deserialize)]
+pub struct OperationalDemandActualData {
+    #[serde(rename = "ROW_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    csv_row_identifier: Arc<str>,
+    #[serde(rename = "FILE_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    category: Arc<str>,
+    #[serde(rename = "FILE_SUBTYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    report_type: Arc<str>,
+    #[serde(rename = "FILE_DESCRIPTOR")]
+    report_type_int: u32,
+    #[serde(rename = "INTERVAL_DATETIME")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    interval_datetime: DateTime<Utc>,
+    #[serde(rename = "REGIONID")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    region_id: Arc<str>,
+    #[serde(rename = "OPERATIONAL_DEMAND")]
+    operational_demand: Option<f64>,
+    #[serde(rename = "LASTCHANGED")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    last_changed: DateTime<Utc>,
+    #[serde(rename = "OPERATIONAL_DEMAND_ADJUSTMENT")]
+    operational_demand_adjustment: Option<f64>,
+    #[serde(rename = "WDR_ESTIMATE")]
+    wdr_estimate: Option<f64>,
+}
+
+impl fmt::Display for OperationalDemandActualData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "OperationalDemandActualData: {{ csv_row_identifier: {}, category: {}, report_type: {}, report_type_int: {}, interval_datetime: {:?}, region_id: {:?}, operational_demand: {:?}, last_changed: {:?}, operational_demand_adjustment: {:?}, wdr_estimate: {:?} }}",
+            self.csv_row_identifier,
+            self.category,
+            self.report_type,
+            self.report_type_int,
+            self.interval_datetime,
+            self.region_id,
+            self.operational_demand,
+            self.last_changed,
+            self.operational_demand_adjustment,
+            self.wdr_estimate
+        )
+    }
+}
+
+/// OPERATIONAL_DEMAND.ACTUAL_5MIN: average operational demand for a region over the 5 minutes
+/// ending at `INTERVAL_DATETIME`.
+#[derive(Debug, Deserialize)]
+pub struct OperationalDemandActual5MinData {
+    #[serde(rename = "ROW_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    csv_row_identifier: Arc<str>,
+    #[serde(rename = "FILE_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    category: Arc<str>,
+    #[serde(rename = "FILE_SUBTYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    report_type: Arc<str>,
+    #[serde(rename = "FILE_DESCRIPTOR")]
+    report_type_int: u32,
+    #[serde(rename = "INTERVAL_DATETIME")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    interval_datetime: DateTime<Utc>,
+    #[serde(rename = "REGIONID")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    region_id: Arc<str>,
+    #[serde(rename = "OPERATIONAL_DEMAND")]
+    operational_demand: Option<f64>,
+    #[serde(rename = "LASTCHANGED")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    last_changed: DateTime<Utc>,
+    #[serde(rename = "OPERATIONAL_DEMAND_ADJUSTMENT")]
+    operational_demand_adjustment: Option<f64>,
+    #[serde(rename = "WDR_ESTIMATE")]
+    wdr_estimate: Option<f64>,
+}
+
+impl fmt::Display for OperationalDemandActual5MinData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "OperationalDemandActual5MinData: {{ csv_row_identifier: {}, category: {}, report_type: {}, report_type_int: {}, interval_datetime: {:?}, region_id: {:?}, operational_demand: {:?}, last_changed: {:?}, operational_demand_adjustment: {:?}, wdr_estimate: {:?} }}",
+            self.csv_row_identifier,
+            self.category,
+            self.report_type,
+            self.report_type_int,
+            self.interval_datetime,
+            self.region_id,
+            self.operational_demand,
+            self.last_changed,
+            self.operational_demand_adjustment,
+            self.wdr_estimate
+        )
+    }
+}
+
+/// OPERATIONAL_DEMAND.FORECAST: the half-hourly operational demand forecast for a region.
+/// `LOAD_DATE` is when the forecast was made.
+#[derive(Debug, Deserialize)]
+pub struct OperationalDemandForecastData {
+    #[serde(rename = "ROW_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    csv_row_identifier: Arc<str>,
+    #[serde(rename = "FILE_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    category: Arc<str>,
+    #[serde(rename = "FILE_SUBTYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    report_type: Arc<str>,
+    #[serde(rename = "FILE_DESCRIPTOR")]
+    report_type_int: u32,
+    #[serde(rename = "INTERVAL_DATETIME")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    interval_datetime: DateTime<Utc>,
+    #[serde(rename = "REGIONID")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    region_id: Arc<str>,
+    #[serde(rename = "LOAD_DATE")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    load_date: DateTime<Utc>,
+    #[serde(rename = "OPERATIONAL_DEMAND_POE10")]
+    operational_demand_poe10: Option<f64>,
+    #[serde(rename = "OPERATIONAL_DEMAND_POE50")]
+    operational_demand_poe50: Option<f64>,
+    #[serde(rename = "OPERATIONAL_DEMAND_POE90")]
+    operational_demand_poe90: Option<f64>,
+    #[serde(rename = "LASTCHANGED")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    last_changed: DateTime<Utc>,
+}
+
+impl fmt::Display for OperationalDemandForecastData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "OperationalDemandForecastData: {{ csv_row_identifier: {}, category: {}, report_type: {}, report_type_int: {}, interval_datetime: {:?}, region_id: {:?}, load_date: {:?}, operational_demand_poe10: {:?}, operational_demand_poe50: {:?}, operational_demand_poe90: {:?}, last_changed: {:?} }}",
+            self.csv_row_identifier,
+            self.category,
+            self.report_type,
+            self.report_type_int,
+            self.interval_datetime,
+            self.region_id,
+            self.load_date,
+            self.operational_demand_poe10,
+            self.operational_demand_poe50,
+            self.operational_demand_poe90,
+            self.last_changed
+        )
+    }
+}
+
+impl ForecastRow for OperationalDemandForecastData {
+    fn run_datetime(&self) -> DateTime<Utc> {
+        self.load_date
+    }
+
+    fn interval_datetime(&self) -> DateTime<Utc> {
+        self.interval_datetime
+    }
+}
+
+/// Records from the `ACTUAL_HH`, `ACTUAL_5MIN` and `FORECAST_HH` folders of
+/// `/Reports/Current/Operational_Demand/`.
+#[derive(Debug)]
+pub enum RecordOperationalDemand {
+    ActualHalfHour(OperationalDemandActualData),
+    Actual5Min(OperationalDemandActual5MinData),
+    Forecast(OperationalDemandForecastData),
+}
+
+impl RecordOperationalDemand {
+    pub fn region_id(&self) -> &str {
+        match self {
+            RecordOperationalDemand::ActualHalfHour(actual) => &actual.region_id,
+            RecordOperationalDemand::Actual5Min(actual) => &actual.region_id,
+            RecordOperationalDemand::Forecast(forecast) => &forecast.region_id,
+        }
+    }
+
+    pub fn interval_datetime(&self) -> DateTime<Utc> {
+        match self {
+            RecordOperationalDemand::ActualHalfHour(actual) => actual.interval_datetime,
+            RecordOperationalDemand::Actual5Min(actual) => actual.interval_datetime,
+            RecordOperationalDemand::Forecast(forecast) => forecast.interval_datetime,
+        }
+    }
+}
+
+impl fmt::Display for RecordOperationalDemand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecordOperationalDemand::ActualHalfHour(actual) => write!(f, "{}", actual),
+            RecordOperationalDemand::Actual5Min(actual) => write!(f, "{}", actual),
+            RecordOperationalDemand::Forecast(forecast) => write!(f, "{}", forecast),
+        }
+    }
+}
+
+impl ProcessRecord<RecordOperationalDemand> for OperationalDemandActualData {
+    fn process(row: &DataRow) -> Result<RecordOperationalDemand, ScraperError> {
+        let record = match row.version()? {
+            3 => row.deserialize::<OperationalDemandActualData>()?,
+            version => return Err(row.unsupported_version(version)),
+        };
+        Ok(RecordOperationalDemand::ActualHalfHour(record))
+    }
+}
+
+impl ProcessRecord<RecordOperationalDemand> for OperationalDemandActual5MinData {
+    fn process(row: &DataRow) -> Result<RecordOperationalDemand, ScraperError> {
+        let record = match row.version()? {
+            1 => row.deserialize::<OperationalDemandActual5MinData>()?,
+            version => return Err(row.unsupported_version(version)),
+        };
+        Ok(RecordOperationalDemand::Actual5Min(record))
+    }
+}
+
+impl ProcessRecord<RecordOperationalDemand> for OperationalDemandForecastData {
+    fn process(row: &DataRow) -> Result<RecordOperationalDemand, ScraperError> {
+        let record = match row.version()? {
+            1 => row.deserialize::<OperationalDemandForecastData>()?,
+            version => return Err(row.unsupported_version(version)),
+        };
+        Ok(RecordOperationalDemand::Forecast(record))
+    }
+}
+
+impl FromDataRow for RecordOperationalDemand {
+    fn from_data_row(row: &DataRow) -> Result<Self, ScraperError> {
+        if row.table.is("OPERATIONAL_DEMAND", "ACTUAL") {
+            OperationalDemandActualData::process(row)
+        } else if row.table.is("OPERATIONAL_DEMAND", "ACTUAL_5MIN") {
+            OperationalDemandActual5MinData::process(row)
+        } else if row.table.is("OPERATIONAL_DEMAND", "FORECAST") {
+            OperationalDemandForecastData::process(row)
+        } else {
+            Err(row.unknown_table())
+        }
+    }
+}
+
+/// Streams operational demand records one row at a time.
+pub fn stream_file_operational_demand<R: Read>(
+    reader: R,
+) -> MmsRecords<R, RecordOperationalDemand> {
+    MmsCsvReader::new(reader).records()
+}
+
+pub fn process_file_operational_demand<R: Read>(
+    reader: R,
+) -> Result<RecordsCollection<RecordOperationalDemand>, ScraperError> {
+    RecordsCollection::from_records(stream_file_operational_demand(reader))
+}
+
+/// Half-hourly operational demand next to the rooftop PV output for the same region and
+/// interval. Both are averages over the half hour ending at `interval_datetime`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DemandWithRooftopPv {
+    pub region_id: Arc<str>,
+    pub interval_datetime: DateTime<Utc>,
+    pub operational_demand: Option<f64>,
+    pub rooftop_pv_power: Option<f64>,
+}
+
+impl DemandWithRooftopPv {
+    /// Operational demand plus the demand met by rooftop PV.
+    pub fn underlying_demand(&self) -> Option<f64> {
+        Some(self.operational_demand? + self.rooftop_pv_power?)
+    }
+}
+
+/// Pairs half-hourly operational demand actuals with rooftop PV `MEASUREMENT` actuals.
+/// Intervals missing from either side are left out.
+pub fn combine_with_rooftop_actuals(
+    demand: &[RecordOperationalDemand],
+    rooftop: &[RecordCurrentRooftopPvActual],
+) -> Vec<DemandWithRooftopPv> {
+    let rooftop: HashMap<(&str, DateTime<Utc>), Option<f64>> = rooftop
+        .iter()
+        .filter_map(|RecordCurrentRooftopPvActual::Variant1(actual)| {
+            (&*actual.type_ == "MEASUREMENT")
+                .then_some(((&*actual.regionid, actual.interval_datetime), actual.power))
+        })
+        .collect();
+
+    let mut combined: Vec<DemandWithRooftopPv> = demand
+        .iter()
+        .filter_map(|record| match record {
+            RecordOperationalDemand::ActualHalfHour(actual) => Some(actual),
+            _ => None,
+        })
+        .filter_map(|actual| {
+            let rooftop_pv_power = rooftop.get(&(&*actual.region_id, actual.interval_datetime))?;
+            Some(DemandWithRooftopPv {
+                region_id: Arc::clone(&actual.region_id),
+                interval_datetime: actual.interval_datetime,
+                operational_demand: actual.operational_demand,
+                rooftop_pv_power: *rooftop_pv_power,
+            })
+        })
+        .collect();
+    combined.sort_by(|a, b| {
+        (a.interval_datetime, &a.region_id).cmp(&(b.interval_datetime, &b.region_id))
+    });
+    combined
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::nem_current_rooftop_pv_actual::process_file_current_rooftop_actual;
+    use std::fs;
+
+    const ACTUAL_HH: &str = "C,NEMP.WORLD,ACTUAL_OPERATIONAL_DEMAND_HH,AEMO,PUBLIC,2024/03/03,19:30:14,0000000412706542,DEMAND,0000000412706541\n\
+        I,OPERATIONAL_DEMAND,ACTUAL,3,INTERVAL_DATETIME,REGIONID,OPERATIONAL_DEMAND,LASTCHANGED,OPERATIONAL_DEMAND_ADJUSTMENT,WDR_ESTIMATE\n\
+        D,OPERATIONAL_DEMAND,ACTUAL,3,\"2024/03/03 19:30:00\",NSW1,8315,\"2024/03/03 19:30:06\",-4,0\n\
+        D,OPERATIONAL_DEMAND,ACTUAL,3,\"2024/03/03 19:30:00\",QLD1,7120,\"2024/03/03 19:30:06\",0,0\n\
+        D,OPERATIONAL_DEMAND,ACTUAL,3,\"2024/03/03 19:30:00\",SA1,1689,\"2024/03/03 19:30:06\",0,\n\
+        C,\"END OF REPORT\",6\n";
+
+    const ACTUAL_5MIN: &str = "C,NEMP.WORLD,ACTUAL_OPERATIONAL_DEMAND_5MIN,AEMO,PUBLIC,2024/03/03,19:35:12,0000000412706689,DEMAND,0000000412706688\n\
+        I,OPERATIONAL_DEMAND,ACTUAL_5MIN,1,INTERVAL_DATETIME,REGIONID,OPERATIONAL_DEMAND,LASTCHANGED\n\
+        D,OPERATIONAL_DEMAND,ACTUAL_5MIN,1,\"2024/03/03 19:35:00\",VIC1,5873,\"2024/03/03 19:35:04\"\n\
+        C,\"END OF REPORT\",4\n";
+
+    const FORECAST_HH: &str = "C,NEMP.WORLD,FORECAST_OPERATIONAL_DEMAND_HH,AEMO,PUBLIC,2024/03/03,19:30:22,0000000412706560,DEMAND,0000000412706559\n\
+        I,OPERATIONAL_DEMAND,FORECAST,1,INTERVAL_DATETIME,REGIONID,LOAD_DATE,OPERATIONAL_DEMAND_POE10,OPERATIONAL_DEMAND_POE50,OPERATIONAL_DEMAND_POE90,LASTCHANGED\n\
+        D,OPERATIONAL_DEMAND,FORECAST,1,\"2024/03/03 20:00:00\",NSW1,\"2024/03/03 19:30:00\",8617,8402,8190,\"2024/03/03 19:30:15\"\n\
+        C,\"END OF REPORT\",4\n";
+
+    #[test]
+    fn test_process_file_operational_demand() {
+        let actual = process_file_operational_demand(ACTUAL_HH.as_bytes())
+            .expect("Failed to parse")
+            .records;
+        assert_eq!(actual.len(), 3);
+        assert_eq!(actual[2].region_id(), "SA1");
+        assert_eq!(
+            actual[2].interval_datetime().to_rfc3339(),
+            "2024-03-03T08:30:00+00:00"
+        );
+        match &actual[0] {
+            RecordOperationalDemand::ActualHalfHour(nsw) => {
+                assert_eq!(nsw.operational_demand, Some(8315.0));
+                assert_eq!(nsw.operational_demand_adjustment, Some(-4.0));
+            }
+            other => panic!("Expected ACTUAL, got {}", other),
+        }
+
+        let actual_5min = process_file_operational_demand(ACTUAL_5MIN.as_bytes())
+            .expect("Failed to parse")
+            .records;
+        match &actual_5min[0] {
+            RecordOperationalDemand::Actual5Min(vic) => {
+                assert_eq!(vic.operational_demand, Some(5873.0));
+                assert_eq!(vic.wdr_estimate, None);
+            }
+            other => panic!("Expected ACTUAL_5MIN, got {}", other),
+        }
+
+        let forecast = process_file_operational_demand(FORECAST_HH.as_bytes())
+            .expect("Failed to parse")
+            .records;
+        match &forecast[0] {
+            RecordOperationalDemand::Forecast(nsw) => {
+                assert_eq!(nsw.operational_demand_poe50, Some(8402.0));
+                assert_eq!(nsw.lead_time().num_minutes(), 30);
+            }
+            other => panic!("Expected FORECAST, got {}", other),
+        }
+    }
+
+    #[test]
+    fn test_combine_with_rooftop_actuals() {
+        let demand = process_file_operational_demand(ACTUAL_HH.as_bytes())
+            .expect("Failed to parse")
+            .records;
+        let contents = fs::read(
+            "src/fixtures/PUBLIC_ROOFTOP_PV_ACTUAL_MEASUREMENT_20240303200000_0000000412707330.csv",
+        )
+        .expect("Failed to read rooftop PV fixture");
+        let rooftop = process_file_current_rooftop_actual(contents.as_slice())
+            .expect("Failed to parse")
+            .records;
+
+        let combined = combine_with_rooftop_actuals(&demand, &rooftop);
+        assert_eq!(combined.len(), 3);
+        let sa = combined
+            .iter()
+            .find(|row| &*row.region_id == "SA1")
+            .expect("No SA1 row");
+        assert_eq!(sa.rooftop_pv_power, Some(6.617));
+        assert_eq!(sa.underlying_demand(), Some(1689.0 + 6.617));
+    }
+}
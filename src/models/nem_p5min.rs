@@ -0,0 +1,366 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::fmt;
+use std::io::Read;
+use std::sync::Arc;
+
+use crate::common::forecast::ForecastRow;
+use crate::common::intern::deserialize_interned;
+use crate::common::mms_csv::{DataRow, FromDataRow, MmsCsvReader, MmsRecords, TableKey};
+use crate::common::unzip_process::{ProcessRecord, RecordsCollection};
+use crate::error::ScraperError;
+use crate::time::datetimezone_conversion::deserialize_sydney_datetime_to_utc;
+
+// Each P5MIN file is one run covering the next 12 dispatch intervals. RUN_DATETIME is the
+// first of them, so the row for that interval has no lead time.
+
+/// P5MIN.REGIONSOLUTION: forecast prices and demand for a region and 5-minute interval.
+#[derive(Debug, Deserialize)]
+pub struct RegionSolutionData {
+    #[serde(rename = "ROW_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    csv_row_identifier: Arc<str>,
+    #[serde(rename = "FILE_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    category: Arc<str>,
+    #[serde(rename = "FILE_SUBTYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    report_type: Arc<str>,
+    #[serde(rename = "FILE_DESCRIPTOR")]
+    report_type_int: u32,
+    #[serde(rename = "RUN_DATETIME")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    run_datetime: DateTime<Utc>,
+    #[serde(rename = "INTERVENTION")]
+    intervention: Option<u32>,
+    #[serde(rename = "INTERVAL_DATETIME")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    interval_datetime: DateTime<Utc>,
+    #[serde(rename = "REGIONID")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    region_id: Arc<str>,
+    #[serde(rename = "RRP")]
+    rrp: Option<f64>,
+    #[serde(rename = "ROP")]
+    rop: Option<f64>,
+    #[serde(rename = "EXCESSGENERATION")]
+    excess_generation: Option<f64>,
+    #[serde(rename = "RAISE6SECRRP")]
+    raise_6_sec_rrp: Option<f64>,
+    #[serde(rename = "RAISE60SECRRP")]
+    raise_60_sec_rrp: Option<f64>,
+    #[serde(rename = "RAISE5MINRRP")]
+    raise_5_min_rrp: Option<f64>,
+    #[serde(rename = "RAISEREGRRP")]
+    raise_reg_rrp: Option<f64>,
+    #[serde(rename = "LOWER6SECRRP")]
+    lower_6_sec_rrp: Option<f64>,
+    #[serde(rename = "LOWER60SECRRP")]
+    lower_60_sec_rrp: Option<f64>,
+    #[serde(rename = "LOWER5MINRRP")]
+    lower_5_min_rrp: Option<f64>,
+    #[serde(rename = "LOWERREGRRP")]
+    lower_reg_rrp: Option<f64>,
+    #[serde(rename = "RAISE1SECRRP")]
+    raise_1_sec_rrp: Option<f64>,
+    #[serde(rename = "LOWER1SECRRP")]
+    lower_1_sec_rrp: Option<f64>,
+    #[serde(rename = "TOTALDEMAND")]
+    total_demand: Option<f64>,
+    #[serde(rename = "AVAILABLEGENERATION")]
+    available_generation: Option<f64>,
+    #[serde(rename = "AVAILABLELOAD")]
+    available_load: Option<f64>,
+    #[serde(rename = "DEMANDFORECAST")]
+    demand_forecast: Option<f64>,
+    #[serde(rename = "DISPATCHABLEGENERATION")]
+    dispatchable_generation: Option<f64>,
+    #[serde(rename = "DISPATCHABLELOAD")]
+    dispatchable_load: Option<f64>,
+    #[serde(rename = "NETINTERCHANGE")]
+    net_interchange: Option<f64>,
+    #[serde(rename = "INITIALSUPPLY")]
+    initial_supply: Option<f64>,
+    #[serde(rename = "CLEAREDSUPPLY")]
+    cleared_supply: Option<f64>,
+    #[serde(rename = "UIGF")]
+    uigf: Option<f64>,
+    #[serde(rename = "SS_SOLAR_UIGF")]
+    ss_solar_uigf: Option<f64>,
+    #[serde(rename = "SS_WIND_UIGF")]
+    ss_wind_uigf: Option<f64>,
+    #[serde(rename = "LASTCHANGED")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    last_changed: DateTime<Utc>,
+}
+
+impl fmt::Display for RegionSolutionData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "RegionSolutionData: {{ csv_row_identifier: {}, category: {}, report_type: {}, report_type_int: {}, run_datetime: {:?}, intervention: {:?}, interval_datetime: {:?}, region_id: {:?}, rrp: {:?}, rop: {:?}, excess_generation: {:?}, raise_6_sec_rrp: {:?}, raise_60_sec_rrp: {:?}, raise_5_min_rrp: {:?}, raise_reg_rrp: {:?}, lower_6_sec_rrp: {:?}, lower_60_sec_rrp: {:?}, lower_5_min_rrp: {:?}, lower_reg_rrp: {:?}, raise_1_sec_rrp: {:?}, lower_1_sec_rrp: {:?}, total_demand: {:?}, available_generation: {:?}, available_load: {:?}, demand_forecast: {:?}, dispatchable_generation: {:?}, dispatchable_load: {:?}, net_interchange: {:?}, initial_supply: {:?}, cleared_supply: {:?}, uigf: {:?}, ss_solar_uigf: {:?}, ss_wind_uigf: {:?}, last_changed: {:?} }}",
+            self.csv_row_identifier,
+            self.category,
+            self.report_type,
+            self.report_type_int,
+            self.run_datetime,
+            self.intervention,
+            self.interval_datetime,
+            self.region_id,
+            self.rrp,
+            self.rop,
+            self.excess_generation,
+            self.raise_6_sec_rrp,
+            self.raise_60_sec_rrp,
+            self.raise_5_min_rrp,
+            self.raise_reg_rrp,
+            self.lower_6_sec_rrp,
+            self.lower_60_sec_rrp,
+            self.lower_5_min_rrp,
+            self.lower_reg_rrp,
+            self.raise_1_sec_rrp,
+            self.lower_1_sec_rrp,
+            self.total_demand,
+            self.available_generation,
+            self.available_load,
+            self.demand_forecast,
+            self.dispatchable_generation,
+            self.dispatchable_load,
+            self.net_interchange,
+            self.initial_supply,
+            self.cleared_supply,
+            self.uigf,
+            self.ss_solar_uigf,
+            self.ss_wind_uigf,
+            self.last_changed
+        )
+    }
+}
+
+/// P5MIN.INTERCONNECTORSOLN: forecast flow and limits for an interconnector and 5-minute
+/// interval.
+#[derive(Debug, Deserialize)]
+pub struct InterconnectorSolutionData {
+    #[serde(rename = "ROW_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    csv_row_identifier: Arc<str>,
+    #[serde(rename = "FILE_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    category: Arc<str>,
+    #[serde(rename = "FILE_SUBTYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    report_type: Arc<str>,
+    #[serde(rename = "FILE_DESCRIPTOR")]
+    report_type_int: u32,
+    #[serde(rename = "RUN_DATETIME")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    run_datetime: DateTime<Utc>,
+    #[serde(rename = "INTERCONNECTORID")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    interconnector_id: Arc<str>,
+    #[serde(rename = "INTERVAL_DATETIME")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    interval_datetime: DateTime<Utc>,
+    #[serde(rename = "METEREDMWFLOW")]
+    metered_mw_flow: Option<f64>,
+    #[serde(rename = "MWFLOW")]
+    mw_flow: Option<f64>,
+    #[serde(rename = "MWLOSSES")]
+    mw_losses: Option<f64>,
+    #[serde(rename = "MARGINALVALUE")]
+    marginal_value: Option<f64>,
+    #[serde(rename = "VIOLATIONDEGREE")]
+    violation_degree: Option<f64>,
+    #[serde(rename = "MNSP")]
+    mnsp: Option<u32>,
+    #[serde(rename = "EXPORTLIMIT")]
+    export_limit: Option<f64>,
+    #[serde(rename = "IMPORTLIMIT")]
+    import_limit: Option<f64>,
+    #[serde(rename = "MARGINALLOSS")]
+    marginal_loss: Option<f64>,
+    #[serde(rename = "EXPORTGENCONID")]
+    export_gen_con_id: Option<String>,
+    #[serde(rename = "IMPORTGENCONID")]
+    import_gen_con_id: Option<String>,
+    #[serde(rename = "FCASEXPORTLIMIT")]
+    fcas_export_limit: Option<f64>,
+    #[serde(rename = "FCASIMPORTLIMIT")]
+    fcas_import_limit: Option<f64>,
+    #[serde(rename = "LASTCHANGED")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    last_changed: DateTime<Utc>,
+    #[serde(rename = "INTERVENTION")]
+    intervention: Option<u32>,
+}
+
+impl fmt::Display for InterconnectorSolutionData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "InterconnectorSolutionData: {{ csv_row_identifier: {}, category: {}, report_type: {}, report_type_int: {}, run_datetime: {:?}, interconnector_id: {:?}, interval_datetime: {:?}, metered_mw_flow: {:?}, mw_flow: {:?}, mw_losses: {:?}, marginal_value: {:?}, violation_degree: {:?}, mnsp: {:?}, export_limit: {:?}, import_limit: {:?}, marginal_loss: {:?}, export_gen_con_id: {:?}, import_gen_con_id: {:?}, fcas_export_limit: {:?}, fcas_import_limit: {:?}, last_changed: {:?}, intervention: {:?} }}",
+            self.csv_row_identifier,
+            self.category,
+            self.report_type,
+            self.report_type_int,
+            self.run_datetime,
+            self.interconnector_id,
+            self.interval_datetime,
+            self.metered_mw_flow,
+            self.mw_flow,
+            self.mw_losses,
+            self.marginal_value,
+            self.violation_degree,
+            self.mnsp,
+            self.export_limit,
+            self.import_limit,
+            self.marginal_loss,
+            self.export_gen_con_id,
+            self.import_gen_con_id,
+            self.fcas_export_limit,
+            self.fcas_import_limit,
+            self.last_changed,
+            self.intervention
+        )
+    }
+}
+
+impl ForecastRow for RegionSolutionData {
+    fn run_datetime(&self) -> DateTime<Utc> {
+        self.run_datetime
+    }
+
+    fn interval_datetime(&self) -> DateTime<Utc> {
+        self.interval_datetime
+    }
+}
+
+impl ForecastRow for InterconnectorSolutionData {
+    fn run_datetime(&self) -> DateTime<Utc> {
+        self.run_datetime
+    }
+
+    fn interval_datetime(&self) -> DateTime<Utc> {
+        self.interval_datetime
+    }
+}
+
+#[derive(Debug)]
+pub enum RecordP5Min {
+    RegionSolution(RegionSolutionData),
+    InterconnectorSolution(InterconnectorSolutionData),
+}
+
+impl fmt::Display for RecordP5Min {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecordP5Min::RegionSolution(region_solution) => write!(f, "{}", region_solution),
+            RecordP5Min::InterconnectorSolution(interconnector_solution) => {
+                write!(f, "{}", interconnector_solution)
+            }
+        }
+    }
+}
+
+impl ProcessRecord<RecordP5Min> for RegionSolutionData {
+    fn process(row: &DataRow) -> Result<RecordP5Min, ScraperError> {
+        let record = match row.version()? {
+            9 => row.deserialize::<RegionSolutionData>()?,
+            version => return Err(row.unsupported_version(version)),
+        };
+        Ok(RecordP5Min::RegionSolution(record))
+    }
+}
+
+impl ProcessRecord<RecordP5Min> for InterconnectorSolutionData {
+    fn process(row: &DataRow) -> Result<RecordP5Min, ScraperError> {
+        let record = match row.version()? {
+            4 => row.deserialize::<InterconnectorSolutionData>()?,
+            version => return Err(row.unsupported_version(version)),
+        };
+        Ok(RecordP5Min::InterconnectorSolution(record))
+    }
+}
+
+impl FromDataRow for RecordP5Min {
+    fn from_data_row(row: &DataRow) -> Result<Self, ScraperError> {
+        if row.table.is("P5MIN", "REGIONSOLUTION") {
+            RegionSolutionData::process(row)
+        } else if row.table.is("P5MIN", "INTERCONNECTORSOLN") {
+            InterconnectorSolutionData::process(row)
+        } else {
+            Err(row.unknown_table())
+        }
+    }
+
+    // P5MIN also carries case, constraint, price sensitivity and unit solution tables
+    fn reads_table(table: &TableKey) -> bool {
+        table.is("P5MIN", "REGIONSOLUTION") || table.is("P5MIN", "INTERCONNECTORSOLN")
+    }
+}
+
+/// Streams P5MIN records one row at a time.
+pub fn stream_file_p5min<R: Read>(reader: R) -> MmsRecords<R, RecordP5Min> {
+    MmsCsvReader::new(reader).records()
+}
+
+pub fn process_file_p5min<R: Read>(
+    reader: R,
+) -> Result<RecordsCollection<RecordP5Min>, ScraperError> {
+    RecordsCollection::from_records(stream_file_p5min(reader))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const P5MIN: &str = "C,NEMP.WORLD,P5MIN,AEMO,PUBLIC,2024/03/03,19:25:36,0000000412705301,P5MIN,0000000412705296\n\
+        I,P5MIN,CASESOLUTION,2,RUN_DATETIME,STARTINTERVAL_DATETIME,TOTALOBJECTIVE,LASTCHANGED\n\
+        D,P5MIN,CASESOLUTION,2,\"2024/03/03 19:30:00\",\"2024/03/03 19:30:00\",-48720531.2,\"2024/03/03 19:25:32\"\n\
+        I,P5MIN,REGIONSOLUTION,9,RUN_DATETIME,INTERVENTION,INTERVAL_DATETIME,REGIONID,RRP,ROP,EXCESSGENERATION,RAISE6SECRRP,RAISE60SECRRP,RAISE5MINRRP,RAISEREGRRP,LOWER6SECRRP,LOWER60SECRRP,LOWER5MINRRP,LOWERREGRRP,RAISE1SECRRP,LOWER1SECRRP,TOTALDEMAND,AVAILABLEGENERATION,AVAILABLELOAD,DEMANDFORECAST,DISPATCHABLEGENERATION,DISPATCHABLELOAD,NETINTERCHANGE,INITIALSUPPLY,CLEAREDSUPPLY,UIGF,SS_SOLAR_UIGF,SS_WIND_UIGF,LASTCHANGED\n\
+        D,P5MIN,REGIONSOLUTION,9,\"2024/03/03 19:30:00\",0,\"2024/03/03 19:30:00\",NSW1,87.1,87.1,0,0.39,0.39,0.38,3.25,0.01,0.01,0.01,0.72,0.1,0.05,8102.3,12690.2,194,-8.4,8370.9,0,268.6,8095.1,8102.3,1120.4,0,1120.4,\"2024/03/03 19:25:32\"\n\
+        D,P5MIN,REGIONSOLUTION,9,\"2024/03/03 19:30:00\",0,\"2024/03/03 19:35:00\",NSW1,91.4,91.4,0,0.39,0.39,0.38,3.25,0.01,0.01,0.01,0.72,0.1,0.05,8080.9,12690.2,194,-8.4,8370.9,0,268.6,8095.1,8080.9,1120.4,0,1120.4,\"2024/03/03 19:25:32\"\n\
+        D,P5MIN,REGIONSOLUTION,9,\"2024/03/03 19:30:00\",0,\"2024/03/03 19:40:00\",NSW1,89.9,89.9,0,0.39,0.39,0.38,3.25,0.01,0.01,0.01,0.72,0.1,0.05,8051.6,12690.2,194,-8.4,8370.9,0,268.6,8095.1,8051.6,1120.4,0,1120.4,\"2024/03/03 19:25:32\"\n\
+        I,P5MIN,INTERCONNECTORSOLN,4,RUN_DATETIME,INTERCONNECTORID,INTERVAL_DATETIME,METEREDMWFLOW,MWFLOW,MWLOSSES,MARGINALVALUE,VIOLATIONDEGREE,MNSP,EXPORTLIMIT,IMPORTLIMIT,MARGINALLOSS,EXPORTGENCONID,IMPORTGENCONID,FCASEXPORTLIMIT,FCASIMPORTLIMIT,LASTCHANGED,LOCAL_PRICE_ADJUSTMENT_EXPORT,LOCALLY_CONSTRAINED_EXPORT,LOCAL_PRICE_ADJUSTMENT_IMPORT,LOCALLY_CONSTRAINED_IMPORT,INTERVENTION\n\
+        D,P5MIN,INTERCONNECTORSOLN,4,\"2024/03/03 19:30:00\",VIC1-NSW1,\"2024/03/03 19:35:00\",0,-412.3,18.2,0,0,0,1050.4,-1288.2,0.97512,V>>N-NIL_HA,N^^V_NIL_1,1050.4,-1288.2,\"2024/03/03 19:25:32\",0,0,0,0,0\n\
+        C,\"END OF REPORT\",10\n";
+
+    #[test]
+    fn test_process_file_p5min() {
+        let records = process_file_p5min(P5MIN.as_bytes())
+            .expect("Failed to parse")
+            .records;
+
+        assert_eq!(records.len(), 4);
+        let lead_times: Vec<i64> = records
+            .iter()
+            .filter_map(|record| match record {
+                RecordP5Min::RegionSolution(region) => Some(region.lead_time().num_minutes()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(lead_times, [0, 5, 10]);
+
+        match &records[1] {
+            RecordP5Min::RegionSolution(region) => {
+                assert_eq!(&*region.region_id, "NSW1");
+                assert_eq!(region.rrp, Some(91.4));
+                assert_eq!(region.total_demand, Some(8080.9));
+                assert_eq!(
+                    region.run_datetime.to_rfc3339(),
+                    "2024-03-03T08:30:00+00:00"
+                );
+                assert_eq!(
+                    region.interval_datetime.to_rfc3339(),
+                    "2024-03-03T08:35:00+00:00"
+                );
+            }
+            other => panic!("Expected REGIONSOLUTION, got {}", other),
+        }
+        match &records[3] {
+            RecordP5Min::InterconnectorSolution(interconnector) => {
+                assert_eq!(&*interconnector.interconnector_id, "VIC1-NSW1");
+                assert_eq!(interconnector.mw_flow, Some(-412.3));
+                assert_eq!(interconnector.export_limit, Some(1050.4));
+                assert_eq!(interconnector.intervention, Some(0));
+            }
+            other => panic!("Expected INTERCONNECTORSOLN, got {}", other),
+        }
+    }
+}
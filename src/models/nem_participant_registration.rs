@@ -0,0 +1,567 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Read;
+use std::sync::Arc;
+
+use crate::common::intern::deserialize_interned;
+use crate::common::mms_csv::{DataRow, FromDataRow, MmsCsvReader, MmsRecords, TableKey};
+use crate::common::unit::UnitRow;
+use crate::common::unzip_process::{ProcessRecord, RecordsCollection};
+use crate::error::ScraperError;
+use crate::time::datetimezone_conversion::{
+    deserialize_optional_sydney_datetime_to_utc, deserialize_sydney_datetime_to_utc,
+};
+
+/// PARTICIPANT_REGISTRATION.DUDETAILSUMMARY: where a dispatchable unit sits and who runs it, for
+/// the period from `START_DATE` up to `END_DATE`.
+#[derive(Debug, Deserialize)]
+pub struct DuDetailSummaryData {
+    #[serde(rename = "ROW_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    csv_row_identifier: Arc<str>,
+    #[serde(rename = "FILE_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    category: Arc<str>,
+    #[serde(rename = "FILE_SUBTYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    report_type: Arc<str>,
+    #[serde(rename = "FILE_DESCRIPTOR")]
+    report_type_int: u32,
+    #[serde(rename = "DUID")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    duid: Arc<str>,
+    #[serde(rename = "START_DATE")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    start_date: DateTime<Utc>,
+    #[serde(rename = "END_DATE")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    end_date: DateTime<Utc>,
+    #[serde(rename = "DISPATCHTYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    dispatch_type: Arc<str>,
+    #[serde(rename = "CONNECTIONPOINTID")]
+    connection_point_id: Option<String>,
+    #[serde(rename = "REGIONID")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    region_id: Arc<str>,
+    #[serde(rename = "STATIONID")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    station_id: Arc<str>,
+    #[serde(rename = "PARTICIPANTID")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    participant_id: Arc<str>,
+    #[serde(rename = "LASTCHANGED")]
+    #[serde(deserialize_with = "deserialize_optional_sydney_datetime_to_utc")]
+    last_changed: Option<DateTime<Utc>>,
+    #[serde(rename = "TRANSMISSIONLOSSFACTOR")]
+    transmission_loss_factor: Option<f64>,
+    #[serde(rename = "STARTTYPE")]
+    start_type: Option<String>,
+    #[serde(rename = "DISTRIBUTIONLOSSFACTOR")]
+    distribution_loss_factor: Option<f64>,
+    #[serde(rename = "SCHEDULE_TYPE")]
+    schedule_type: Option<String>,
+    #[serde(rename = "IS_AGGREGATED")]
+    is_aggregated: Option<u32>,
+    #[serde(rename = "DISPATCHSUBTYPE")]
+    dispatch_sub_type: Option<String>,
+}
+
+impl fmt::Display for DuDetailSummaryData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "DuDetailSummaryData: {{ csv_row_identifier: {}, category: {}, report_type: {}, report_type_int: {}, duid: {:?}, start_date: {:?}, end_date: {:?}, dispatch_type: {:?}, connection_point_id: {:?}, region_id: {:?}, station_id: {:?}, participant_id: {:?}, last_changed: {:?}, transmission_loss_factor: {:?}, start_type: {:?}, distribution_loss_factor: {:?}, schedule_type: {:?}, is_aggregated: {:?}, dispatch_sub_type: {:?} }}",
+            self.csv_row_identifier,
+            self.category,
+            self.report_type,
+            self.report_type_int,
+            self.duid,
+            self.start_date,
+            self.end_date,
+            self.dispatch_type,
+            self.connection_point_id,
+            self.region_id,
+            self.station_id,
+            self.participant_id,
+            self.last_changed,
+            self.transmission_loss_factor,
+            self.start_type,
+            self.distribution_loss_factor,
+            self.schedule_type,
+            self.is_aggregated,
+            self.dispatch_sub_type
+        )
+    }
+}
+
+/// PARTICIPANT_REGISTRATION.DUDETAIL: registered and maximum capacity of a unit from
+/// `EFFECTIVEDATE`. Where several versions share an effective date the highest `VERSIONNO`
+/// applies.
+#[derive(Debug, Deserialize)]
+pub struct DuDetailData {
+    #[serde(rename = "ROW_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    csv_row_identifier: Arc<str>,
+    #[serde(rename = "FILE_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    category: Arc<str>,
+    #[serde(rename = "FILE_SUBTYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    report_type: Arc<str>,
+    #[serde(rename = "FILE_DESCRIPTOR")]
+    report_type_int: u32,
+    #[serde(rename = "EFFECTIVEDATE")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    effective_date: DateTime<Utc>,
+    #[serde(rename = "DUID")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    duid: Arc<str>,
+    #[serde(rename = "VERSIONNO")]
+    version_no: Option<u64>,
+    #[serde(rename = "REGISTEREDCAPACITY")]
+    registered_capacity: Option<f64>,
+    #[serde(rename = "MAXCAPACITY")]
+    max_capacity: Option<f64>,
+    #[serde(rename = "DISPATCHTYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    dispatch_type: Arc<str>,
+    #[serde(rename = "INTERMITTENTFLAG")]
+    intermittent_flag: Option<String>,
+    #[serde(rename = "SEMISCHEDULE_FLAG")]
+    semi_schedule_flag: Option<String>,
+    #[serde(rename = "MAXSTORAGECAPACITY")]
+    max_storage_capacity: Option<f64>,
+    #[serde(rename = "LASTCHANGED")]
+    #[serde(deserialize_with = "deserialize_optional_sydney_datetime_to_utc")]
+    last_changed: Option<DateTime<Utc>>,
+}
+
+impl fmt::Display for DuDetailData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "DuDetailData: {{ csv_row_identifier: {}, category: {}, report_type: {}, report_type_int: {}, effective_date: {:?}, duid: {:?}, version_no: {:?}, registered_capacity: {:?}, max_capacity: {:?}, dispatch_type: {:?}, intermittent_flag: {:?}, semi_schedule_flag: {:?}, max_storage_capacity: {:?}, last_changed: {:?} }}",
+            self.csv_row_identifier,
+            self.category,
+            self.report_type,
+            self.report_type_int,
+            self.effective_date,
+            self.duid,
+            self.version_no,
+            self.registered_capacity,
+            self.max_capacity,
+            self.dispatch_type,
+            self.intermittent_flag,
+            self.semi_schedule_flag,
+            self.max_storage_capacity,
+            self.last_changed
+        )
+    }
+}
+
+/// PARTICIPANT_REGISTRATION.DUALLOC: the generating sets that make up a unit from
+/// `EFFECTIVEDATE`.
+#[derive(Debug, Deserialize)]
+pub struct DuAllocData {
+    #[serde(rename = "ROW_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    csv_row_identifier: Arc<str>,
+    #[serde(rename = "FILE_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    category: Arc<str>,
+    #[serde(rename = "FILE_SUBTYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    report_type: Arc<str>,
+    #[serde(rename = "FILE_DESCRIPTOR")]
+    report_type_int: u32,
+    #[serde(rename = "EFFECTIVEDATE")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    effective_date: DateTime<Utc>,
+    #[serde(rename = "VERSIONNO")]
+    version_no: Option<u64>,
+    #[serde(rename = "DUID")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    duid: Arc<str>,
+    #[serde(rename = "GENSETID")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    genset_id: Arc<str>,
+    #[serde(rename = "LASTCHANGED")]
+    #[serde(deserialize_with = "deserialize_optional_sydney_datetime_to_utc")]
+    last_changed: Option<DateTime<Utc>>,
+}
+
+impl fmt::Display for DuAllocData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "DuAllocData: {{ csv_row_identifier: {}, category: {}, report_type: {}, report_type_int: {}, effective_date: {:?}, version_no: {:?}, duid: {:?}, genset_id: {:?}, last_changed: {:?} }}",
+            self.csv_row_identifier,
+            self.category,
+            self.report_type,
+            self.report_type_int,
+            self.effective_date,
+            self.version_no,
+            self.duid,
+            self.genset_id,
+            self.last_changed
+        )
+    }
+}
+
+/// PARTICIPANT_REGISTRATION.GENUNITS: physical details of a generating set, including its
+/// energy source (`CO2E_ENERGY_SOURCE`, e.g. `Black coal` or `Wind`).
+#[derive(Debug, Deserialize)]
+pub struct GenUnitsData {
+    #[serde(rename = "ROW_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    csv_row_identifier: Arc<str>,
+    #[serde(rename = "FILE_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    category: Arc<str>,
+    #[serde(rename = "FILE_SUBTYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    report_type: Arc<str>,
+    #[serde(rename = "FILE_DESCRIPTOR")]
+    report_type_int: u32,
+    #[serde(rename = "GENSETID")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    genset_id: Arc<str>,
+    #[serde(rename = "STATIONID")]
+    station_id: Option<String>,
+    #[serde(rename = "REGISTEREDCAPACITY")]
+    registered_capacity: Option<f64>,
+    #[serde(rename = "MAXCAPACITY")]
+    max_capacity: Option<f64>,
+    #[serde(rename = "GENSETTYPE")]
+    genset_type: Option<String>,
+    #[serde(rename = "CO2E_EMISSIONS_FACTOR")]
+    co2e_emissions_factor: Option<f64>,
+    #[serde(rename = "CO2E_ENERGY_SOURCE")]
+    co2e_energy_source: Option<String>,
+    #[serde(rename = "LASTCHANGED")]
+    #[serde(deserialize_with = "deserialize_optional_sydney_datetime_to_utc")]
+    last_changed: Option<DateTime<Utc>>,
+}
+
+impl fmt::Display for GenUnitsData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "GenUnitsData: {{ csv_row_identifier: {}, category: {}, report_type: {}, report_type_int: {}, genset_id: {:?}, station_id: {:?}, registered_capacity: {:?}, max_capacity: {:?}, genset_type: {:?}, co2e_emissions_factor: {:?}, co2e_energy_source: {:?}, last_changed: {:?} }}",
+            self.csv_row_identifier,
+            self.category,
+            self.report_type,
+            self.report_type_int,
+            self.genset_id,
+            self.station_id,
+            self.registered_capacity,
+            self.max_capacity,
+            self.genset_type,
+            self.co2e_emissions_factor,
+            self.co2e_energy_source,
+            self.last_changed
+        )
+    }
+}
+
+#[derive(Debug)]
+pub enum RecordParticipantRegistration {
+    DuDetailSummary(DuDetailSummaryData),
+    DuDetail(DuDetailData),
+    DuAlloc(DuAllocData),
+    GenUnits(GenUnitsData),
+}
+
+impl fmt::Display for RecordParticipantRegistration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecordParticipantRegistration::DuDetailSummary(summary) => write!(f, "{}", summary),
+            RecordParticipantRegistration::DuDetail(detail) => write!(f, "{}", detail),
+            RecordParticipantRegistration::DuAlloc(allocation) => write!(f, "{}", allocation),
+            RecordParticipantRegistration::GenUnits(genset) => write!(f, "{}", genset),
+        }
+    }
+}
+
+impl ProcessRecord<RecordParticipantRegistration> for DuDetailSummaryData {
+    fn process(row: &DataRow) -> Result<RecordParticipantRegistration, ScraperError> {
+        let record = match row.version()? {
+            4..=6 => row.deserialize::<DuDetailSummaryData>()?,
+            version => return Err(row.unsupported_version(version)),
+        };
+        Ok(RecordParticipantRegistration::DuDetailSummary(record))
+    }
+}
+
+impl ProcessRecord<RecordParticipantRegistration> for DuDetailData {
+    fn process(row: &DataRow) -> Result<RecordParticipantRegistration, ScraperError> {
+        let record = match row.version()? {
+            3..=7 => row.deserialize::<DuDetailData>()?,
+            version => return Err(row.unsupported_version(version)),
+        };
+        Ok(RecordParticipantRegistration::DuDetail(record))
+    }
+}
+
+impl ProcessRecord<RecordParticipantRegistration> for DuAllocData {
+    fn process(row: &DataRow) -> Result<RecordParticipantRegistration, ScraperError> {
+        let record = match row.version()? {
+            1 => row.deserialize::<DuAllocData>()?,
+            version => return Err(row.unsupported_version(version)),
+        };
+        Ok(RecordParticipantRegistration::DuAlloc(record))
+    }
+}
+
+impl ProcessRecord<RecordParticipantRegistration> for GenUnitsData {
+    fn process(row: &DataRow) -> Result<RecordParticipantRegistration, ScraperError> {
+        let record = match row.version()? {
+            1 | 2 => row.deserialize::<GenUnitsData>()?,
+            version => return Err(row.unsupported_version(version)),
+        };
+        Ok(RecordParticipantRegistration::GenUnits(record))
+    }
+}
+
+impl FromDataRow for RecordParticipantRegistration {
+    fn from_data_row(row: &DataRow) -> Result<Self, ScraperError> {
+        if row.table.is("PARTICIPANT_REGISTRATION", "DUDETAILSUMMARY") {
+            DuDetailSummaryData::process(row)
+        } else if row.table.is("PARTICIPANT_REGISTRATION", "DUDETAIL") {
+            DuDetailData::process(row)
+        } else if row.table.is("PARTICIPANT_REGISTRATION", "DUALLOC") {
+            DuAllocData::process(row)
+        } else if row.table.is("PARTICIPANT_REGISTRATION", "GENUNITS") {
+            GenUnitsData::process(row)
+        } else {
+            Err(row.unknown_table())
+        }
+    }
+
+    // The package also covers participants, stations, loss factors and bank accounts
+    fn reads_table(table: &TableKey) -> bool {
+        table.is("PARTICIPANT_REGISTRATION", "DUDETAILSUMMARY")
+            || table.is("PARTICIPANT_REGISTRATION", "DUDETAIL")
+            || table.is("PARTICIPANT_REGISTRATION", "DUALLOC")
+            || table.is("PARTICIPANT_REGISTRATION", "GENUNITS")
+    }
+}
+
+/// Streams participant registration records one row at a time.
+pub fn stream_file_participant_registration<R: Read>(
+    reader: R,
+) -> MmsRecords<R, RecordParticipantRegistration> {
+    MmsCsvReader::new(reader).records()
+}
+
+pub fn process_file_participant_registration<R: Read>(
+    reader: R,
+) -> Result<RecordsCollection<RecordParticipantRegistration>, ScraperError> {
+    RecordsCollection::from_records(stream_file_participant_registration(reader))
+}
+
+/// A unit's registration as it stood at one moment, combined from the registration tables.
+/// Capacity and energy source are `None` when DUDETAIL or DUALLOC/GENUNITS were not loaded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnitRegistration {
+    pub duid: Arc<str>,
+    pub station_id: Arc<str>,
+    pub region_id: Arc<str>,
+    pub participant_id: Arc<str>,
+    /// `GENERATOR`, `LOAD` or `BIDIRECTIONAL`.
+    pub dispatch_type: Arc<str>,
+    pub schedule_type: Option<String>,
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+    pub registered_capacity: Option<f64>,
+    pub max_capacity: Option<f64>,
+    pub energy_source: Option<String>,
+}
+
+/// Effective-dated registration reference data, for looking up the station, region, capacity
+/// and fuel of the unit behind a DUID.
+///
+/// MMS publishes each registration table as its own file, so append the collections of all
+/// of them before building the registry.
+#[derive(Debug, Default)]
+pub struct UnitRegistry {
+    summaries: HashMap<Arc<str>, Vec<DuDetailSummaryData>>,
+    details: HashMap<Arc<str>, Vec<DuDetailData>>,
+    allocations: HashMap<Arc<str>, Vec<DuAllocData>>,
+    gensets: HashMap<Arc<str>, GenUnitsData>,
+}
+
+impl UnitRegistry {
+    pub fn from_records<I>(records: I) -> Self
+    where
+        I: IntoIterator<Item = RecordParticipantRegistration>,
+    {
+        let mut registry = UnitRegistry::default();
+        for record in records {
+            match record {
+                RecordParticipantRegistration::DuDetailSummary(summary) => registry
+                    .summaries
+                    .entry(Arc::clone(&summary.duid))
+                    .or_default()
+                    .push(summary),
+                RecordParticipantRegistration::DuDetail(detail) => registry
+                    .details
+                    .entry(Arc::clone(&detail.duid))
+                    .or_default()
+                    .push(detail),
+                RecordParticipantRegistration::DuAlloc(allocation) => registry
+                    .allocations
+                    .entry(Arc::clone(&allocation.duid))
+                    .or_default()
+                    .push(allocation),
+                RecordParticipantRegistration::GenUnits(genset) => {
+                    registry
+                        .gensets
+                        .insert(Arc::clone(&genset.genset_id), genset);
+                }
+            }
+        }
+        for summaries in registry.summaries.values_mut() {
+            summaries.sort_by_key(|summary| summary.start_date);
+        }
+        for details in registry.details.values_mut() {
+            details.sort_by_key(|detail| (detail.effective_date, detail.version_no));
+        }
+        for allocations in registry.allocations.values_mut() {
+            allocations
+                .sort_by_key(|allocation| (allocation.effective_date, allocation.version_no));
+        }
+        registry
+    }
+
+    /// Number of DUIDs with a DUDETAILSUMMARY entry.
+    pub fn len(&self) -> usize {
+        self.summaries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.summaries.is_empty()
+    }
+
+    pub fn duids(&self) -> impl Iterator<Item = &str> {
+        self.summaries.keys().map(|duid| &**duid)
+    }
+
+    /// The registration of `duid` in effect at `at`, i.e. the DUDETAILSUMMARY period with
+    /// `START_DATE <= at < END_DATE` and the latest DUDETAIL and DUALLOC effective by then.
+    pub fn get(&self, duid: &str, at: DateTime<Utc>) -> Option<UnitRegistration> {
+        let summary = self
+            .summaries
+            .get(duid)?
+            .iter()
+            .rfind(|summary| summary.start_date <= at && at < summary.end_date)?;
+        let detail = self
+            .details
+            .get(duid)
+            .and_then(|details| details.iter().rfind(|detail| detail.effective_date <= at));
+        let energy_source = self.energy_source(duid, at);
+
+        Some(UnitRegistration {
+            duid: Arc::clone(&summary.duid),
+            station_id: Arc::clone(&summary.station_id),
+            region_id: Arc::clone(&summary.region_id),
+            participant_id: Arc::clone(&summary.participant_id),
+            dispatch_type: Arc::clone(&summary.dispatch_type),
+            schedule_type: summary.schedule_type.clone(),
+            start_date: summary.start_date,
+            end_date: summary.end_date,
+            registered_capacity: detail.and_then(|detail| detail.registered_capacity),
+            max_capacity: detail.and_then(|detail| detail.max_capacity),
+            energy_source,
+        })
+    }
+
+    /// The registration of the unit a report row belongs to, at the time the row applies to.
+    pub fn lookup<R: UnitRow>(&self, row: &R) -> Option<UnitRegistration> {
+        self.get(row.duid(), row.settlement_datetime())
+    }
+
+    // A unit can be allocated several generating sets; they share a fuel in practice, so the
+    // first one with an energy source is used.
+    fn energy_source(&self, duid: &str, at: DateTime<Utc>) -> Option<String> {
+        let allocations = self.allocations.get(duid)?;
+        let current = allocations
+            .iter()
+            .rfind(|allocation| allocation.effective_date <= at)?;
+        allocations
+            .iter()
+            .filter(|allocation| {
+                (allocation.effective_date, allocation.version_no)
+                    == (current.effective_date, current.version_no)
+            })
+            .filter_map(|allocation| self.gensets.get(&allocation.genset_id))
+            .find_map(|genset| genset.co2e_energy_source.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::unzip_process::unzip_and_process;
+    use crate::models::nem_dispatch_scada::{process_file_dispatch_scada, RecordDispatchScada};
+    use chrono::TimeZone;
+
+    const REGISTRATION: &str = "C,NEMP.WORLD,PARTICIPANT_REGISTRATION,AEMO,PUBLIC,2024/03/01,04:30:00,0000000412550018,PARTICIPANT_REGISTRATION,0000000412550017\n\
+        I,PARTICIPANT_REGISTRATION,DUDETAILSUMMARY,5,DUID,START_DATE,END_DATE,DISPATCHTYPE,CONNECTIONPOINTID,REGIONID,STATIONID,PARTICIPANTID,LASTCHANGED,TRANSMISSIONLOSSFACTOR,STARTTYPE,DISTRIBUTIONLOSSFACTOR,MINIMUM_ENERGY_PRICE,MAXIMUM_ENERGY_PRICE,SCHEDULE_TYPE,MIN_RAMP_RATE_UP,MIN_RAMP_RATE_DOWN,MAX_RAMP_RATE_UP,MAX_RAMP_RATE_DOWN,IS_AGGREGATED\n\
+        D,PARTICIPANT_REGISTRATION,DUDETAILSUMMARY,5,BAYSW1,\"2022/07/01 00:00:00\",\"2023/07/01 00:00:00\",GENERATOR,NBAY1,NSW1,BAYSWATER,MACQGEN,\"2022/06/28 11:02:13\",0.9688,NOTSELFCOMMIT,1,,,SCHEDULED,,,,,0\n\
+        D,PARTICIPANT_REGISTRATION,DUDETAILSUMMARY,5,BAYSW1,\"2023/07/01 00:00:00\",\"2999/12/31 00:00:00\",GENERATOR,NBAY1,NSW1,BAYSWATER,AGLMACQ,\"2023/06/29 09:41:50\",0.9701,NOTSELFCOMMIT,1,,,SCHEDULED,,,,,0\n\
+        D,PARTICIPANT_REGISTRATION,DUDETAILSUMMARY,5,ARWF1,\"2021/10/01 00:00:00\",\"2999/12/31 00:00:00\",GENERATOR,VARW1A,VIC1,ARARATWF,ARWFPL,\"2021/09/30 15:12:44\",0.9602,NOTSELFCOMMIT,1,,,SEMI-SCHEDULED,,,,,0\n\
+        I,PARTICIPANT_REGISTRATION,DUDETAIL,7,EFFECTIVEDATE,DUID,VERSIONNO,CONNECTIONPOINTID,VOLTLEVEL,REGISTEREDCAPACITY,AGCCAPABILITY,DISPATCHTYPE,MAXCAPACITY,STARTTYPE,NORMALLYONFLAG,PHYSICALDETAILSFLAG,SPINNINGRESERVEFLAG,AUTHORISEDBY,AUTHORISEDDATE,LASTCHANGED,INTERMITTENTFLAG,SEMISCHEDULE_FLAG,MAXRATEOFCHANGEUP,MAXRATEOFCHANGEDOWN,DISPATCHSUBTYPE,ADG_ID,MINCAPACITY,REGISTEREDMINCAPACITY,MAXSTORAGECAPACITY\n\
+        D,PARTICIPANT_REGISTRATION,DUDETAIL,7,\"2020/01/01 00:00:00\",BAYSW1,1,NBAY1,330,660,Y,GENERATOR,685,NOTSELFCOMMIT,,,,,,\"2019/12/20 10:00:00\",N,N,,,,,,,\n\
+        D,PARTICIPANT_REGISTRATION,DUDETAIL,7,\"2020/01/01 00:00:00\",BAYSW1,2,NBAY1,330,660,Y,GENERATOR,700,NOTSELFCOMMIT,,,,,,\"2019/12/21 10:00:00\",N,N,,,,,,,\n\
+        D,PARTICIPANT_REGISTRATION,DUDETAIL,7,\"2025/01/01 00:00:00\",BAYSW1,1,NBAY1,330,685,Y,GENERATOR,710,NOTSELFCOMMIT,,,,,,\"2024/12/20 10:00:00\",N,N,,,,,,,\n\
+        I,PARTICIPANT_REGISTRATION,DUALLOC,1,EFFECTIVEDATE,VERSIONNO,DUID,GENSETID,LASTCHANGED\n\
+        D,PARTICIPANT_REGISTRATION,DUALLOC,1,\"2020/01/01 00:00:00\",1,BAYSW1,BW01,\"2019/12/20 10:00:00\"\n\
+        I,PARTICIPANT_REGISTRATION,GENUNITS,2,GENSETID,STATIONID,SETLOSSFACTOR,CDINDICATOR,AGCFLAG,SPINNINGFLAG,VOLTLEVEL,REGISTEREDCAPACITY,DISPATCHTYPE,STARTTYPE,MKTGENERATORIND,NORMALSTATUS,MAXCAPACITY,GENSETTYPE,GENSETNAME,LASTCHANGED,CO2E_EMISSIONS_FACTOR,CO2E_ENERGY_SOURCE,CO2E_DATA_SOURCE,MINCAPACITY,REGISTEREDMINCAPACITY,MAXSTORAGECAPACITY\n\
+        D,PARTICIPANT_REGISTRATION,GENUNITS,2,BW01,BAYSWATER,,,,,330,660,GENERATOR,NOTSELFCOMMIT,Y,ON,685,,,\"2019/12/20 10:00:00\",0.9,\"Black coal\",NGA 2023,,,\n\
+        C,\"END OF REPORT\",14\n";
+
+    fn registry() -> UnitRegistry {
+        let collection = process_file_participant_registration(REGISTRATION.as_bytes())
+            .expect("Failed to parse");
+        UnitRegistry::from_records(collection.records)
+    }
+
+    #[test]
+    fn test_unit_registry_is_effective_dated() {
+        let registry = registry();
+        assert_eq!(registry.len(), 2);
+
+        let at = |year| Utc.with_ymd_and_hms(year, 3, 1, 0, 0, 0).unwrap();
+        let earlier = registry.get("BAYSW1", at(2023)).expect("No registration");
+        assert_eq!(&*earlier.participant_id, "MACQGEN");
+        assert_eq!(earlier.max_capacity, Some(700.0));
+
+        let later = registry.get("BAYSW1", at(2025)).expect("No registration");
+        assert_eq!(&*later.participant_id, "AGLMACQ");
+        assert_eq!(&*later.station_id, "BAYSWATER");
+        assert_eq!(later.registered_capacity, Some(685.0));
+        assert_eq!(later.energy_source.as_deref(), Some("Black coal"));
+
+        assert!(registry.get("BAYSW1", at(2021)).is_none());
+        assert!(registry.get("NOTAUNIT", at(2025)).is_none());
+    }
+
+    #[test]
+    fn test_unit_registry_enriches_scada() {
+        let registry = registry();
+        let scada = unzip_and_process(
+            "src/fixtures/PUBLIC_DISPATCHSCADA_202403031335_0000000412683170.zip",
+            |reader| process_file_dispatch_scada(reader),
+        )
+        .expect("Failed to process SCADA fixture");
+
+        let regions: Vec<(&str, Arc<str>)> = scada
+            .records
+            .iter()
+            .filter_map(|RecordDispatchScada::UnitScada(unit)| {
+                let registration = registry.lookup(unit)?;
+                Some((unit.duid(), registration.region_id))
+            })
+            .collect();
+        assert_eq!(
+            regions,
+            [("ARWF1", Arc::from("VIC1")), ("BAYSW1", Arc::from("NSW1"))]
+        );
+    }
+}
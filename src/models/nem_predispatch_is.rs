@@ -0,0 +1,505 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::fmt;
+use std::io::Read;
+use std::sync::Arc;
+
+use crate::common::forecast::ForecastRow;
+use crate::common::intern::deserialize_interned;
+use crate::common::mms_csv::{DataRow, FromDataRow, MmsCsvReader, MmsRecords, TableKey};
+use crate::common::unzip_process::{ProcessRecord, RecordsCollection};
+use crate::error::ScraperError;
+use crate::time::datetimezone_conversion::{
+    deserialize_predispatch_seq_no, deserialize_sydney_datetime_to_utc,
+};
+
+// Each PREDISPATCHIS file is one run. The run time comes from PREDISPATCHSEQNO and the target
+// half hour (its end) from DATETIME.
+
+/// PREDISPATCH.REGION_PRICES: the forecast energy and FCAS prices for a region and half hour.
+#[derive(Debug, Deserialize)]
+pub struct RegionPricesData {
+    #[serde(rename = "ROW_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    csv_row_identifier: Arc<str>,
+    #[serde(rename = "FILE_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    category: Arc<str>,
+    #[serde(rename = "FILE_SUBTYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    report_type: Arc<str>,
+    #[serde(rename = "FILE_DESCRIPTOR")]
+    report_type_int: u32,
+    #[serde(rename = "PREDISPATCHSEQNO")]
+    #[serde(deserialize_with = "deserialize_predispatch_seq_no")]
+    run_datetime: DateTime<Utc>,
+    #[serde(rename = "RUNNO")]
+    run_no: Option<u32>,
+    #[serde(rename = "REGIONID")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    region_id: Arc<str>,
+    #[serde(rename = "PERIODID")]
+    period_id: String,
+    #[serde(rename = "INTERVENTION")]
+    intervention: Option<u32>,
+    #[serde(rename = "RRP")]
+    rrp: Option<f64>,
+    #[serde(rename = "EEP")]
+    eep: Option<f64>,
+    #[serde(rename = "LASTCHANGED")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    last_changed: DateTime<Utc>,
+    #[serde(rename = "DATETIME")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    interval_datetime: DateTime<Utc>,
+    #[serde(rename = "RAISE6SECRRP")]
+    raise_6_sec_rrp: Option<f64>,
+    #[serde(rename = "RAISE60SECRRP")]
+    raise_60_sec_rrp: Option<f64>,
+    #[serde(rename = "RAISE5MINRRP")]
+    raise_5_min_rrp: Option<f64>,
+    #[serde(rename = "RAISEREGRRP")]
+    raise_reg_rrp: Option<f64>,
+    #[serde(rename = "LOWER6SECRRP")]
+    lower_6_sec_rrp: Option<f64>,
+    #[serde(rename = "LOWER60SECRRP")]
+    lower_60_sec_rrp: Option<f64>,
+    #[serde(rename = "LOWER5MINRRP")]
+    lower_5_min_rrp: Option<f64>,
+    #[serde(rename = "LOWERREGRRP")]
+    lower_reg_rrp: Option<f64>,
+    #[serde(rename = "RAISE1SECRRP")]
+    raise_1_sec_rrp: Option<f64>,
+    #[serde(rename = "LOWER1SECRRP")]
+    lower_1_sec_rrp: Option<f64>,
+}
+
+impl fmt::Display for RegionPricesData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "RegionPricesData: {{ csv_row_identifier: {}, category: {}, report_type: {}, report_type_int: {}, run_datetime: {:?}, run_no: {:?}, region_id: {:?}, period_id: {:?}, intervention: {:?}, rrp: {:?}, eep: {:?}, last_changed: {:?}, interval_datetime: {:?}, raise_6_sec_rrp: {:?}, raise_60_sec_rrp: {:?}, raise_5_min_rrp: {:?}, raise_reg_rrp: {:?}, lower_6_sec_rrp: {:?}, lower_60_sec_rrp: {:?}, lower_5_min_rrp: {:?}, lower_reg_rrp: {:?}, raise_1_sec_rrp: {:?}, lower_1_sec_rrp: {:?} }}",
+            self.csv_row_identifier,
+            self.category,
+            self.report_type,
+            self.report_type_int,
+            self.run_datetime,
+            self.run_no,
+            self.region_id,
+            self.period_id,
+            self.intervention,
+            self.rrp,
+            self.eep,
+            self.last_changed,
+            self.interval_datetime,
+            self.raise_6_sec_rrp,
+            self.raise_60_sec_rrp,
+            self.raise_5_min_rrp,
+            self.raise_reg_rrp,
+            self.lower_6_sec_rrp,
+            self.lower_60_sec_rrp,
+            self.lower_5_min_rrp,
+            self.lower_reg_rrp,
+            self.raise_1_sec_rrp,
+            self.lower_1_sec_rrp
+        )
+    }
+}
+
+/// PREDISPATCH.REGION_SOLUTION: forecast demand, supply and interchange for a region and half
+/// hour.
+#[derive(Debug, Deserialize)]
+pub struct RegionSolutionData {
+    #[serde(rename = "ROW_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    csv_row_identifier: Arc<str>,
+    #[serde(rename = "FILE_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    category: Arc<str>,
+    #[serde(rename = "FILE_SUBTYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    report_type: Arc<str>,
+    #[serde(rename = "FILE_DESCRIPTOR")]
+    report_type_int: u32,
+    #[serde(rename = "PREDISPATCHSEQNO")]
+    #[serde(deserialize_with = "deserialize_predispatch_seq_no")]
+    run_datetime: DateTime<Utc>,
+    #[serde(rename = "RUNNO")]
+    run_no: Option<u32>,
+    #[serde(rename = "REGIONID")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    region_id: Arc<str>,
+    #[serde(rename = "PERIODID")]
+    period_id: String,
+    #[serde(rename = "INTERVENTION")]
+    intervention: Option<u32>,
+    #[serde(rename = "TOTALDEMAND")]
+    total_demand: Option<f64>,
+    #[serde(rename = "AVAILABLEGENERATION")]
+    available_generation: Option<f64>,
+    #[serde(rename = "AVAILABLELOAD")]
+    available_load: Option<f64>,
+    #[serde(rename = "DEMANDFORECAST")]
+    demand_forecast: Option<f64>,
+    #[serde(rename = "DISPATCHABLEGENERATION")]
+    dispatchable_generation: Option<f64>,
+    #[serde(rename = "DISPATCHABLELOAD")]
+    dispatchable_load: Option<f64>,
+    #[serde(rename = "NETINTERCHANGE")]
+    net_interchange: Option<f64>,
+    #[serde(rename = "EXCESSGENERATION")]
+    excess_generation: Option<f64>,
+    #[serde(rename = "LASTCHANGED")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    last_changed: DateTime<Utc>,
+    #[serde(rename = "DATETIME")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    interval_datetime: DateTime<Utc>,
+    #[serde(rename = "INITIALSUPPLY")]
+    initial_supply: Option<f64>,
+    #[serde(rename = "CLEAREDSUPPLY")]
+    cleared_supply: Option<f64>,
+    #[serde(rename = "UIGF")]
+    uigf: Option<f64>,
+    #[serde(rename = "SS_SOLAR_UIGF")]
+    ss_solar_uigf: Option<f64>,
+    #[serde(rename = "SS_WIND_UIGF")]
+    ss_wind_uigf: Option<f64>,
+}
+
+impl fmt::Display for RegionSolutionData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "RegionSolutionData: {{ csv_row_identifier: {}, category: {}, report_type: {}, report_type_int: {}, run_datetime: {:?}, run_no: {:?}, region_id: {:?}, period_id: {:?}, intervention: {:?}, total_demand: {:?}, available_generation: {:?}, available_load: {:?}, demand_forecast: {:?}, dispatchable_generation: {:?}, dispatchable_load: {:?}, net_interchange: {:?}, excess_generation: {:?}, last_changed: {:?}, interval_datetime: {:?}, initial_supply: {:?}, cleared_supply: {:?}, uigf: {:?}, ss_solar_uigf: {:?}, ss_wind_uigf: {:?} }}",
+            self.csv_row_identifier,
+            self.category,
+            self.report_type,
+            self.report_type_int,
+            self.run_datetime,
+            self.run_no,
+            self.region_id,
+            self.period_id,
+            self.intervention,
+            self.total_demand,
+            self.available_generation,
+            self.available_load,
+            self.demand_forecast,
+            self.dispatchable_generation,
+            self.dispatchable_load,
+            self.net_interchange,
+            self.excess_generation,
+            self.last_changed,
+            self.interval_datetime,
+            self.initial_supply,
+            self.cleared_supply,
+            self.uigf,
+            self.ss_solar_uigf,
+            self.ss_wind_uigf
+        )
+    }
+}
+
+/// PREDISPATCH.INTERCONNECTOR_SOLN: forecast flow and limits for an interconnector and half hour.
+#[derive(Debug, Deserialize)]
+pub struct InterconnectorSolutionData {
+    #[serde(rename = "ROW_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    csv_row_identifier: Arc<str>,
+    #[serde(rename = "FILE_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    category: Arc<str>,
+    #[serde(rename = "FILE_SUBTYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    report_type: Arc<str>,
+    #[serde(rename = "FILE_DESCRIPTOR")]
+    report_type_int: u32,
+    #[serde(rename = "PREDISPATCHSEQNO")]
+    #[serde(deserialize_with = "deserialize_predispatch_seq_no")]
+    run_datetime: DateTime<Utc>,
+    #[serde(rename = "RUNNO")]
+    run_no: Option<u32>,
+    #[serde(rename = "INTERCONNECTORID")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    interconnector_id: Arc<str>,
+    #[serde(rename = "PERIODID")]
+    period_id: String,
+    #[serde(rename = "INTERVENTION")]
+    intervention: Option<u32>,
+    #[serde(rename = "METEREDMWFLOW")]
+    metered_mw_flow: Option<f64>,
+    #[serde(rename = "MWFLOW")]
+    mw_flow: Option<f64>,
+    #[serde(rename = "MWLOSSES")]
+    mw_losses: Option<f64>,
+    #[serde(rename = "MARGINALVALUE")]
+    marginal_value: Option<f64>,
+    #[serde(rename = "VIOLATIONDEGREE")]
+    violation_degree: Option<f64>,
+    #[serde(rename = "LASTCHANGED")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    last_changed: DateTime<Utc>,
+    #[serde(rename = "DATETIME")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    interval_datetime: DateTime<Utc>,
+    #[serde(rename = "EXPORTLIMIT")]
+    export_limit: Option<f64>,
+    #[serde(rename = "IMPORTLIMIT")]
+    import_limit: Option<f64>,
+    #[serde(rename = "MARGINALLOSS")]
+    marginal_loss: Option<f64>,
+    #[serde(rename = "EXPORTGENCONID")]
+    export_gen_con_id: Option<String>,
+    #[serde(rename = "IMPORTGENCONID")]
+    import_gen_con_id: Option<String>,
+    #[serde(rename = "FCASEXPORTLIMIT")]
+    fcas_export_limit: Option<f64>,
+    #[serde(rename = "FCASIMPORTLIMIT")]
+    fcas_import_limit: Option<f64>,
+}
+
+impl fmt::Display for InterconnectorSolutionData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "InterconnectorSolutionData: {{ csv_row_identifier: {}, category: {}, report_type: {}, report_type_int: {}, run_datetime: {:?}, run_no: {:?}, interconnector_id: {:?}, period_id: {:?}, intervention: {:?}, metered_mw_flow: {:?}, mw_flow: {:?}, mw_losses: {:?}, marginal_value: {:?}, violation_degree: {:?}, last_changed: {:?}, interval_datetime: {:?}, export_limit: {:?}, import_limit: {:?}, marginal_loss: {:?}, export_gen_con_id: {:?}, import_gen_con_id: {:?}, fcas_export_limit: {:?}, fcas_import_limit: {:?} }}",
+            self.csv_row_identifier,
+            self.category,
+            self.report_type,
+            self.report_type_int,
+            self.run_datetime,
+            self.run_no,
+            self.interconnector_id,
+            self.period_id,
+            self.intervention,
+            self.metered_mw_flow,
+            self.mw_flow,
+            self.mw_losses,
+            self.marginal_value,
+            self.violation_degree,
+            self.last_changed,
+            self.interval_datetime,
+            self.export_limit,
+            self.import_limit,
+            self.marginal_loss,
+            self.export_gen_con_id,
+            self.import_gen_con_id,
+            self.fcas_export_limit,
+            self.fcas_import_limit
+        )
+    }
+}
+
+impl ForecastRow for RegionPricesData {
+    fn run_datetime(&self) -> DateTime<Utc> {
+        self.run_datetime
+    }
+
+    fn interval_datetime(&self) -> DateTime<Utc> {
+        self.interval_datetime
+    }
+}
+
+impl ForecastRow for RegionSolutionData {
+    fn run_datetime(&self) -> DateTime<Utc> {
+        self.run_datetime
+    }
+
+    fn interval_datetime(&self) -> DateTime<Utc> {
+        self.interval_datetime
+    }
+}
+
+impl ForecastRow for InterconnectorSolutionData {
+    fn run_datetime(&self) -> DateTime<Utc> {
+        self.run_datetime
+    }
+
+    fn interval_datetime(&self) -> DateTime<Utc> {
+        self.interval_datetime
+    }
+}
+
+#[derive(Debug)]
+pub enum RecordPredispatchIs {
+    RegionPrices(RegionPricesData),
+    RegionSolution(RegionSolutionData),
+    InterconnectorSolution(InterconnectorSolutionData),
+}
+
+impl fmt::Display for RecordPredispatchIs {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecordPredispatchIs::RegionPrices(region_prices) => write!(f, "{}", region_prices),
+            RecordPredispatchIs::RegionSolution(region_solution) => {
+                write!(f, "{}", region_solution)
+            }
+            RecordPredispatchIs::InterconnectorSolution(interconnector_solution) => {
+                write!(f, "{}", interconnector_solution)
+            }
+        }
+    }
+}
+
+impl ProcessRecord<RecordPredispatchIs> for RegionPricesData {
+    fn process(row: &DataRow) -> Result<RecordPredispatchIs, ScraperError> {
+        let record = match row.version()? {
+            1 => row.deserialize::<RegionPricesData>()?,
+            version => return Err(row.unsupported_version(version)),
+        };
+        Ok(RecordPredispatchIs::RegionPrices(record))
+    }
+}
+
+impl ProcessRecord<RecordPredispatchIs> for RegionSolutionData {
+    fn process(row: &DataRow) -> Result<RecordPredispatchIs, ScraperError> {
+        let record = match row.version()? {
+            9 => row.deserialize::<RegionSolutionData>()?,
+            version => return Err(row.unsupported_version(version)),
+        };
+        Ok(RecordPredispatchIs::RegionSolution(record))
+    }
+}
+
+impl ProcessRecord<RecordPredispatchIs> for InterconnectorSolutionData {
+    fn process(row: &DataRow) -> Result<RecordPredispatchIs, ScraperError> {
+        let record = match row.version()? {
+            3 => row.deserialize::<InterconnectorSolutionData>()?,
+            version => return Err(row.unsupported_version(version)),
+        };
+        Ok(RecordPredispatchIs::InterconnectorSolution(record))
+    }
+}
+
+impl FromDataRow for RecordPredispatchIs {
+    fn from_data_row(row: &DataRow) -> Result<Self, ScraperError> {
+        if row.table.is("PREDISPATCH", "REGION_PRICES") {
+            RegionPricesData::process(row)
+        } else if row.table.is("PREDISPATCH", "REGION_SOLUTION") {
+            RegionSolutionData::process(row)
+        } else if row.table.is("PREDISPATCH", "INTERCONNECTOR_SOLN") {
+            InterconnectorSolutionData::process(row)
+        } else {
+            Err(row.unknown_table())
+        }
+    }
+
+    // PREDISPATCHIS also carries case, constraint, sensitivity and FCAS requirement tables
+    fn reads_table(table: &TableKey) -> bool {
+        table.is("PREDISPATCH", "REGION_PRICES")
+            || table.is("PREDISPATCH", "REGION_SOLUTION")
+            || table.is("PREDISPATCH", "INTERCONNECTOR_SOLN")
+    }
+}
+
+/// Streams PREDISPATCHIS records one row at a time.
+pub fn stream_file_predispatch_is<R: Read>(reader: R) -> MmsRecords<R, RecordPredispatchIs> {
+    MmsCsvReader::new(reader).records()
+}
+
+pub fn process_file_predispatch_is<R: Read>(
+    reader: R,
+) -> Result<RecordsCollection<RecordPredispatchIs>, ScraperError> {
+    RecordsCollection::from_records(stream_file_predispatch_is(reader))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::forecast::latest_as_of;
+    use crate::time::datetimezone_conversion::parse_sydney_datetime;
+
+    const PREDISPATCH_IS_1900: &str = "C,NEMP.WORLD,PREDISPATCHIS,AEMO,PUBLIC,2024/03/03,19:00:41,0000000412730001,PREDISPATCHIS,0000000412700000\n\
+        I,PREDISPATCH,REGION_PRICES,1,PREDISPATCHSEQNO,RUNNO,REGIONID,PERIODID,INTERVENTION,RRP,EEP,RRP1,EEP1,LASTCHANGED,DATETIME,RAISE6SECRRP,RAISE60SECRRP,RAISE5MINRRP,RAISEREGRRP,LOWER6SECRRP,LOWER60SECRRP,LOWER5MINRRP,LOWERREGRRP,RAISE1SECRRP,LOWER1SECRRP\n\
+        D,PREDISPATCH,REGION_PRICES,1,2024030330,1,NSW1,2024030332,0,95.1,0,0,0,\"2024/03/03 19:00:00\",\"2024/03/03 20:00:00\",0.39,0.39,0.38,3.25,0.01,0.01,0.01,0.72,0.1,0.05\n\
+        D,PREDISPATCH,REGION_PRICES,1,2024030330,1,NSW1,2024030333,0,88.2,0,0,0,\"2024/03/03 19:00:00\",\"2024/03/03 20:30:00\",0.39,0.39,0.38,3.25,0.01,0.01,0.01,0.72,0.1,0.05\n\
+        I,PREDISPATCH,REGION_SOLUTION,9,PREDISPATCHSEQNO,RUNNO,REGIONID,PERIODID,INTERVENTION,TOTALDEMAND,AVAILABLEGENERATION,AVAILABLELOAD,DEMANDFORECAST,DISPATCHABLEGENERATION,DISPATCHABLELOAD,NETINTERCHANGE,EXCESSGENERATION,LASTCHANGED,DATETIME,INITIALSUPPLY,CLEAREDSUPPLY,UIGF,SS_SOLAR_UIGF,SS_WIND_UIGF\n\
+        D,PREDISPATCH,REGION_SOLUTION,9,2024030330,1,NSW1,2024030332,0,8721.5,12714.4,194,-12.3,8990.1,0,268.6,0,\"2024/03/03 19:00:00\",\"2024/03/03 20:00:00\",8700.2,8721.5,1140.2,310.5,829.7\n\
+        I,PREDISPATCH,INTERCONNECTOR_SOLN,3,PREDISPATCHSEQNO,RUNNO,INTERCONNECTORID,PERIODID,INTERVENTION,METEREDMWFLOW,MWFLOW,MWLOSSES,MARGINALVALUE,VIOLATIONDEGREE,LASTCHANGED,DATETIME,EXPORTLIMIT,IMPORTLIMIT,MARGINALLOSS,EXPORTGENCONID,IMPORTGENCONID,FCASEXPORTLIMIT,FCASIMPORTLIMIT\n\
+        D,PREDISPATCH,INTERCONNECTOR_SOLN,3,2024030330,1,NSW1-QLD1,2024030332,0,0,119,6.89,0,0,\"2024/03/03 19:00:00\",\"2024/03/03 20:00:00\",582.65,-912.16,1.07457,N^^Q_NIL_KPP_1,Q^^N_NIL_SRAR,2204,-2478\n\
+        I,PREDISPATCH,CASE_SOLUTION,1,PREDISPATCHSEQNO,RUNNO,SOLUTIONSTATUS\n\
+        D,PREDISPATCH,CASE_SOLUTION,1,2024030330,1,0\n\
+        C,\"END OF REPORT\",11\n";
+
+    const PREDISPATCH_IS_1930: &str = "C,NEMP.WORLD,PREDISPATCHIS,AEMO,PUBLIC,2024/03/03,19:30:39,0000000412731001,PREDISPATCHIS,0000000412700000\n\
+        I,PREDISPATCH,REGION_PRICES,1,PREDISPATCHSEQNO,RUNNO,REGIONID,PERIODID,INTERVENTION,RRP,EEP,RRP1,EEP1,LASTCHANGED,DATETIME,RAISE6SECRRP,RAISE60SECRRP,RAISE5MINRRP,RAISEREGRRP,LOWER6SECRRP,LOWER60SECRRP,LOWER5MINRRP,LOWERREGRRP,RAISE1SECRRP,LOWER1SECRRP\n\
+        D,PREDISPATCH,REGION_PRICES,1,2024030331,1,NSW1,2024030332,0,102.5,0,0,0,\"2024/03/03 19:30:00\",\"2024/03/03 20:00:00\",0.39,0.39,0.38,3.25,0.01,0.01,0.01,0.72,0.1,0.05\n\
+        D,PREDISPATCH,REGION_PRICES,1,2024030331,1,NSW1,2024030333,0,90.7,0,0,0,\"2024/03/03 19:30:00\",\"2024/03/03 20:30:00\",0.39,0.39,0.38,3.25,0.01,0.01,0.01,0.72,0.1,0.05\n\
+        I,PREDISPATCH,REGION_SOLUTION,9,PREDISPATCHSEQNO,RUNNO,REGIONID,PERIODID,INTERVENTION,TOTALDEMAND,AVAILABLEGENERATION,AVAILABLELOAD,DEMANDFORECAST,DISPATCHABLEGENERATION,DISPATCHABLELOAD,NETINTERCHANGE,EXCESSGENERATION,LASTCHANGED,DATETIME,INITIALSUPPLY,CLEAREDSUPPLY,UIGF,SS_SOLAR_UIGF,SS_WIND_UIGF\n\
+        D,PREDISPATCH,REGION_SOLUTION,9,2024030331,1,NSW1,2024030332,0,8721.5,12714.4,194,-12.3,8990.1,0,268.6,0,\"2024/03/03 19:30:00\",\"2024/03/03 20:00:00\",8700.2,8721.5,1140.2,310.5,829.7\n\
+        I,PREDISPATCH,INTERCONNECTOR_SOLN,3,PREDISPATCHSEQNO,RUNNO,INTERCONNECTORID,PERIODID,INTERVENTION,METEREDMWFLOW,MWFLOW,MWLOSSES,MARGINALVALUE,VIOLATIONDEGREE,LASTCHANGED,DATETIME,EXPORTLIMIT,IMPORTLIMIT,MARGINALLOSS,EXPORTGENCONID,IMPORTGENCONID,FCASEXPORTLIMIT,FCASIMPORTLIMIT\n\
+        D,PREDISPATCH,INTERCONNECTOR_SOLN,3,2024030331,1,NSW1-QLD1,2024030332,0,0,119,6.89,0,0,\"2024/03/03 19:30:00\",\"2024/03/03 20:00:00\",582.65,-912.16,1.07457,N^^Q_NIL_KPP_1,Q^^N_NIL_SRAR,2204,-2478\n\
+        I,PREDISPATCH,CASE_SOLUTION,1,PREDISPATCHSEQNO,RUNNO,SOLUTIONSTATUS\n\
+        D,PREDISPATCH,CASE_SOLUTION,1,2024030331,1,0\n\
+        C,\"END OF REPORT\",11\n";
+
+    fn process_runs() -> RecordsCollection<RecordPredispatchIs> {
+        let mut collection = RecordsCollection::new();
+        for (file_name, contents) in [
+            ("PUBLIC_PREDISPATCHIS_202403031900.CSV", PREDISPATCH_IS_1900),
+            ("PUBLIC_PREDISPATCHIS_202403031930.CSV", PREDISPATCH_IS_1930),
+        ] {
+            let run = process_file_predispatch_is(contents.as_bytes()).expect("Failed to parse");
+            collection.append(file_name, run);
+        }
+        collection
+    }
+
+    #[test]
+    fn test_process_file_predispatch_is() {
+        let records = process_file_predispatch_is(PREDISPATCH_IS_1930.as_bytes())
+            .expect("Failed to parse")
+            .records;
+
+        assert_eq!(records.len(), 4);
+        match &records[0] {
+            RecordPredispatchIs::RegionPrices(prices) => {
+                assert_eq!(&*prices.region_id, "NSW1");
+                assert_eq!(prices.rrp, Some(102.5));
+                assert_eq!(prices.raise_1_sec_rrp, Some(0.1));
+                assert_eq!(
+                    prices.run_datetime.to_rfc3339(),
+                    "2024-03-03T08:30:00+00:00"
+                );
+                assert_eq!(
+                    prices.interval_datetime.to_rfc3339(),
+                    "2024-03-03T09:00:00+00:00"
+                );
+            }
+            other => panic!("Expected REGION_PRICES, got {}", other),
+        }
+        match &records[2] {
+            RecordPredispatchIs::RegionSolution(solution) => {
+                assert_eq!(solution.total_demand, Some(8721.5));
+                assert_eq!(solution.ss_wind_uigf, Some(829.7));
+            }
+            other => panic!("Expected REGION_SOLUTION, got {}", other),
+        }
+        match &records[3] {
+            RecordPredispatchIs::InterconnectorSolution(interconnector) => {
+                assert_eq!(&*interconnector.interconnector_id, "NSW1-QLD1");
+                assert_eq!(interconnector.import_limit, Some(-912.16));
+            }
+            other => panic!("Expected INTERCONNECTOR_SOLN, got {}", other),
+        }
+    }
+
+    #[test]
+    fn test_price_expected_at_time_for_interval() {
+        let collection = process_runs();
+        let nsw_prices: Vec<&RegionPricesData> = collection
+            .records
+            .iter()
+            .filter_map(|record| match record {
+                RecordPredispatchIs::RegionPrices(prices) if &*prices.region_id == "NSW1" => {
+                    Some(prices)
+                }
+                _ => None,
+            })
+            .collect();
+        let interval = parse_sydney_datetime("2024/03/03 20:00:00").unwrap();
+
+        let at_1915 = parse_sydney_datetime("2024/03/03 19:15:00").unwrap();
+        let expected = latest_as_of(nsw_prices.iter().copied(), interval, at_1915).unwrap();
+        assert_eq!(expected.rrp, Some(95.1));
+        assert_eq!(expected.lead_time().num_minutes(), 60);
+
+        let at_1930 = parse_sydney_datetime("2024/03/03 19:30:00").unwrap();
+        let expected = latest_as_of(nsw_prices.iter().copied(), interval, at_1930).unwrap();
+        assert_eq!(expected.rrp, Some(102.5));
+    }
+}
@@ -0,0 +1,331 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::fmt;
+use std::io::Read;
+use std::sync::Arc;
+
+use crate::common::forecast::{latest_by, ForecastRow};
+use crate::common::intern::deserialize_interned;
+use crate::common::mms_csv::{DataRow, FromDataRow, MmsCsvReader, MmsRecords, TableKey};
+use crate::common::unzip_process::{ProcessRecord, RecordsCollection};
+use crate::error::ScraperError;
+use crate::time::datetimezone_conversion::deserialize_sydney_datetime_to_utc;
+
+// Each ST PASA file is one run covering every half hour of the next six to seven days, so a
+// run holds a few thousand region rows. Later runs overlap almost all of the same intervals;
+// use `latest_outlook` to collapse a store of runs into the current view.
+
+/// STPASA.REGIONSOLUTION: the reserve outlook for a region and half hour, up to seven days
+/// ahead.
+///
+/// Each run is published once per `RUNTYPE` (e.g. `LOR` and `OUTAGE_LRC`), so the run type is
+/// part of the key along with the run, interval and region. `LORCONDITION` is 0 when there is
+/// no lack of reserve and 1 to 3 for LOR1 to LOR3.
+#[derive(Debug, Deserialize)]
+pub struct RegionSolutionData {
+    #[serde(rename = "ROW_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    csv_row_identifier: Arc<str>,
+    #[serde(rename = "FILE_TYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    category: Arc<str>,
+    #[serde(rename = "FILE_SUBTYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    report_type: Arc<str>,
+    #[serde(rename = "FILE_DESCRIPTOR")]
+    report_type_int: u32,
+    #[serde(rename = "RUN_DATETIME")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    run_datetime: DateTime<Utc>,
+    #[serde(rename = "INTERVAL_DATETIME")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    interval_datetime: DateTime<Utc>,
+    #[serde(rename = "REGIONID")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    region_id: Arc<str>,
+    #[serde(rename = "DEMAND10")]
+    demand_10: Option<f64>,
+    #[serde(rename = "DEMAND50")]
+    demand_50: Option<f64>,
+    #[serde(rename = "DEMAND90")]
+    demand_90: Option<f64>,
+    #[serde(rename = "RESERVEREQ")]
+    reserve_req: Option<f64>,
+    #[serde(rename = "CAPACITYREQ")]
+    capacity_req: Option<f64>,
+    #[serde(rename = "ENERGYREQDEMAND50")]
+    energy_req_demand_50: Option<f64>,
+    #[serde(rename = "UNCONSTRAINEDCAPACITY")]
+    unconstrained_capacity: Option<f64>,
+    #[serde(rename = "CONSTRAINEDCAPACITY")]
+    constrained_capacity: Option<f64>,
+    #[serde(rename = "NETINTERCHANGEUNDERSCARCITY")]
+    net_interchange_under_scarcity: Option<f64>,
+    #[serde(rename = "SURPLUSCAPACITY")]
+    surplus_capacity: Option<f64>,
+    #[serde(rename = "SURPLUSRESERVE")]
+    surplus_reserve: Option<f64>,
+    #[serde(rename = "RESERVECONDITION")]
+    reserve_condition: Option<u32>,
+    #[serde(rename = "MAXSURPLUSRESERVE")]
+    max_surplus_reserve: Option<f64>,
+    #[serde(rename = "MAXSPARECAPACITY")]
+    max_spare_capacity: Option<f64>,
+    #[serde(rename = "LORCONDITION")]
+    lor_condition: Option<u32>,
+    #[serde(rename = "AGGREGATECAPACITYAVAILABLE")]
+    aggregate_capacity_available: Option<f64>,
+    #[serde(rename = "AGGREGATESCHEDULEDLOAD")]
+    aggregate_scheduled_load: Option<f64>,
+    #[serde(rename = "LASTCHANGED")]
+    #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+    last_changed: DateTime<Utc>,
+    #[serde(rename = "AGGREGATEPASAAVAILABILITY")]
+    aggregate_pasa_availability: Option<f64>,
+    #[serde(rename = "RUNTYPE")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    run_type: Arc<str>,
+    #[serde(rename = "ENERGYREQDEMAND10")]
+    energy_req_demand_10: Option<f64>,
+    #[serde(rename = "CALCULATEDLOR1LEVEL")]
+    calculated_lor1_level: Option<f64>,
+    #[serde(rename = "CALCULATEDLOR2LEVEL")]
+    calculated_lor2_level: Option<f64>,
+    #[serde(rename = "MSRNETINTERCHANGEUNDERSCARCITY")]
+    msr_net_interchange_under_scarcity: Option<f64>,
+    #[serde(rename = "LORNETINTERCHANGEUNDERSCARCITY")]
+    lor_net_interchange_under_scarcity: Option<f64>,
+    #[serde(rename = "TOTALINTERMITTENTGENERATION")]
+    total_intermittent_generation: Option<f64>,
+    #[serde(rename = "DEMAND_AND_NONSCHEDGEN")]
+    demand_and_nonschedgen: Option<f64>,
+    #[serde(rename = "UIGF")]
+    uigf: Option<f64>,
+    #[serde(rename = "SEMISCHEDULEDCAPACITY")]
+    semi_scheduled_capacity: Option<f64>,
+    #[serde(rename = "LOR_SEMISCHEDULEDCAPACITY")]
+    lor_semi_scheduled_capacity: Option<f64>,
+    #[serde(rename = "LCR")]
+    lcr: Option<f64>,
+    #[serde(rename = "LCR2")]
+    lcr2: Option<f64>,
+    #[serde(rename = "FUM")]
+    fum: Option<f64>,
+    #[serde(rename = "SS_SOLAR_UIGF")]
+    ss_solar_uigf: Option<f64>,
+    #[serde(rename = "SS_WIND_UIGF")]
+    ss_wind_uigf: Option<f64>,
+}
+
+impl fmt::Display for RegionSolutionData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "RegionSolutionData: {{ csv_row_identifier: {}, category: {}, report_type: {}, report_type_int: {}, run_datetime: {:?}, interval_datetime: {:?}, region_id: {:?}, demand_10: {:?}, demand_50: {:?}, demand_90: {:?}, reserve_req: {:?}, capacity_req: {:?}, energy_req_demand_50: {:?}, unconstrained_capacity: {:?}, constrained_capacity: {:?}, net_interchange_under_scarcity: {:?}, surplus_capacity: {:?}, surplus_reserve: {:?}, reserve_condition: {:?}, max_surplus_reserve: {:?}, max_spare_capacity: {:?}, lor_condition: {:?}, aggregate_capacity_available: {:?}, aggregate_scheduled_load: {:?}, last_changed: {:?}, aggregate_pasa_availability: {:?}, run_type: {:?}, energy_req_demand_10: {:?}, calculated_lor1_level: {:?}, calculated_lor2_level: {:?}, msr_net_interchange_under_scarcity: {:?}, lor_net_interchange_under_scarcity: {:?}, total_intermittent_generation: {:?}, demand_and_nonschedgen: {:?}, uigf: {:?}, semi_scheduled_capacity: {:?}, lor_semi_scheduled_capacity: {:?}, lcr: {:?}, lcr2: {:?}, fum: {:?}, ss_solar_uigf: {:?}, ss_wind_uigf: {:?} }}",
+            self.csv_row_identifier,
+            self.category,
+            self.report_type,
+            self.report_type_int,
+            self.run_datetime,
+            self.interval_datetime,
+            self.region_id,
+            self.demand_10,
+            self.demand_50,
+            self.demand_90,
+            self.reserve_req,
+            self.capacity_req,
+            self.energy_req_demand_50,
+            self.unconstrained_capacity,
+            self.constrained_capacity,
+            self.net_interchange_under_scarcity,
+            self.surplus_capacity,
+            self.surplus_reserve,
+            self.reserve_condition,
+            self.max_surplus_reserve,
+            self.max_spare_capacity,
+            self.lor_condition,
+            self.aggregate_capacity_available,
+            self.aggregate_scheduled_load,
+            self.last_changed,
+            self.aggregate_pasa_availability,
+            self.run_type,
+            self.energy_req_demand_10,
+            self.calculated_lor1_level,
+            self.calculated_lor2_level,
+            self.msr_net_interchange_under_scarcity,
+            self.lor_net_interchange_under_scarcity,
+            self.total_intermittent_generation,
+            self.demand_and_nonschedgen,
+            self.uigf,
+            self.semi_scheduled_capacity,
+            self.lor_semi_scheduled_capacity,
+            self.lcr,
+            self.lcr2,
+            self.fum,
+            self.ss_solar_uigf,
+            self.ss_wind_uigf
+        )
+    }
+}
+
+impl RegionSolutionData {
+    pub fn region_id(&self) -> &str {
+        &self.region_id
+    }
+
+    pub fn run_type(&self) -> &str {
+        &self.run_type
+    }
+
+    /// The forecast LOR level (1 to 3), or `None` if reserves are adequate.
+    pub fn lack_of_reserve(&self) -> Option<u32> {
+        self.lor_condition.filter(|level| *level > 0)
+    }
+}
+
+impl ForecastRow for RegionSolutionData {
+    fn run_datetime(&self) -> DateTime<Utc> {
+        self.run_datetime
+    }
+
+    fn interval_datetime(&self) -> DateTime<Utc> {
+        self.interval_datetime
+    }
+}
+
+#[derive(Debug)]
+pub enum RecordStPasa {
+    RegionSolution(RegionSolutionData),
+}
+
+impl fmt::Display for RecordStPasa {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecordStPasa::RegionSolution(region_solution) => write!(f, "{}", region_solution),
+        }
+    }
+}
+
+impl ProcessRecord<RecordStPasa> for RegionSolutionData {
+    fn process(row: &DataRow) -> Result<RecordStPasa, ScraperError> {
+        let record = match row.version()? {
+            7 => row.deserialize::<RegionSolutionData>()?,
+            version => return Err(row.unsupported_version(version)),
+        };
+        Ok(RecordStPasa::RegionSolution(record))
+    }
+}
+
+impl FromDataRow for RecordStPasa {
+    fn from_data_row(row: &DataRow) -> Result<Self, ScraperError> {
+        if row.table.is("STPASA", "REGIONSOLUTION") {
+            RegionSolutionData::process(row)
+        } else {
+            Err(row.unknown_table())
+        }
+    }
+
+    // ST PASA also carries case, constraint and interconnector solution tables
+    fn reads_table(table: &TableKey) -> bool {
+        table.is("STPASA", "REGIONSOLUTION")
+    }
+}
+
+/// Streams ST PASA records one row at a time.
+pub fn stream_file_stpasa<R: Read>(reader: R) -> MmsRecords<R, RecordStPasa> {
+    MmsCsvReader::new(reader).records()
+}
+
+pub fn process_file_stpasa<R: Read>(
+    reader: R,
+) -> Result<RecordsCollection<RecordStPasa>, ScraperError> {
+    RecordsCollection::from_records(stream_file_stpasa(reader))
+}
+
+/// The latest run's region solution for each run type, region and half hour, in interval
+/// order. Feed it the records of several ST PASA files to get the current outlook.
+pub fn latest_outlook<'a, I>(records: I) -> Vec<&'a RegionSolutionData>
+where
+    I: IntoIterator<Item = &'a RecordStPasa>,
+{
+    let rows = records.into_iter().map(|record| match record {
+        RecordStPasa::RegionSolution(region_solution) => region_solution,
+    });
+    latest_by(rows, |row| {
+        (
+            Arc::clone(&row.run_type),
+            Arc::clone(&row.region_id),
+            row.interval_datetime,
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STPASA_1400: &str = "C,NEMP.WORLD,STPASA,AEMO,PUBLIC,2024/03/03,13:31:40,0000000412660531,STPASA,0000000412660528\n\
+        I,STPASA,REGIONSOLUTION,7,RUN_DATETIME,INTERVAL_DATETIME,REGIONID,DEMAND10,DEMAND50,DEMAND90,RESERVEREQ,CAPACITYREQ,ENERGYREQDEMAND50,UNCONSTRAINEDCAPACITY,CONSTRAINEDCAPACITY,NETINTERCHANGEUNDERSCARCITY,SURPLUSCAPACITY,SURPLUSRESERVE,RESERVECONDITION,MAXSURPLUSRESERVE,MAXSPARECAPACITY,LORCONDITION,AGGREGATECAPACITYAVAILABLE,AGGREGATESCHEDULEDLOAD,LASTCHANGED,AGGREGATEPASAAVAILABILITY,RUNTYPE,ENERGYREQDEMAND10,CALCULATEDLOR1LEVEL,CALCULATEDLOR2LEVEL,MSRNETINTERCHANGEUNDERSCARCITY,LORNETINTERCHANGEUNDERSCARCITY,TOTALINTERMITTENTGENERATION,DEMAND_AND_NONSCHEDGEN,UIGF,SEMISCHEDULEDCAPACITY,LOR_SEMISCHEDULEDCAPACITY,LCR,LCR2,FUM,SS_SOLAR_UIGF,SS_WIND_UIGF\n\
+        D,STPASA,REGIONSOLUTION,7,\"2024/03/03 14:00:00\",\"2024/03/09 17:30:00\",NSW1,11550,11850,12150,0,0,,12880,12410,-650,3120,,0,,,0,13410,0,\"2024/03/03 13:31:14\",13702,LOR,,1710,790,,-610,2980,11970,2860,4410,2860,,,,2310,550\n\
+        D,STPASA,REGIONSOLUTION,7,\"2024/03/03 14:00:00\",\"2024/03/09 18:00:00\",NSW1,11940,12240,12540,0,0,,12880,12410,-650,3120,,0,,,1,13410,0,\"2024/03/03 13:31:14\",13702,LOR,,1710,790,,-610,2980,12360,2860,4410,2860,,,,2310,550\n\
+        D,STPASA,REGIONSOLUTION,7,\"2024/03/03 14:00:00\",\"2024/03/09 18:00:00\",NSW1,11940,12240,12540,0,0,,12880,12410,-650,3120,,0,,,0,13410,0,\"2024/03/03 13:31:14\",13702,OUTAGE_LRC,,1710,790,,-610,2980,12360,2860,4410,2860,,,,2310,550\n\
+        C,\"END OF REPORT\",6\n";
+
+    const STPASA_1600: &str = "C,NEMP.WORLD,STPASA,AEMO,PUBLIC,2024/03/03,15:31:36,0000000412668024,STPASA,0000000412668021\n\
+        I,STPASA,REGIONSOLUTION,7,RUN_DATETIME,INTERVAL_DATETIME,REGIONID,DEMAND10,DEMAND50,DEMAND90,RESERVEREQ,CAPACITYREQ,ENERGYREQDEMAND50,UNCONSTRAINEDCAPACITY,CONSTRAINEDCAPACITY,NETINTERCHANGEUNDERSCARCITY,SURPLUSCAPACITY,SURPLUSRESERVE,RESERVECONDITION,MAXSURPLUSRESERVE,MAXSPARECAPACITY,LORCONDITION,AGGREGATECAPACITYAVAILABLE,AGGREGATESCHEDULEDLOAD,LASTCHANGED,AGGREGATEPASAAVAILABILITY,RUNTYPE,ENERGYREQDEMAND10,CALCULATEDLOR1LEVEL,CALCULATEDLOR2LEVEL,MSRNETINTERCHANGEUNDERSCARCITY,LORNETINTERCHANGEUNDERSCARCITY,TOTALINTERMITTENTGENERATION,DEMAND_AND_NONSCHEDGEN,UIGF,SEMISCHEDULEDCAPACITY,LOR_SEMISCHEDULEDCAPACITY,LCR,LCR2,FUM,SS_SOLAR_UIGF,SS_WIND_UIGF\n\
+        D,STPASA,REGIONSOLUTION,7,\"2024/03/03 16:00:00\",\"2024/03/09 18:00:00\",NSW1,11710,12010,12310,0,0,,12880,12410,-650,3120,,0,,,0,13410,0,\"2024/03/03 15:31:09\",13702,LOR,,1710,790,,-610,2980,12130,2860,4410,2860,,,,2310,550\n\
+        D,STPASA,REGIONSOLUTION,7,\"2024/03/03 16:00:00\",\"2024/03/09 18:30:00\",NSW1,12160,12460,12760,0,0,,12880,12410,-650,3120,,0,,,2,13410,0,\"2024/03/03 15:31:09\",13702,LOR,,1710,790,,-610,2980,12580,2860,4410,2860,,,,2310,550\n\
+        C,\"END OF REPORT\",5\n";
+
+    #[test]
+    fn test_process_file_stpasa() {
+        let records = process_file_stpasa(STPASA_1400.as_bytes())
+            .expect("Failed to parse")
+            .records;
+
+        assert_eq!(records.len(), 3);
+        match &records[1] {
+            RecordStPasa::RegionSolution(region) => {
+                assert_eq!(region.region_id(), "NSW1");
+                assert_eq!(region.run_type(), "LOR");
+                assert_eq!(region.demand_50, Some(12240.0));
+                assert_eq!(region.energy_req_demand_50, None);
+                assert_eq!(region.lack_of_reserve(), Some(1));
+                assert_eq!(
+                    region.interval_datetime.to_rfc3339(),
+                    "2024-03-09T07:00:00+00:00"
+                );
+                assert_eq!(region.lead_time().num_hours(), 148);
+            }
+        }
+    }
+
+    #[test]
+    fn test_latest_outlook_prefers_latest_run() {
+        let mut collection = process_file_stpasa(STPASA_1400.as_bytes()).expect("Failed to parse");
+        let later = process_file_stpasa(STPASA_1600.as_bytes()).expect("Failed to parse");
+        collection.append("STPASA_1600.CSV", later);
+
+        let outlook: Vec<_> = latest_outlook(&collection.records)
+            .into_iter()
+            .filter(|row| row.run_type() == "LOR")
+            .map(|row| {
+                (
+                    row.interval_datetime.to_rfc3339(),
+                    row.demand_50,
+                    row.lack_of_reserve(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            outlook,
+            [
+                ("2024-03-09T06:30:00+00:00".to_string(), Some(11850.0), None),
+                ("2024-03-09T07:00:00+00:00".to_string(), Some(12010.0), None),
+                (
+                    "2024-03-09T07:30:00+00:00".to_string(),
+                    Some(12460.0),
+                    Some(2)
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Deserialize;
+use std::fmt;
+use std::io::Read;
+
+use crate::common::unzip_process::RecordsCollection;
+use crate::common::wem::{process_wem_file, WemFormat};
+use crate::error::ScraperError;
+use crate::time::awst::{
+    deserialize_awst_datetime_to_utc, deserialize_optional_awst_datetime_to_utc,
+};
+
+/// Balancing summary: the balancing price and system totals for a WEM trading interval.
+/// `Trading Interval` is the start of the interval.
+#[derive(Debug, Deserialize)]
+pub struct BalancingSummaryData {
+    #[serde(rename = "Trading Date")]
+    trading_date: NaiveDate,
+    #[serde(rename = "Interval Number")]
+    interval_number: u32,
+    #[serde(rename = "Trading Interval")]
+    #[serde(deserialize_with = "deserialize_awst_datetime_to_utc")]
+    trading_interval: DateTime<Utc>,
+    #[serde(rename = "Final Price ($/MWh)")]
+    final_price: Option<f64>,
+    #[serde(rename = "Total Generation (MW)")]
+    total_generation: Option<f64>,
+    #[serde(rename = "Total Outage (MW)")]
+    total_outage: Option<f64>,
+    #[serde(rename = "Load Forecast (MW)")]
+    load_forecast: Option<f64>,
+    #[serde(rename = "Forecast As At")]
+    #[serde(default)]
+    #[serde(deserialize_with = "deserialize_optional_awst_datetime_to_utc")]
+    forecast_as_at: Option<DateTime<Utc>>,
+}
+
+impl fmt::Display for BalancingSummaryData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "BalancingSummaryData: {{ trading_date: {:?}, interval_number: {:?}, trading_interval: {:?}, final_price: {:?}, total_generation: {:?}, total_outage: {:?}, load_forecast: {:?}, forecast_as_at: {:?} }}",
+            self.trading_date,
+            self.interval_number,
+            self.trading_interval,
+            self.final_price,
+            self.total_generation,
+            self.total_outage,
+            self.load_forecast,
+            self.forecast_as_at
+        )
+    }
+}
+
+impl BalancingSummaryData {
+    pub fn trading_interval(&self) -> DateTime<Utc> {
+        self.trading_interval
+    }
+
+    pub fn final_price(&self) -> Option<f64> {
+        self.final_price
+    }
+}
+
+/// Reads a balancing summary file, e.g. `balancing-summary-2024.csv` or its `.json` twin.
+pub fn process_file_wem_balancing_summary<R: Read>(
+    reader: R,
+    format: WemFormat,
+) -> Result<RecordsCollection<BalancingSummaryData>, ScraperError> {
+    process_wem_file(reader, format)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::unzip_process::RowErrorPolicy;
+    use crate::common::wem::process_wem_file_with_policy;
+
+    const BALANCING_SUMMARY_CSV: &str = "Trading Date,Interval Number,Trading Interval,Final Price ($/MWh),Total Generation (MW),Total Outage (MW),Load Forecast (MW),Forecast As At\n\
+        2023-09-30,1,2023-09-30 08:00:00,51.21,1873.45,612.0,1850,2023-09-30 07:45:10\n\
+        2023-09-30,2,2023-09-30 08:30:00,48.9,1902.11,612.0,,\n";
+
+    const BALANCING_SUMMARY_JSON: &str = r#"[
+        {"Trading Date": "2023-09-30", "Interval Number": 1, "Trading Interval": "2023-09-30T08:00:00+08:00", "Final Price ($/MWh)": 51.21, "Total Generation (MW)": 1873.45, "Total Outage (MW)": 612.0, "Load Forecast (MW)": 1850, "Forecast As At": "2023-09-30T07:45:10+08:00"},
+        {"Trading Date": "2023-09-30", "Interval Number": 2, "Trading Interval": "2023-09-30T08:30:00+08:00", "Final Price ($/MWh)": 48.9, "Total Generation (MW)": 1902.11, "Total Outage (MW)": 612.0, "Load Forecast (MW)": null}
+    ]"#;
+
+    #[test]
+    fn test_process_file_wem_balancing_summary_csv_and_json_agree() {
+        let csv =
+            process_file_wem_balancing_summary(BALANCING_SUMMARY_CSV.as_bytes(), WemFormat::Csv)
+                .expect("Failed to parse CSV")
+                .records;
+        let json =
+            process_file_wem_balancing_summary(BALANCING_SUMMARY_JSON.as_bytes(), WemFormat::Json)
+                .expect("Failed to parse JSON")
+                .records;
+
+        assert_eq!(csv.len(), 2);
+        assert_eq!(json.len(), 2);
+        for (csv, json) in csv.iter().zip(&json) {
+            assert_eq!(csv.to_string(), json.to_string());
+        }
+        assert_eq!(
+            csv[0].trading_interval().to_rfc3339(),
+            "2023-09-30T00:00:00+00:00"
+        );
+        assert_eq!(csv[0].final_price(), Some(51.21));
+        assert_eq!(csv[1].load_forecast, None);
+        assert_eq!(csv[1].forecast_as_at, None);
+    }
+
+    #[test]
+    fn test_bad_json_elements_are_skipped() {
+        let data = BALANCING_SUMMARY_JSON
+            .replace("\"Interval Number\": 2", "\"Interval Number\": \"two\"");
+
+        let collection = process_wem_file_with_policy::<BalancingSummaryData, _>(
+            data.as_bytes(),
+            WemFormat::Json,
+            RowErrorPolicy::Skip {
+                max_rejected_rows: 1,
+            },
+        )
+        .expect("Failed to parse");
+        assert_eq!(collection.records.len(), 1);
+        assert_eq!(collection.rejected_row_count, 1);
+
+        let err = process_file_wem_balancing_summary(data.as_bytes(), WemFormat::Json).unwrap_err();
+        assert!(err.is_row_error());
+    }
+}
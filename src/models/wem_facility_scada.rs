@@ -0,0 +1,148 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Deserialize;
+use std::fmt;
+use std::io::Read;
+use std::sync::Arc;
+
+use crate::common::intern::deserialize_interned;
+use crate::common::unzip_process::RecordsCollection;
+use crate::common::wem::{process_wem_file, WemFormat};
+use crate::error::ScraperError;
+use crate::time::awst::{
+    deserialize_awst_datetime_to_utc, deserialize_optional_awst_datetime_to_utc,
+};
+
+/// Facility SCADA: metered energy of a WEM facility over a trading interval. `Trading
+/// Interval` is the start of the interval and `EOI Quantity` the end-of-interval output.
+#[derive(Debug, Deserialize)]
+pub struct FacilityScadaData {
+    #[serde(rename = "Trading Date")]
+    trading_date: NaiveDate,
+    #[serde(rename = "Interval Number")]
+    interval_number: u32,
+    #[serde(rename = "Trading Interval")]
+    #[serde(deserialize_with = "deserialize_awst_datetime_to_utc")]
+    trading_interval: DateTime<Utc>,
+    #[serde(rename = "Participant Code")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    participant_code: Arc<str>,
+    #[serde(rename = "Facility Code")]
+    #[serde(deserialize_with = "deserialize_interned")]
+    facility_code: Arc<str>,
+    #[serde(rename = "Energy Generated (MWh)")]
+    energy_generated: Option<f64>,
+    #[serde(rename = "EOI Quantity (MW)")]
+    eoi_quantity: Option<f64>,
+    #[serde(rename = "Extracted At")]
+    #[serde(default)]
+    #[serde(deserialize_with = "deserialize_optional_awst_datetime_to_utc")]
+    extracted_at: Option<DateTime<Utc>>,
+}
+
+impl fmt::Display for FacilityScadaData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "FacilityScadaData: {{ trading_date: {:?}, interval_number: {:?}, trading_interval: {:?}, participant_code: {:?}, facility_code: {:?}, energy_generated: {:?}, eoi_quantity: {:?}, extracted_at: {:?} }}",
+            self.trading_date,
+            self.interval_number,
+            self.trading_interval,
+            self.participant_code,
+            self.facility_code,
+            self.energy_generated,
+            self.eoi_quantity,
+            self.extracted_at
+        )
+    }
+}
+
+impl FacilityScadaData {
+    pub fn facility_code(&self) -> &str {
+        &self.facility_code
+    }
+
+    pub fn trading_interval(&self) -> DateTime<Utc> {
+        self.trading_interval
+    }
+
+    pub fn energy_generated(&self) -> Option<f64> {
+        self.energy_generated
+    }
+}
+
+/// Reads a facility SCADA file, e.g. `facility-scada-2024-03.csv` or its `.json` twin.
+pub fn process_file_wem_facility_scada<R: Read>(
+    reader: R,
+    format: WemFormat,
+) -> Result<RecordsCollection<FacilityScadaData>, ScraperError> {
+    process_wem_file(reader, format)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::unzip_process::RowErrorPolicy;
+    use crate::common::wem::process_wem_file_with_policy;
+
+    const FACILITY_SCADA_CSV: &str = "Trading Date,Interval Number,Trading Interval,Participant Code,Facility Code,Energy Generated (MWh),EOI Quantity (MW),Extracted At\n\
+        2024-03-01,1,2024-03-01 08:00:00,ALINTA,ALINTA_PNJ_U1,68.4,137.2,2024-03-02 04:00:12\n\
+        2024-03-01,1,2024-03-01 08:00:00,SYNERGY,MUJA_G7,102.75,205.9,2024-03-02 04:00:12\n";
+
+    #[test]
+    fn test_process_file_wem_facility_scada() {
+        let records =
+            process_file_wem_facility_scada(FACILITY_SCADA_CSV.as_bytes(), WemFormat::Csv)
+                .expect("Failed to parse")
+                .records;
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].facility_code(), "MUJA_G7");
+        assert_eq!(&*records[1].participant_code, "SYNERGY");
+        assert_eq!(records[1].energy_generated(), Some(102.75));
+        assert_eq!(
+            records[1].trading_interval().to_rfc3339(),
+            "2024-03-01T00:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_process_file_wem_facility_scada_reports_bad_row() {
+        let data = "Trading Date,Interval Number,Trading Interval,Participant Code,Facility Code,Energy Generated (MWh),EOI Quantity (MW),Extracted At\n\
+            2024-03-01,1,01/03/2024 08:00,ALINTA,ALINTA_PNJ_U1,68.4,137.2,\n";
+        match process_file_wem_facility_scada(data.as_bytes(), WemFormat::Csv) {
+            Err(ScraperError::DateTime { location, value }) => {
+                assert_eq!(location.line, Some(2));
+                assert_eq!(value, "01/03/2024 08:00");
+            }
+            other => panic!(
+                "Expected a datetime error, got {:?}",
+                other.map(|c| c.records.len())
+            ),
+        }
+    }
+
+    #[test]
+    fn test_bad_rows_are_quarantined() {
+        let data = format!(
+            "{}2024-03-01,1,2024-03-01 08:00:00,BW,BW1,lots,137.2,2024-03-02 04:00:12\n",
+            FACILITY_SCADA_CSV
+        );
+        let policy = RowErrorPolicy::Quarantine {
+            max_rejected_rows: 1,
+        };
+
+        let collection = process_wem_file_with_policy::<FacilityScadaData, _>(
+            data.as_bytes(),
+            WemFormat::Csv,
+            policy,
+        )
+        .expect("Failed to parse");
+        assert_eq!(collection.records.len(), 2);
+        assert_eq!(collection.rejected_row_count, 1);
+        let rejected = &collection.rejected_rows[0];
+        assert_eq!(rejected.line, Some(4));
+        assert_eq!(rejected.fields[4], "BW1");
+        assert_eq!(
+            rejected.error.location().and_then(|l| l.column.as_deref()),
+            Some("Energy Generated (MWh)")
+        );
+    }
+}
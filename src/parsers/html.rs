@@ -1,5 +1,8 @@
+use chrono::NaiveDate;
 use regex::Regex;
 
+use crate::parsers::market_notice::MarketNoticeLink;
+
 pub struct ZipLinkExtractorFromHtml {
     pattern: Regex,
 }
@@ -21,6 +24,55 @@ impl ZipLinkExtractorFromHtml {
     }
 }
 
+impl Default for ZipLinkExtractorFromHtml {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Extracts notice links from the `/Reports/Current/Market_Notice/` listing. Notices are
+/// plain text files named `NEMITWEB1_MKTNOTICE_<date>.R<notice id>` rather than zips.
+pub struct MarketNoticeLinkExtractorFromHtml {
+    pattern: Regex,
+}
+
+impl MarketNoticeLinkExtractorFromHtml {
+    pub fn new() -> Self {
+        MarketNoticeLinkExtractorFromHtml {
+            pattern: Regex::new(r#"HREF="([^"]*_MKTNOTICE_(\d{8})\.R(\d+))""#).unwrap(),
+        }
+    }
+
+    pub fn extract_links(&self, html: &str) -> Vec<String> {
+        self.pattern
+            .captures_iter(html)
+            .filter_map(|cap| cap.get(1))
+            .map(|link| link.as_str().to_string())
+            .collect()
+    }
+
+    /// Like `extract_links`, with the date and notice ID read from each file name so the
+    /// listing can be filtered before any notice is fetched.
+    pub fn extract_notice_links(&self, html: &str) -> Vec<MarketNoticeLink> {
+        self.pattern
+            .captures_iter(html)
+            .filter_map(|cap| {
+                Some(MarketNoticeLink {
+                    path: cap[1].to_string(),
+                    date: NaiveDate::parse_from_str(&cap[2], "%Y%m%d").ok()?,
+                    notice_id: cap[3].parse().ok()?,
+                })
+            })
+            .collect()
+    }
+}
+
+impl Default for MarketNoticeLinkExtractorFromHtml {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -39,7 +91,7 @@ mod tests {
 
         let links = extractor.extract_links(&html_content);
 
-        let expected_links = vec![
+        let expected_links = [
             "/Reports/Current/TradingIS_Reports/PUBLIC_TRADINGIS_202403120535_0000000413460134.zip",
             "/Reports/Current/TradingIS_Reports/PUBLIC_TRADINGIS_202403120540_0000000413460407.zip",
             "/Reports/Current/TradingIS_Reports/PUBLIC_TRADINGIS_202403120545_0000000413460679.zip",
@@ -50,4 +102,34 @@ mod tests {
 
         assert_eq!(links, expected_links);
     }
+
+    #[test]
+    fn test_extract_links_from_market_notice_html() {
+        let extractor = MarketNoticeLinkExtractorFromHtml::new();
+        let mut file = File::open(Path::new("src/fixtures/Market_Notice.html"))
+            .expect("Failed to open HTML file");
+        let mut html_content = String::new();
+        file.read_to_string(&mut html_content)
+            .expect("Failed to read HTML content");
+
+        let links = extractor.extract_links(&html_content);
+
+        assert_eq!(
+            links,
+            [
+                "/Reports/Current/Market_Notice/NEMITWEB1_MKTNOTICE_20240303.R116005",
+                "/Reports/Current/Market_Notice/NEMITWEB1_MKTNOTICE_20240303.R116006",
+                "/Reports/Current/Market_Notice/NEMITWEB1_MKTNOTICE_20240304.R116021",
+            ]
+        );
+
+        let notice_links = extractor.extract_notice_links(&html_content);
+        assert_eq!(notice_links.len(), 3);
+        assert_eq!(notice_links[2].path, links[2]);
+        assert_eq!(notice_links[2].notice_id, 116021);
+        assert_eq!(
+            notice_links[2].date,
+            NaiveDate::from_ymd_opt(2024, 3, 4).unwrap()
+        );
+    }
 }
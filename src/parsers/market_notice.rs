@@ -0,0 +1,457 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use chrono_tz::Australia::Sydney;
+use regex::Regex;
+use std::fmt;
+
+use crate::error::ScraperError;
+use crate::time::datetimezone_conversion::sydney_to_utc;
+
+/// A notice from `/Reports/Current/Market_Notice/`.
+///
+/// Notices are plain text: a block of `Key : Value` header lines, then the free text after
+/// `Reason :`. The subject is the `External Reference` header.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarketNotice {
+    pub notice_id: u64,
+    /// `Notice Type ID`, e.g. `RESERVE NOTICE` or `RECLASSIFY CONTINGENCY`.
+    pub notice_type: String,
+    pub notice_type_description: String,
+    /// `Creation Date`, falling back to the start of `Issue Date`.
+    pub issue_datetime: DateTime<Utc>,
+    pub subject: String,
+    pub body: String,
+    /// Other notices the body refers to, e.g. the notice a cancellation cancels.
+    pub referenced_notices: Vec<u64>,
+}
+
+impl fmt::Display for MarketNotice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "MarketNotice: {{ notice_id: {}, notice_type: {:?}, notice_type_description: {:?}, issue_datetime: {}, subject: {:?}, referenced_notices: {:?} }}",
+            self.notice_id,
+            self.notice_type,
+            self.notice_type_description,
+            self.issue_datetime,
+            self.subject,
+            self.referenced_notices
+        )
+    }
+}
+
+/// A notice in the Market_Notice listing, before it is fetched.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarketNoticeLink {
+    pub path: String,
+    /// The date in the file name, which is the day the notice was published.
+    pub date: NaiveDate,
+    pub notice_id: u64,
+}
+
+pub struct MarketNoticeParser {
+    header: Regex,
+    reference: Regex,
+}
+
+impl MarketNoticeParser {
+    pub fn new() -> Self {
+        MarketNoticeParser {
+            header: Regex::new(r"^\s*([A-Za-z][A-Za-z ]*?)\s*:\s*(.*?)\s*$").unwrap(),
+            reference: Regex::new(r"(?i)market\s+notice\s*(?:no\.?|number)?\s*(\d{4,})").unwrap(),
+        }
+    }
+
+    pub fn parse(&self, text: &str) -> Result<MarketNotice, ScraperError> {
+        let mut notice_id = None;
+        let mut notice_type = None;
+        let mut notice_type_description = None;
+        let mut creation_date = None;
+        let mut issue_date = None;
+        let mut subject = None;
+        let mut body_start = None;
+
+        for (offset, line) in line_offsets(text) {
+            let Some(cap) = self.header.captures(line) else {
+                continue;
+            };
+            let value = cap[2].to_string();
+            match &cap[1] {
+                "Notice ID" => notice_id = Some(value),
+                "Notice Type ID" => notice_type = Some(value),
+                "Notice Type Description" => notice_type_description = Some(value),
+                "Creation Date" => creation_date = Some(value),
+                "Issue Date" => issue_date = Some(value),
+                "External Reference" => subject = Some(value),
+                "Reason" => {
+                    body_start = Some(offset + line.len());
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        let notice_id: u64 = required(notice_id, "Notice ID")?
+            .parse()
+            .map_err(|_| ScraperError::InvalidMarketNotice("Notice ID is not a number".into()))?;
+        let issue_datetime = match (creation_date, issue_date) {
+            (Some(creation_date), _) => parse_notice_datetime(&creation_date),
+            (None, Some(issue_date)) => parse_notice_date(&issue_date)
+                .and_then(|date| sydney_to_utc(&date.and_time(NaiveTime::MIN))),
+            (None, None) => None,
+        }
+        .ok_or_else(|| ScraperError::InvalidMarketNotice("Issue time is missing".into()))?;
+
+        let body = body_start
+            .map(|start| strip_trailer(&text[start..]))
+            .unwrap_or_default();
+        let mut referenced_notices: Vec<u64> = self
+            .reference
+            .captures_iter(&body)
+            .filter_map(|cap| cap[1].parse().ok())
+            .filter(|id| *id != notice_id)
+            .collect();
+        referenced_notices.sort_unstable();
+        referenced_notices.dedup();
+
+        Ok(MarketNotice {
+            notice_id,
+            notice_type: required(notice_type, "Notice Type ID")?,
+            notice_type_description: notice_type_description.unwrap_or_default(),
+            issue_datetime,
+            subject: subject.unwrap_or_default(),
+            body,
+            referenced_notices,
+        })
+    }
+}
+
+impl Default for MarketNoticeParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn line_offsets(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.split_inclusive('\n').scan(0, |offset, line| {
+        let start = *offset;
+        *offset += line.len();
+        Some((start, line.trim_end_matches(['\r', '\n'])))
+    })
+}
+
+fn required(value: Option<String>, name: &str) -> Result<String, ScraperError> {
+    value.ok_or_else(|| ScraperError::InvalidMarketNotice(format!("{} is missing", name)))
+}
+
+// Notices end with a rule, `END OF REPORT` and another rule
+fn strip_trailer(body: &str) -> String {
+    let body = match body.find("END OF REPORT") {
+        Some(end) => &body[..end],
+        None => body,
+    };
+    body.trim_end_matches(|c: char| c == '-' || c.is_whitespace())
+        .trim_start()
+        .to_string()
+}
+
+fn parse_notice_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.trim(), "%d/%m/%Y").ok()
+}
+
+// `Creation Date` pads the date and time apart with spaces
+fn parse_notice_datetime(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+    let naive = NaiveDateTime::parse_from_str(&value, "%d/%m/%Y %H:%M:%S").ok()?;
+    sydney_to_utc(&naive)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MarketEventKind {
+    /// A Lack of Reserve condition, `level` 1 to 3. `forecast` is false for actual LORs.
+    LackOfReserve {
+        level: u8,
+        forecast: bool,
+    },
+    Direction,
+    Reclassification,
+}
+
+/// A reliability event announced by a market notice.
+///
+/// `start` and `end` are the first two times given in the notice as `HHMM hrs`, optionally
+/// followed by a date; times without a date fall on the day the notice was issued. For a
+/// cancellation `start` is when the event was cancelled.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarketEvent {
+    pub notice_id: u64,
+    pub kind: MarketEventKind,
+    pub cancelled: bool,
+    pub region_id: Option<String>,
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+}
+
+pub struct MarketEventExtractor {
+    lor: Regex,
+    direction: Regex,
+    reclassification: Regex,
+    cancellation: Regex,
+    region: Regex,
+    time: Regex,
+}
+
+impl MarketEventExtractor {
+    pub fn new() -> Self {
+        MarketEventExtractor {
+            lor: Regex::new(r"(?i)\b(actual|forecast)\b.*?\bLOR\s*([1-3])\b").unwrap(),
+            direction: Regex::new(r"(?i)\bdirection\b").unwrap(),
+            reclassification: Regex::new(r"(?i)\breclassif").unwrap(),
+            cancellation: Regex::new(r"(?i)\bcancel").unwrap(),
+            region: Regex::new(r"\b(NSW|QLD|VIC|SA|TAS)1?\b").unwrap(),
+            time: Regex::new(r"\b(\d{4})\s*hrs\b(?:\s*(?:on\s+)?(\d{1,2}/\d{1,2}/\d{4}))?")
+                .unwrap(),
+        }
+    }
+
+    /// Classifies a notice as a LOR, direction or reclassification event, or `None` for any
+    /// other notice.
+    pub fn extract(&self, notice: &MarketNotice) -> Option<MarketEvent> {
+        let kind = self.kind(notice)?;
+        let region_id = self
+            .region
+            .captures(&notice.subject)
+            .or_else(|| self.region.captures(&notice.body))
+            .map(|cap| format!("{}1", &cap[1]));
+        let issue_date = notice.issue_datetime.with_timezone(&Sydney).date_naive();
+        let mut times = self.time.captures_iter(&notice.body).filter_map(|cap| {
+            let date = match cap.get(2) {
+                Some(date) => parse_notice_date(date.as_str())?,
+                None => issue_date,
+            };
+            parse_hrs(&cap[1], date)
+        });
+
+        Some(MarketEvent {
+            notice_id: notice.notice_id,
+            kind,
+            cancelled: self.cancellation.is_match(&notice.subject),
+            region_id,
+            start: times.next(),
+            end: times.next(),
+        })
+    }
+
+    fn kind(&self, notice: &MarketNotice) -> Option<MarketEventKind> {
+        if let Some(cap) = self.lor.captures(&notice.subject) {
+            return Some(MarketEventKind::LackOfReserve {
+                level: cap[2].parse().ok()?,
+                forecast: cap[1].eq_ignore_ascii_case("forecast"),
+            });
+        }
+        if notice.notice_type.contains("RECLASSIFY")
+            || self.reclassification.is_match(&notice.subject)
+        {
+            return Some(MarketEventKind::Reclassification);
+        }
+        if self.direction.is_match(&notice.subject) {
+            return Some(MarketEventKind::Direction);
+        }
+        None
+    }
+}
+
+impl Default for MarketEventExtractor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// `2400 hrs` is midnight at the end of the day
+fn parse_hrs(hhmm: &str, date: NaiveDate) -> Option<DateTime<Utc>> {
+    let (date, hhmm) = if hhmm == "2400" {
+        (date.succ_opt()?, "0000")
+    } else {
+        (date, hhmm)
+    };
+    let time = NaiveTime::parse_from_str(hhmm, "%H%M").ok()?;
+    sydney_to_utc(&date.and_time(time))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ACTUAL_LOR1: &str = "-------------------------------------------------------------------
+                       MARKET NOTICE
+-------------------------------------------------------------------
+
+From :              AEMO
+To   :              NEMITWEB1
+Creation Date :     03/03/2024     17:45:10
+
+-------------------------------------------------------------------
+
+Notice ID               :         116005
+Notice Type ID          :         RESERVE NOTICE
+Notice Type Description :         LRC/LOR1/LOR2/LOR3
+Issue Date              :         03/03/2024
+External Reference      :         Actual Lack Of Reserve Level 1 (LOR1) in the NSW Region - 03/03/2024
+
+-------------------------------------------------------------------
+
+Reason :
+
+AEMO ELECTRICITY MARKET NOTICE
+
+Actual Lack Of Reserve Level 1 (LOR1) in the NSW Region - 03/03/2024
+
+An Actual LOR1 condition has been declared under clause 4.8.4(a) of the National Electricity Rules for the NSW region from 1730 hrs.
+The Actual LOR1 condition is forecast to exist until 1900 hrs.
+
+This supersedes AEMO Electricity Market Notice No. 115998.
+
+The capacity reserve requirement is 1710 MW
+The minimum capacity reserve available is 1558 MW
+
+Manager NEM Real Time Operations
+-------------------------------------------------------------------
+END OF REPORT
+-------------------------------------------------------------------
+";
+
+    const RECLASSIFICATION_CANCELLED: &str = "-------------------------------------------------------------------
+                       MARKET NOTICE
+-------------------------------------------------------------------
+
+From :              AEMO
+To   :              NEMITWEB1
+Creation Date :     04/03/2024     02:10:44
+
+-------------------------------------------------------------------
+
+Notice ID               :         116021
+Notice Type ID          :         RECLASSIFY CONTINGENCY
+Notice Type Description :         Reclassification of a Non-Credible Contingency Event
+Issue Date              :         04/03/2024
+External Reference      :         Cancellation of a Non-Credible Contingency Event as a Credible Contingency Event due to Lightning: 04/03/2024
+
+-------------------------------------------------------------------
+
+Reason :
+
+AEMO ELECTRICITY MARKET NOTICE
+
+Cancellation of reclassification of a Non-Credible Contingency Event as a Credible Contingency Event due to Lightning.
+
+Based on advice from AEMO's weather monitoring service, AEMO no longer considers the non-credible loss of the following lines likely.
+
+Region: VIC
+
+Lines: Moorabool - Sydenham No. 1 and No. 2 500 kV Lines
+
+Cancellation time: 0205 hrs 04/03/2024
+
+Constraint set(s) revoked: V-MLSY
+
+Refer AEMO Electricity Market Notice 116012
+
+Manager NEM Real Time Operations
+-------------------------------------------------------------------
+END OF REPORT
+-------------------------------------------------------------------
+";
+
+    #[test]
+    fn test_parse_market_notice() {
+        let notice = MarketNoticeParser::new()
+            .parse(ACTUAL_LOR1)
+            .expect("Failed to parse notice");
+
+        assert_eq!(notice.notice_id, 116005);
+        assert_eq!(notice.notice_type, "RESERVE NOTICE");
+        assert_eq!(notice.notice_type_description, "LRC/LOR1/LOR2/LOR3");
+        assert_eq!(
+            notice.issue_datetime.to_rfc3339(),
+            "2024-03-03T06:45:10+00:00"
+        );
+        assert_eq!(
+            notice.subject,
+            "Actual Lack Of Reserve Level 1 (LOR1) in the NSW Region - 03/03/2024"
+        );
+        assert!(notice.body.starts_with("AEMO ELECTRICITY MARKET NOTICE"));
+        assert!(notice.body.ends_with("Manager NEM Real Time Operations"));
+        assert_eq!(notice.referenced_notices, [115998]);
+    }
+
+    #[test]
+    fn test_parse_market_notice_without_id() {
+        let text = ACTUAL_LOR1.replace("Notice ID ", "Notice Number ");
+        match MarketNoticeParser::new().parse(&text) {
+            Err(ScraperError::InvalidMarketNotice(message)) => {
+                assert_eq!(message, "Notice ID is missing")
+            }
+            other => panic!("Expected an invalid notice, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_extract_lor_event() {
+        let notice = MarketNoticeParser::new().parse(ACTUAL_LOR1).unwrap();
+        let event = MarketEventExtractor::new()
+            .extract(&notice)
+            .expect("No event");
+
+        assert_eq!(
+            event.kind,
+            MarketEventKind::LackOfReserve {
+                level: 1,
+                forecast: false
+            }
+        );
+        assert!(!event.cancelled);
+        assert_eq!(event.region_id.as_deref(), Some("NSW1"));
+        assert_eq!(
+            event.start.map(|dt| dt.to_rfc3339()),
+            Some("2024-03-03T06:30:00+00:00".to_string())
+        );
+        assert_eq!(
+            event.end.map(|dt| dt.to_rfc3339()),
+            Some("2024-03-03T08:00:00+00:00".to_string())
+        );
+    }
+
+    #[test]
+    fn test_extract_reclassification_cancellation() {
+        let notice = MarketNoticeParser::new()
+            .parse(RECLASSIFICATION_CANCELLED)
+            .unwrap();
+        let event = MarketEventExtractor::new()
+            .extract(&notice)
+            .expect("No event");
+
+        assert_eq!(event.kind, MarketEventKind::Reclassification);
+        assert!(event.cancelled);
+        assert_eq!(event.region_id.as_deref(), Some("VIC1"));
+        assert_eq!(
+            event.start.map(|dt| dt.to_rfc3339()),
+            Some("2024-03-03T15:05:00+00:00".to_string())
+        );
+        assert_eq!(event.end, None);
+        assert_eq!(notice.referenced_notices, [116012]);
+    }
+
+    #[test]
+    fn test_extract_direction_event() {
+        let text = ACTUAL_LOR1
+            .replace("RESERVE NOTICE", "MARKET INTERVENTION")
+            .replace(
+                "Actual Lack Of Reserve Level 1 (LOR1) in the NSW Region - 03/03/2024\n\n-",
+                "Direction - SA region - 03/03/2024\n\n-",
+            );
+        let notice = MarketNoticeParser::new().parse(&text).unwrap();
+        let event = MarketEventExtractor::new().extract(&notice).unwrap();
+
+        assert_eq!(event.kind, MarketEventKind::Direction);
+        assert_eq!(event.region_id.as_deref(), Some("SA1"));
+    }
+}
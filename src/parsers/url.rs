@@ -79,6 +79,21 @@ pub enum ZipReportUrlParseError {
     UniqueKeyNotNumber(String),
 }
 
+impl fmt::Display for ZipReportUrlParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ZipReportUrlParseError::UrlPathBadFormat(msg)
+            | ZipReportUrlParseError::NotZipFileName(msg)
+            | ZipReportUrlParseError::UniqueKeyNotNumber(msg) => write!(f, "{}", msg),
+            ZipReportUrlParseError::DateTimeBadFormat => {
+                write!(f, "Report datetime is not in %Y%m%d%H%M format")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ZipReportUrlParseError {}
+
 impl From<ParseError> for ZipReportUrlParseError {
     fn from(err: ParseError) -> Self {
         match err {
@@ -0,0 +1,74 @@
+use chrono::{DateTime, FixedOffset, NaiveDateTime, TimeZone, Utc};
+use serde::Deserializer;
+
+use crate::time::datetimezone_conversion::{DateTimeVisitor, OptionalDateTimeVisitor};
+
+/// WEM timestamps are Australian Western Standard Time. Western Australia has no daylight
+/// saving, so a fixed offset is exact.
+const AWST_OFFSET_SECONDS: i32 = 8 * 3600;
+
+/// WEM CSV timestamps, e.g. `2024-03-03 08:30:00`.
+pub const WEM_DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+fn awst() -> FixedOffset {
+    FixedOffset::east_opt(AWST_OFFSET_SECONDS).unwrap()
+}
+
+/// Converts a naive WEM timestamp, as published in AWST, to UTC.
+pub fn awst_to_utc(naive: &NaiveDateTime) -> DateTime<Utc> {
+    awst()
+        .from_local_datetime(naive)
+        .unwrap()
+        .with_timezone(&Utc)
+}
+
+/// Parses a WEM timestamp. CSV files give AWST without an offset; JSON files give ISO 8601,
+/// usually with a `+08:00` offset, which is respected when present.
+pub fn parse_awst_datetime(s: &str) -> Option<DateTime<Utc>> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(s) {
+        return Some(datetime.with_timezone(&Utc));
+    }
+    NaiveDateTime::parse_from_str(s, WEM_DATE_FORMAT)
+        .or_else(|_| NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S"))
+        .ok()
+        .map(|naive| awst_to_utc(&naive))
+}
+
+pub fn deserialize_awst_datetime_to_utc<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_str(DateTimeVisitor(parse_awst_datetime))
+}
+
+pub fn deserialize_optional_awst_datetime_to_utc<'de, D>(
+    deserializer: D,
+) -> Result<Option<DateTime<Utc>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_option(OptionalDateTimeVisitor(parse_awst_datetime))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_awst_datetime() {
+        // No DST shift in January, unlike Sydney
+        assert_eq!(
+            parse_awst_datetime("2024-01-15 08:30:00").map(|dt| dt.to_rfc3339()),
+            Some("2024-01-15T00:30:00+00:00".to_string())
+        );
+        assert_eq!(
+            parse_awst_datetime("2024-01-15T08:30:00+08:00").map(|dt| dt.to_rfc3339()),
+            Some("2024-01-15T00:30:00+00:00".to_string())
+        );
+        assert_eq!(
+            parse_awst_datetime("2024-01-15T08:30:00").map(|dt| dt.to_rfc3339()),
+            Some("2024-01-15T00:30:00+00:00".to_string())
+        );
+        assert_eq!(parse_awst_datetime("2024/01/15 08:30:00"), None);
+    }
+}
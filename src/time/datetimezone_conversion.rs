@@ -1,10 +1,102 @@
 use chrono::TimeZone;
-use chrono::{DateTime, NaiveDateTime, Utc};
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Utc};
 use chrono_tz::Australia::Sydney; // automatically adjusts for DST
-use serde::de::Error;
-use serde::{self, Deserialize, Deserializer};
+use rustc_hash::FxHashMap;
+use serde::de::{Error, Visitor};
+use serde::{self, Deserializer};
+use std::cell::RefCell;
+use std::fmt;
 
-const DATE_FORMAT_FROM: &str = "%Y/%m/%d %H:%M:%S";
+/// NEM timestamps, e.g. `2024/03/03 13:35:00`. Some tables (e.g. NETWORK `LASTCHANGED`) add
+/// milliseconds, `2011/01/28 10:29:12.250`, which `%.f` accepts when present.
+pub const DATE_FORMAT_FROM: &str = "%Y/%m/%d %H:%M:%S%.f";
+
+/// Prefix of the deserializer error message, which ends with the offending value so the
+/// failure can be reported as a datetime error against its column.
+pub const INVALID_DATETIME: &str = "invalid NEM datetime: ";
+
+/// Converts a naive NEM timestamp, as published in Sydney local time, to UTC.
+pub fn sydney_to_utc(naive: &NaiveDateTime) -> Option<DateTime<Utc>> {
+    Sydney
+        .from_local_datetime(naive)
+        .single()
+        .map(|sydney_date| sydney_date.with_timezone(&Utc))
+}
+
+// Reports repeat the same few timestamps on every row of an interval, so conversions are
+// cached per thread. The cache is cleared once it grows past a few intervals' worth.
+const CONVERSION_CACHE_SIZE: usize = 1024;
+
+thread_local! {
+    static CONVERSIONS: RefCell<FxHashMap<String, DateTime<Utc>>> =
+        RefCell::new(FxHashMap::default());
+}
+
+/// Parses a NEM timestamp and converts it from Sydney local time to UTC.
+pub fn parse_sydney_datetime(s: &str) -> Option<DateTime<Utc>> {
+    CONVERSIONS.with(|conversions| {
+        let mut conversions = conversions.borrow_mut();
+        if let Some(utc) = conversions.get(s) {
+            return Some(*utc);
+        }
+        let utc = NaiveDateTime::parse_from_str(s, DATE_FORMAT_FROM)
+            .ok()
+            .and_then(|naive| sydney_to_utc(&naive))?;
+        if conversions.len() >= CONVERSION_CACHE_SIZE {
+            conversions.clear();
+        }
+        conversions.insert(s.to_string(), utc);
+        Some(utc)
+    })
+}
+
+/// Parses a datetime column with the wrapped function. Only `visit_str` is implemented, which
+/// serde also calls for borrowed and owned strings, so rows are parsed in place when the
+/// deserializer can lend out its buffer and still work when it cannot, as with
+/// `serde_json::from_reader`.
+pub(crate) struct DateTimeVisitor(pub(crate) fn(&str) -> Option<DateTime<Utc>>);
+
+impl<'de> Visitor<'de> for DateTimeVisitor {
+    type Value = DateTime<Utc>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a datetime string")
+    }
+
+    fn visit_str<E: Error>(self, s: &str) -> Result<Self::Value, E> {
+        (self.0)(s).ok_or_else(|| E::custom(format!("{}{}", INVALID_DATETIME, s)))
+    }
+}
+
+/// Like [`DateTimeVisitor`] for columns that may be null or empty.
+pub(crate) struct OptionalDateTimeVisitor(pub(crate) fn(&str) -> Option<DateTime<Utc>>);
+
+impl<'de> Visitor<'de> for OptionalDateTimeVisitor {
+    type Value = Option<DateTime<Utc>>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "an optional datetime string")
+    }
+
+    fn visit_none<E: Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_unit<E: Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_str(self)
+    }
+
+    fn visit_str<E: Error>(self, s: &str) -> Result<Self::Value, E> {
+        if s.is_empty() {
+            return Ok(None);
+        }
+        DateTimeVisitor(self.0).visit_str(s).map(Some)
+    }
+}
 
 pub fn deserialize_sydney_datetime_to_utc<'de, D>(
     deserializer: D,
@@ -12,11 +104,92 @@ pub fn deserialize_sydney_datetime_to_utc<'de, D>(
 where
     D: Deserializer<'de>,
 {
-    let s = String::deserialize(deserializer)?;
-    let naive = NaiveDateTime::parse_from_str(&s, DATE_FORMAT_FROM).map_err(D::Error::custom)?;
-    let sydney_date = Sydney
-        .from_local_datetime(&naive)
-        .single()
-        .ok_or_else(|| D::Error::custom("Invalid Sydney date/time"))?;
-    Ok(sydney_date.with_timezone(&Utc))
+    deserializer.deserialize_str(DateTimeVisitor(parse_sydney_datetime))
+}
+
+/// Like [`deserialize_sydney_datetime_to_utc`] for columns that may be empty, such as the
+/// actual start and end of an outage that has not happened yet.
+pub fn deserialize_optional_sydney_datetime_to_utc<'de, D>(
+    deserializer: D,
+) -> Result<Option<DateTime<Utc>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_option(OptionalDateTimeVisitor(parse_sydney_datetime))
+}
+
+/// Converts a predispatch run ID, `YYYYMMDDPP`, to the time the run was made. `PP` counts
+/// half hours from the 04:00 start of the trading day, so `2024030331` ran at 19:30.
+pub fn parse_predispatch_seq_no(s: &str) -> Option<DateTime<Utc>> {
+    if s.len() != 10 {
+        return None;
+    }
+    let trading_day = NaiveDate::parse_from_str(s.get(..8)?, "%Y%m%d").ok()?;
+    let periods: i64 = s.get(8..)?.parse().ok()?;
+    let run = trading_day.and_hms_opt(4, 0, 0)? + Duration::try_minutes(30 * periods)?;
+    sydney_to_utc(&run)
+}
+
+pub fn deserialize_predispatch_seq_no<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_str(DateTimeVisitor(parse_predispatch_seq_no))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sydney_datetime_with_and_without_milliseconds() {
+        assert_eq!(
+            parse_sydney_datetime("2024/03/03 13:35:00").map(|dt| dt.to_rfc3339()),
+            Some("2024-03-03T02:35:00+00:00".to_string())
+        );
+        assert_eq!(
+            parse_sydney_datetime("2011/01/28 10:29:12.250").map(|dt| dt.to_rfc3339()),
+            Some("2011-01-27T23:29:12.250+00:00".to_string())
+        );
+        assert_eq!(parse_sydney_datetime("2024/03/03"), None);
+    }
+
+    #[test]
+    fn test_parse_predispatch_seq_no() {
+        assert_eq!(
+            parse_predispatch_seq_no("2024030331").map(|dt| dt.to_rfc3339()),
+            Some("2024-03-03T08:30:00+00:00".to_string())
+        );
+        // Runs after midnight belong to the previous trading day
+        assert_eq!(
+            parse_predispatch_seq_no("2024030241").map(|dt| dt.to_rfc3339()),
+            Some("2024-03-02T13:30:00+00:00".to_string())
+        );
+        assert_eq!(parse_predispatch_seq_no("20240303"), None);
+    }
+
+    #[derive(serde::Deserialize)]
+    struct Interval {
+        #[serde(deserialize_with = "deserialize_sydney_datetime_to_utc")]
+        start: DateTime<Utc>,
+        #[serde(deserialize_with = "deserialize_optional_sydney_datetime_to_utc")]
+        end: Option<DateTime<Utc>>,
+    }
+
+    #[test]
+    fn test_deserializers_accept_owned_strings() {
+        // A reader cannot lend out a `&str`, unlike a borrowed CSV row
+        let json = r#"{"start": "2024/03/03 13:35:00", "end": null}"#;
+        let interval: Interval = serde_json::from_reader(json.as_bytes()).unwrap();
+
+        assert_eq!(interval.start.to_rfc3339(), "2024-03-03T02:35:00+00:00");
+        assert_eq!(interval.end, None);
+
+        let json = r#"{"start": "2024/03/03 13:35:00", "end": "2024/03/03 13:40:00"}"#;
+        let interval: Interval = serde_json::from_reader(json.as_bytes()).unwrap();
+        assert_eq!(
+            interval.end.map(|end| end.to_rfc3339()),
+            Some("2024-03-03T02:40:00+00:00".to_string())
+        );
+    }
 }
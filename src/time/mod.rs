@@ -1,2 +1,3 @@
+pub mod awst;
 pub mod datetimezone_conversion;
 pub mod time_ranges;
@@ -1,4 +1,4 @@
-use chrono::{Duration, NaiveDate, NaiveDateTime, Timelike};
+use chrono::{Duration, NaiveDateTime, Timelike};
 use std::convert::TryFrom;
 
 use std::fmt;
@@ -104,6 +104,7 @@ impl TimestampGenerator {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::NaiveDate;
 
     #[test]
     fn test_timestamp_strftime_range_try_from_valid() {